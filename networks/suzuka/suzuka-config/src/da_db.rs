@@ -5,12 +5,24 @@ use serde::{Deserialize, Serialize};
 pub struct Config {
 	#[serde(default = "default_da_db_path")]
 	pub da_db_path: String,
+	/// The directory of a snapshot to bootstrap a new node from, instead of the start of the DA.
+	#[serde(default = "default_snapshot_path")]
+	pub snapshot_path: Option<String>,
+	/// The hash of the snapshot, as published by the operator of the node which exported it.
+	#[serde(default = "default_snapshot_hash")]
+	pub snapshot_hash: Option<String>,
 }
 
 impl Default for Config {
 	fn default() -> Self {
-		Self { da_db_path: default_da_db_path() }
+		Self {
+			da_db_path: default_da_db_path(),
+			snapshot_path: default_snapshot_path(),
+			snapshot_hash: default_snapshot_hash(),
+		}
 	}
 }
 
 env_default!(default_da_db_path, "SUZUKA_DA_DB_PATH", String, "suzuka-da-db".to_string());
+env_default!(default_snapshot_path, "SUZUKA_SNAPSHOT_PATH", String);
+env_default!(default_snapshot_hash, "SUZUKA_SNAPSHOT_HASH", String);
@@ -0,0 +1,36 @@
+use maptos_dof_execution::v1::Executor;
+use suzuka_full_node::{da_db::DaDB, partial::SuzukaPartialNode, snapshot};
+
+use godfig::{backend::config_file::ConfigFile, Godfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use suzuka_config::Config;
+
+/// Exports a snapshot of a stopped node into the directory given as the first argument, and
+/// prints its hash, which nodes importing it are configured with as `SUZUKA_SNAPSHOT_HASH`.
+#[tokio::main]
+async fn main() -> Result<ExitCode, anyhow::Error> {
+	let _guard = movement_tracing::init_tracing_subscriber(movement_tracing::Config::default());
+
+	let path: PathBuf = std::env::args()
+		.nth(1)
+		.ok_or(anyhow::anyhow!("Usage: suzuka-full-node-export-snapshot <directory>"))?
+		.into();
+
+	// get the config file
+	let dot_movement = dot_movement::DotMovement::try_from_env()?;
+	let config_file = dot_movement.try_get_or_create_config_file().await?;
+	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
+	let config = godfig.try_wait_for_ready().await?;
+
+	let (tx, _) = async_channel::unbounded();
+	let executor = Executor::try_from_config(tx, config.execution_config.maptos_config.clone())?;
+	let da_db = DaDB::new(SuzukaPartialNode::<Executor>::create_or_get_da_db(&config).await?);
+
+	// nodes import the snapshot with its hash, which is published along with it
+	let position = snapshot::export_snapshot(&executor, &da_db, &path).await?;
+	let exported = serde_json::json!({ "hash": position.hash()?.to_hex(), "position": position });
+	println!("{}", serde_json::to_string_pretty(&exported)?);
+
+	Ok(ExitCode::SUCCESS)
+}
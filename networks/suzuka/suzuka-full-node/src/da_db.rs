@@ -0,0 +1,319 @@
+use movement_types::Id;
+use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A block executed from the DA, by its height on the chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutedBlock {
+	pub id: String,
+	pub da_height: u64,
+	/// The slot and proposer of the block, if it was proposed by a rotating sequencer.
+	#[serde(default)]
+	pub proposal: Option<(u64, Id)>,
+}
+
+/// The node's record of the blocks it read from the DA.
+#[derive(Debug, Clone)]
+pub struct DaDB {
+	db: Arc<DB>,
+}
+
+impl DaDB {
+	pub fn new(db: DB) -> Self {
+		Self { db: Arc::new(db) }
+	}
+
+	pub fn open(path: &str) -> Result<DB, anyhow::Error> {
+		let mut options = Options::default();
+		options.create_if_missing(true);
+		options.create_missing_column_families(true);
+
+		let synced_height = ColumnFamilyDescriptor::new("synced_height", Options::default());
+		let executed_blocks = ColumnFamilyDescriptor::new("executed_blocks", Options::default());
+		let executed_heights = ColumnFamilyDescriptor::new("executed_heights", Options::default());
+		let rejected_blocks = ColumnFamilyDescriptor::new("rejected_blocks", Options::default());
+
+		let db = DB::open_cf_descriptors(
+			&options,
+			path,
+			vec![synced_height, executed_blocks, executed_heights, rejected_blocks],
+		)
+		.map_err(|e| anyhow::anyhow!("Failed to open DA DB: {:?}", e))?;
+
+		Ok(db)
+	}
+
+	pub async fn set_synced_height(&self, height: u64) -> Result<(), anyhow::Error> {
+		// This is heavy for this purpose, but progressively the contents of the DA DB will be used for more things
+		let da_db = self.db.clone();
+		tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("synced_height")
+				.ok_or(anyhow::anyhow!("No synced_height column family"))?;
+			let height = serde_json::to_string(&height)
+				.map_err(|e| anyhow::anyhow!("Failed to serialize synced height: {:?}", e))?;
+			da_db
+				.put_cf(&cf, "synced_height", height)
+				.map_err(|e| anyhow::anyhow!("Failed to set synced height: {:?}", e))
+		})
+		.await??;
+		Ok(())
+	}
+
+	pub async fn get_synced_height(&self) -> Result<u64, anyhow::Error> {
+		// This is heavy for this purpose, but progressively the contents of the DA DB will be used for more things
+		let da_db = self.db.clone();
+		let height = tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("synced_height")
+				.ok_or(anyhow::anyhow!("No synced_height column family"))?;
+			let height = da_db
+				.get_cf(&cf, "synced_height")
+				.map_err(|e| anyhow::anyhow!("Failed to get synced height: {:?}", e))?;
+			let height = match height {
+				Some(height) => serde_json::from_slice(&height)
+					.map_err(|e| anyhow::anyhow!("Failed to deserialize synced height: {:?}", e))?,
+				None => 0,
+			};
+			Ok::<u64, anyhow::Error>(height)
+		})
+		.await??;
+		Ok(height)
+	}
+
+	pub async fn add_executed_block(&self, id: String) -> Result<(), anyhow::Error> {
+		let da_db = self.db.clone();
+		tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("executed_blocks")
+				.ok_or(anyhow::anyhow!("No executed_blocks column family"))?;
+			da_db
+				.put_cf(&cf, id.clone(), id)
+				.map_err(|e| anyhow::anyhow!("Failed to add executed block: {:?}", e))
+		})
+		.await??;
+		Ok(())
+	}
+
+	pub async fn has_executed_block(&self, id: String) -> Result<bool, anyhow::Error> {
+		let da_db = self.db.clone();
+		let id = tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("executed_blocks")
+				.ok_or(anyhow::anyhow!("No executed_blocks column family"))?;
+			da_db
+				.get_cf(&cf, id)
+				.map_err(|e| anyhow::anyhow!("Failed to get executed block: {:?}", e))
+		})
+		.await??;
+		Ok(id.is_some())
+	}
+
+	/// Records the block executed at a height on the chain, so that it can be rejected later.
+	pub async fn add_executed_height(
+		&self,
+		height: u64,
+		block: ExecutedBlock,
+	) -> Result<(), anyhow::Error> {
+		let da_db = self.db.clone();
+		tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("executed_heights")
+				.ok_or(anyhow::anyhow!("No executed_heights column family"))?;
+			let block = serde_json::to_vec(&block)
+				.map_err(|e| anyhow::anyhow!("Failed to serialize executed block: {:?}", e))?;
+			da_db
+				.put_cf(&cf, height.to_be_bytes(), block)
+				.map_err(|e| anyhow::anyhow!("Failed to add executed height: {:?}", e))
+		})
+		.await??;
+		Ok(())
+	}
+
+	/// Gets the block executed at a height on the chain, if any.
+	pub async fn get_executed_block(
+		&self,
+		height: u64,
+	) -> Result<Option<ExecutedBlock>, anyhow::Error> {
+		let da_db = self.db.clone();
+		let block = tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("executed_heights")
+				.ok_or(anyhow::anyhow!("No executed_heights column family"))?;
+			da_db
+				.get_cf(&cf, height.to_be_bytes())
+				.map_err(|e| anyhow::anyhow!("Failed to get executed block: {:?}", e))
+		})
+		.await??;
+		block
+			.map(|block| {
+				serde_json::from_slice(&block)
+					.map_err(|e| anyhow::anyhow!("Failed to deserialize executed block: {:?}", e))
+			})
+			.transpose()
+	}
+
+	/// Gets the executed blocks read at DA heights above a height, by their heights on the chain.
+	pub async fn get_executed_blocks_above_da_height(
+		&self,
+		da_height: u64,
+	) -> Result<Vec<(u64, ExecutedBlock)>, anyhow::Error> {
+		let da_db = self.db.clone();
+		tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("executed_heights")
+				.ok_or(anyhow::anyhow!("No executed_heights column family"))?;
+
+			// blocks are read in order of DA height, so the latest ones are at the end
+			let mut executed = Vec::new();
+			for item in da_db.iterator_cf(&cf, rocksdb::IteratorMode::End) {
+				let (key, value) =
+					item.map_err(|e| anyhow::anyhow!("Failed to read executed heights: {:?}", e))?;
+				let block: ExecutedBlock = serde_json::from_slice(&value).map_err(|e| {
+					anyhow::anyhow!("Failed to deserialize executed block: {:?}", e)
+				})?;
+				if block.da_height <= da_height {
+					break;
+				}
+				let block_height = u64::from_be_bytes(
+					key.as_ref()
+						.try_into()
+						.map_err(|e| anyhow::anyhow!("Invalid executed height: {:?}", e))?,
+				);
+				executed.push((block_height, block));
+			}
+			executed.reverse();
+			Ok(executed)
+		})
+		.await?
+	}
+
+	/// Marks the executed blocks above a height as rejected, so that they are read and executed
+	/// again, and moves the synced height back to the lowest DA height they were read at.
+	///
+	/// Returns the rejected blocks by their heights on the chain.
+	pub async fn reject_blocks_above(
+		&self,
+		height: u64,
+	) -> Result<Vec<(u64, ExecutedBlock)>, anyhow::Error> {
+		let da_db = self.db.clone();
+		let rejected = tokio::task::spawn_blocking(move || {
+			let executed_heights = da_db
+				.cf_handle("executed_heights")
+				.ok_or(anyhow::anyhow!("No executed_heights column family"))?;
+			let executed_blocks = da_db
+				.cf_handle("executed_blocks")
+				.ok_or(anyhow::anyhow!("No executed_blocks column family"))?;
+			let rejected_blocks = da_db
+				.cf_handle("rejected_blocks")
+				.ok_or(anyhow::anyhow!("No rejected_blocks column family"))?;
+
+			// heights are keyed big endian, so they iterate in order from the first one above
+			let start = (height + 1).to_be_bytes();
+			let mut rejected = Vec::new();
+			for item in da_db.iterator_cf(
+				&executed_heights,
+				rocksdb::IteratorMode::From(&start, rocksdb::Direction::Forward),
+			) {
+				let (key, value) =
+					item.map_err(|e| anyhow::anyhow!("Failed to read executed heights: {:?}", e))?;
+				let block_height = u64::from_be_bytes(
+					key.as_ref()
+						.try_into()
+						.map_err(|e| anyhow::anyhow!("Invalid executed height: {:?}", e))?,
+				);
+				let block: ExecutedBlock = serde_json::from_slice(&value).map_err(|e| {
+					anyhow::anyhow!("Failed to deserialize executed block: {:?}", e)
+				})?;
+				rejected.push((block_height, block));
+			}
+
+			let mut batch = rocksdb::WriteBatch::default();
+			for (block_height, block) in &rejected {
+				batch.delete_cf(&executed_heights, block_height.to_be_bytes());
+				batch.delete_cf(&executed_blocks, block.id.clone());
+				batch.put_cf(&rejected_blocks, block_height.to_be_bytes(), block.id.clone());
+			}
+			da_db
+				.write(batch)
+				.map_err(|e| anyhow::anyhow!("Failed to reject blocks: {:?}", e))?;
+			Ok::<_, anyhow::Error>(rejected)
+		})
+		.await??;
+
+		// the synced height is one below the DA height of the last executed block
+		if let Some(da_height) = rejected.iter().map(|(_, block)| block.da_height).min() {
+			let synced_height = self.get_synced_height().await?;
+			self.set_synced_height(synced_height.min(da_height.saturating_sub(1))).await?;
+		}
+
+		Ok(rejected)
+	}
+
+	/// Gets the id of the block rejected at a height on the chain, if any.
+	pub async fn get_rejected_block(&self, height: u64) -> Result<Option<String>, anyhow::Error> {
+		let da_db = self.db.clone();
+		let id = tokio::task::spawn_blocking(move || {
+			let cf = da_db
+				.cf_handle("rejected_blocks")
+				.ok_or(anyhow::anyhow!("No rejected_blocks column family"))?;
+			da_db
+				.get_cf(&cf, height.to_be_bytes())
+				.map_err(|e| anyhow::anyhow!("Failed to get rejected block: {:?}", e))
+		})
+		.await??;
+		id.map(|id| String::from_utf8(id).map_err(|e| anyhow::anyhow!("Invalid block id: {:?}", e)))
+			.transpose()
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	fn executed_block(id: &str, da_height: u64) -> ExecutedBlock {
+		ExecutedBlock { id: id.to_string(), da_height, proposal: None }
+	}
+
+	#[tokio::test]
+	async fn test_reject_blocks_above() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().to_str().ok_or(anyhow::anyhow!("Invalid path"))?;
+		let da_db = DaDB::new(DaDB::open(path)?);
+
+		for (height, id, da_height) in [(2, "a", 3), (3, "b", 5), (4, "c", 5)] {
+			da_db.add_executed_block(id.to_string()).await?;
+			da_db.add_executed_height(height, executed_block(id, da_height)).await?;
+		}
+		da_db.set_synced_height(4).await?;
+
+		assert_eq!(da_db.get_executed_block(3).await?, Some(executed_block("b", 5)));
+		let rejected = da_db.reject_blocks_above(2).await?;
+		assert_eq!(rejected, vec![(3, executed_block("b", 5)), (4, executed_block("c", 5))]);
+		assert_eq!(da_db.get_executed_block(3).await?, None);
+
+		// the rejected blocks are read from the DA again
+		assert_eq!(da_db.get_synced_height().await?, 4);
+		assert!(da_db.has_executed_block("a".to_string()).await?);
+		assert!(!da_db.has_executed_block("b".to_string()).await?);
+		assert!(!da_db.has_executed_block("c".to_string()).await?);
+		assert_eq!(da_db.get_rejected_block(3).await?, Some("b".to_string()));
+		assert_eq!(da_db.get_rejected_block(2).await?, None);
+
+		// the blocks read at the DA height above the synced height are kept
+		assert_eq!(da_db.get_executed_blocks_above_da_height(4).await?, vec![]);
+		assert_eq!(
+			da_db.get_executed_blocks_above_da_height(2).await?,
+			vec![(2, executed_block("a", 3))]
+		);
+
+		// rejecting below the synced height moves it back
+		let rejected = da_db.reject_blocks_above(1).await?;
+		assert_eq!(rejected, vec![(2, executed_block("a", 3))]);
+		assert_eq!(da_db.get_synced_height().await?, 2);
+
+		Ok(())
+	}
+}
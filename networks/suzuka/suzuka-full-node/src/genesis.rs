@@ -0,0 +1,140 @@
+use m1_da_light_node_client::Transaction as LightNodeTransaction;
+use maptos_dof_execution::{HashValue, SignedTransaction};
+use movement_types::{Block, Transaction};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Prefixes the genesis payload among the transactions of a block, which are otherwise signed
+/// transactions.
+const GENESIS_PAYLOAD_TAG: &[u8] = b"suzuka::genesis";
+
+/// The genesis carried by the first DA block, against which every node checks the genesis it
+/// booted from before rolling it over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisPayload {
+	/// The id of the genesis block, which commits to the genesis.
+	pub genesis_block_id: HashValue,
+	/// The transactions of the genesis block, signed by the root account.
+	pub transactions: Vec<SignedTransaction>,
+}
+
+impl GenesisPayload {
+	pub fn new(genesis_block_id: HashValue, transactions: Vec<SignedTransaction>) -> Self {
+		Self { genesis_block_id, transactions }
+	}
+
+	/// Encodes the payload as a transaction to submit to the DA.
+	pub fn try_to_light_node_transaction(&self) -> Result<LightNodeTransaction, anyhow::Error> {
+		let mut data = GENESIS_PAYLOAD_TAG.to_vec();
+		data.extend(bcs::to_bytes(self)?);
+		Ok(LightNodeTransaction { data, sequence_number: 0 })
+	}
+
+	/// Decodes the payload from the data of a transaction, if it carries one.
+	pub fn try_from_data(data: &[u8]) -> Result<Option<Self>, anyhow::Error> {
+		match data.strip_prefix(GENESIS_PAYLOAD_TAG) {
+			Some(payload) => Ok(Some(bcs::from_bytes(payload)?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Takes the genesis payloads out of a block, and gets the given genesis if they carry it.
+	///
+	/// The transactions of the payloads of the genesis are gathered in order, so that the genesis
+	/// does not depend on which node submitted first. Payloads of other geneses, and payloads
+	/// which do not decode, are dropped, since anyone may post to the DA.
+	pub fn take_from_block(block: &mut Block, genesis_block_id: HashValue) -> Option<Self> {
+		let mut genesis: Option<Self> = None;
+		let mut transactions = Vec::with_capacity(block.transactions.len());
+		for transaction in block.transactions.drain(..) {
+			let payload = match Self::try_from_data(&transaction.data) {
+				Ok(Some(payload)) => payload,
+				Ok(None) => {
+					transactions.push(transaction);
+					continue;
+				}
+				Err(e) => {
+					warn!("Dropping a genesis payload which does not decode: {:?}", e);
+					continue;
+				}
+			};
+			if payload.genesis_block_id != genesis_block_id {
+				warn!(
+					"Dropping the payload of genesis {}, which is not the genesis {}",
+					payload.genesis_block_id, genesis_block_id
+				);
+				continue;
+			}
+			match &mut genesis {
+				None => genesis = Some(payload),
+				Some(genesis) => genesis.transactions.extend(payload.transactions),
+			}
+		}
+		block.transactions = transactions;
+		genesis
+	}
+
+	/// Gets the transactions of the genesis block as transactions of a DA block.
+	pub fn try_into_block_transactions(self) -> Result<Vec<Transaction>, anyhow::Error> {
+		let mut transactions = Vec::with_capacity(self.transactions.len());
+		for transaction in self.transactions {
+			transactions.push(Transaction::new(
+				bcs::to_bytes(&transaction)?,
+				transaction.sequence_number(),
+			));
+		}
+		Ok(transactions)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use movement_types::{BlockMetadata, Id};
+
+	#[test]
+	fn test_take_genesis_from_block() -> Result<(), anyhow::Error> {
+		let genesis_block_id = HashValue::random();
+		let payload = |genesis_block_id| -> Result<Transaction, anyhow::Error> {
+			let transaction = GenesisPayload::new(genesis_block_id, Vec::new())
+				.try_to_light_node_transaction()?;
+			Ok(Transaction::new(transaction.data, transaction.sequence_number))
+		};
+		let mut block = Block::new(
+			BlockMetadata::default(),
+			Id::genesis_block().to_vec(),
+			vec![
+				Transaction::test(),
+				payload(genesis_block_id)?,
+				payload(HashValue::random())?,
+				payload(genesis_block_id)?,
+			],
+		);
+
+		// the payload of another genesis posted first does not take the place of the genesis
+		let mut other_first = block.clone();
+		other_first.transactions.swap(1, 2);
+		let genesis =
+			GenesisPayload::take_from_block(&mut other_first, genesis_block_id).expect("genesis");
+		assert_eq!(genesis.genesis_block_id, genesis_block_id);
+
+		let genesis =
+			GenesisPayload::take_from_block(&mut block, genesis_block_id).expect("genesis");
+		assert_eq!(genesis.genesis_block_id, genesis_block_id);
+		// only the other transactions are left in the block
+		assert_eq!(block.transactions, vec![Transaction::test()]);
+		assert!(GenesisPayload::take_from_block(&mut block, genesis_block_id).is_none());
+
+		// a block which only carries other geneses does not carry the genesis
+		let mut other = Block::new(
+			BlockMetadata::default(),
+			Id::genesis_block().to_vec(),
+			vec![payload(HashValue::random())?, Transaction::new(GENESIS_PAYLOAD_TAG.to_vec(), 0)],
+		);
+		assert!(GenesisPayload::take_from_block(&mut other, genesis_block_id).is_none());
+		assert!(other.transactions.is_empty());
+
+		Ok(())
+	}
+}
@@ -1,5 +1,8 @@
+pub mod da_db;
+pub mod genesis;
 pub mod manager;
 pub mod partial;
+pub mod snapshot;
 
 #[cfg(test)]
 pub mod tests;
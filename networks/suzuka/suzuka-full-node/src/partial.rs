@@ -1,47 +1,105 @@
+use crate::da_db::{DaDB, ExecutedBlock};
+use crate::genesis::GenesisPayload;
+use crate::snapshot;
 use crate::SuzukaFullNode;
 use m1_da_light_node_client::{
-	blob_response, BatchWriteRequest, BlobWrite, LightNodeServiceClient,
-	StreamReadFromHeightRequest,
+	blob_response,
+	connect::{connect, AuthenticatedClient},
+	StreamReadFromHeightRequest, StreamReadFromHeightResponse, SubmitTransactionsRequest,
+	Transaction as LightNodeTransaction,
 };
 use maptos_dof_execution::{
-	v1::Executor, DynOptFinExecutor, ExecutableBlock, ExecutableTransactions, HashValue,
-	SignatureVerifiedTransaction, SignedTransaction, Transaction,
+	v1::Executor, AccountAddress, DynOptFinExecutor, ExecutableBlock, ExecutableTransactions,
+	HashValue, SignatureVerifiedTransaction, SignedTransaction, Transaction,
 };
 use mcr_settlement_client::{McrSettlementClient, McrSettlementClientOperations};
 use mcr_settlement_manager::CommitmentEventStream;
 use mcr_settlement_manager::{McrSettlementManager, McrSettlementManagerOperations};
+use movement_algs::batching::{BatchLimits, FairQueue};
 use movement_rest::MovementRest;
-use movement_types::{Block, BlockCommitment, BlockCommitmentEvent};
+use movement_types::{Block, BlockCommitment, BlockCommitmentEvent, Id};
+use sequencing_util::rotation::{ProposerSchedule, SequencerSet};
 
 use anyhow::Context;
 use async_channel::{Receiver, Sender};
 use core::sync::atomic::AtomicU64;
-use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use rocksdb::DB;
+use std::collections::{HashSet, VecDeque};
 use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio_stream::StreamExt;
 use tracing::{debug, error, info, info_span, warn, Instrument};
 pub struct SuzukaPartialNode<T> {
 	executor: T,
 	transaction_sender: Sender<SignedTransaction>,
 	pub transaction_receiver: Receiver<SignedTransaction>,
-	light_node_client: LightNodeServiceClient<tonic::transport::Channel>,
+	// batches the received transactions, taking turns between their senders
+	transaction_queue: FairQueue<AccountAddress, SignedTransaction>,
+	light_node_client: AuthenticatedClient,
 	settlement_manager: McrSettlementManager,
 	movement_rest: MovementRest,
 	pub config: suzuka_config::Config,
-	da_db: Arc<DB>,
+	da_db: DaDB,
+	// set when blocks are proposed by a rotating sequencer set
+	proposer_schedule: Option<Arc<RwLock<ProposerSchedule>>>,
+	// the block heights to revert to when settlement rejects a commitment
+	reversions: Mutex<mpsc::UnboundedReceiver<u64>>,
+	// the transactions this node wrote to the DA, which it writes again if a block leaves them out
+	written_transactions: Mutex<WrittenTransactions>,
 }
 
 const LOGGING_UID: AtomicU64 = AtomicU64::new(0);
 
+/// How many transactions may wait to be batched before the received transactions wait for them.
+const TRANSACTION_QUEUE_CAPACITY: usize = 65_536;
+
+/// How often a node which has not executed the genesis yet checks whether it has.
+const GENESIS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many of the transactions it wrote to the DA a node remembers.
+const WRITTEN_TRANSACTIONS_CAPACITY: usize = 4 * TRANSACTION_QUEUE_CAPACITY;
+
+/// The hashes of the transactions a node wrote to the DA, of which the oldest are forgotten
+/// beyond a capacity.
+struct WrittenTransactions {
+	capacity: usize,
+	order: VecDeque<HashValue>,
+	hashes: HashSet<HashValue>,
+}
+
+impl WrittenTransactions {
+	fn new(capacity: usize) -> Self {
+		Self { capacity, order: VecDeque::new(), hashes: HashSet::new() }
+	}
+
+	fn insert(&mut self, hash: HashValue) {
+		if !self.hashes.insert(hash) {
+			return;
+		}
+		self.order.push_back(hash);
+		while self.order.len() > self.capacity {
+			if let Some(oldest) = self.order.pop_front() {
+				self.hashes.remove(&oldest);
+			}
+		}
+	}
+
+	/// Forgets a transaction, and gets whether it was written.
+	fn remove(&mut self, hash: &HashValue) -> bool {
+		self.hashes.remove(hash)
+	}
+}
+
 impl<T> SuzukaPartialNode<T>
 where
 	T: DynOptFinExecutor + Clone + Send + Sync,
 {
 	pub fn new<C>(
 		executor: T,
-		light_node_client: LightNodeServiceClient<tonic::transport::Channel>,
+		light_node_client: AuthenticatedClient,
 		settlement_client: C,
 		movement_rest: MovementRest,
 		config: &suzuka_config::Config,
@@ -53,19 +111,26 @@ where
 		let (settlement_manager, commitment_events) =
 			McrSettlementManager::new(settlement_client, &config.mcr);
 		let (transaction_sender, transaction_receiver) = async_channel::unbounded();
+		let (reversion_sender, reversions) = mpsc::unbounded_channel();
 		let bg_executor = executor.clone();
 		(
 			Self {
 				executor,
 				transaction_sender,
 				transaction_receiver,
+				transaction_queue: FairQueue::new(TRANSACTION_QUEUE_CAPACITY),
 				light_node_client,
 				settlement_manager,
 				movement_rest,
 				config: config.clone(),
-				da_db: Arc::new(da_db),
+				da_db: DaDB::new(da_db),
+				proposer_schedule: None,
+				reversions: Mutex::new(reversions),
+				written_transactions: Mutex::new(WrittenTransactions::new(
+					WRITTEN_TRANSACTIONS_CAPACITY,
+				)),
 			},
-			read_commitment_events(commitment_events, bg_executor),
+			read_commitment_events(commitment_events, bg_executor, reversion_sender),
 		)
 	}
 
@@ -75,7 +140,7 @@ where
 
 	pub fn bound<C>(
 		executor: T,
-		light_node_client: LightNodeServiceClient<tonic::transport::Channel>,
+		light_node_client: AuthenticatedClient,
 		settlement_client: C,
 		movement_rest: MovementRest,
 		config: &suzuka_config::Config,
@@ -87,64 +152,66 @@ where
 		let (mut node, background_task) =
 			Self::new(executor, light_node_client, settlement_client, movement_rest, config, da_db);
 		node.bind_transaction_channel();
+		node.proposer_schedule = Self::try_proposer_schedule(config)?;
 		Ok((node, background_task))
 	}
 
+	/// Builds the schedule of block proposers if the sequencers take turns proposing.
+	fn try_proposer_schedule(
+		config: &suzuka_config::Config,
+	) -> Result<Option<Arc<RwLock<ProposerSchedule>>>, anyhow::Error> {
+		let memseq_config = config.m1_da_light_node.m1_da_light_node_config.memseq_config();
+		if !memseq_config.is_rotating() {
+			return Ok(None);
+		}
+
+		let set = SequencerSet::try_from_hex(
+			&memseq_config.memseq_sequencer_set,
+			memseq_config.memseq_slot_duration_ms,
+			memseq_config.memseq_takeover_timeout_ms,
+		)
+		.context("Failed to create the sequencer set")?;
+		Ok(Some(Arc::new(RwLock::new(ProposerSchedule::new(set)))))
+	}
+
+	/// Queues the transactions received from the executor under their senders.
+	async fn queue_transactions(&self) -> Result<(), anyhow::Error> {
+		loop {
+			let transaction = self.transaction_receiver.recv().await?;
+			self.transaction_queue.push(transaction.sender(), transaction).await?;
+		}
+	}
+
 	async fn next_transaction_batch_write(&self) -> Result<(), anyhow::Error> {
 		// limit the total time batching transactions
-		let start = std::time::Instant::now();
 		let (_, half_building_time) = self
 			.config
 			.m1_da_light_node
 			.m1_da_light_node_config
 			.try_block_building_parameters()?;
-
-		let mut transactions = Vec::new();
+		let limits = BatchLimits::new(Duration::from_millis(half_building_time));
+		let batch = self.transaction_queue.next_batch(&limits).await?;
 
 		let batch_id = LOGGING_UID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-		loop {
-			let remaining = match half_building_time.checked_sub(start.elapsed().as_millis() as u64)
-			{
-				Some(remaining) => remaining,
-				None => {
-					// we have exceeded the half building time
-					break;
-				}
-			};
-
-			match tokio::time::timeout(
-				Duration::from_millis(remaining),
-				self.transaction_receiver.recv(),
-			)
-			.await
-			{
-				Ok(transaction) => match transaction {
-					Ok(transaction) => {
-						info!(
-							target : "movement_timing",
-							batch_id = %batch_id,
-							tx_hash = %transaction.committed_hash(),
-							sender = %transaction.sender(),
-							sequence_number = transaction.sequence_number(),
-							"received transaction",
-						);
-						let serialized_aptos_transaction = serde_json::to_vec(&transaction)?;
-						let movement_transaction = movement_types::Transaction::new(
-							serialized_aptos_transaction,
-							transaction.sequence_number(),
-						);
-						let serialized_transaction = serde_json::to_vec(&movement_transaction)?;
-						transactions.push(BlobWrite { data: serialized_transaction });
-					}
-					Err(_) => {
-						break;
-					}
-				},
-				Err(_) => {
-					break;
-				}
-			}
+		let mut transactions = Vec::with_capacity(batch.len());
+		let mut written_transactions = self.written_transactions.lock().await;
+		for transaction in batch {
+			info!(
+				target : "movement_timing",
+				batch_id = %batch_id,
+				tx_hash = %transaction.committed_hash(),
+				sender = %transaction.sender(),
+				sequence_number = transaction.sequence_number(),
+				"received transaction",
+			);
+			written_transactions.insert(transaction.committed_hash());
+			let serialized_aptos_transaction = bcs::to_bytes(&transaction)?;
+			transactions.push(LightNodeTransaction {
+				data: serialized_aptos_transaction,
+				sequence_number: transaction.sequence_number(),
+			});
 		}
+		drop(written_transactions);
 
 		if transactions.len() > 0 {
 			info!(
@@ -153,10 +220,10 @@ where
 				transaction_count = transactions.len(),
 				"built_batch_write"
 			);
-			let batch_write = BatchWriteRequest { blobs: transactions };
+			let submit_transactions = SubmitTransactionsRequest { transactions };
 			let mut light_node_client = self.light_node_client.clone();
 			tokio::task::spawn(async move {
-				light_node_client.batch_write(batch_write).await?;
+				light_node_client.submit_transactions(submit_transactions).await?;
 				Ok::<(), anyhow::Error>(())
 			});
 		}
@@ -164,91 +231,271 @@ where
 		Ok(())
 	}
 
-	async fn write_transactions_to_da(&self) -> Result<(), anyhow::Error> {
+	async fn write_transaction_batches(&self) -> Result<(), anyhow::Error> {
+		// the first DA block is left to the genesis
+		while self.executor.get_block_head_height().await? == 0 {
+			tokio::time::sleep(GENESIS_POLL_INTERVAL).await;
+		}
 		loop {
 			self.next_transaction_batch_write().await?;
 		}
 	}
 
+	async fn write_transactions_to_da(&self) -> Result<(), anyhow::Error> {
+		tokio::try_join!(self.queue_transactions(), self.write_transaction_batches())?;
+
+		Ok(())
+	}
+
 	// receive transactions from the transaction channel and send them to be executed
 	// ! This assumes the m1 da light node is running sequencer mode
 	pub async fn read_blocks_from_da(&self) -> Result<(), anyhow::Error> {
-		let mut stream = {
-			let mut light_node_client = self.light_node_client.clone();
-			light_node_client
-				.stream_read_from_height(StreamReadFromHeightRequest {
-					height: self.get_synced_height().await?,
-				})
-				.await?
+		let mut reversions = self.reversions.lock().await;
+		loop {
+			let mut stream = {
+				let mut light_node_client = self.light_node_client.clone();
+				light_node_client
+					.stream_read_from_height(StreamReadFromHeightRequest {
+						height: self.get_synced_height().await?,
+						namespace: String::new(),
+					})
+					.await?
+			}
+			.into_inner();
+
+			// read until a reversion, after which the stream starts again from the synced height
+			loop {
+				tokio::select! {
+					block_height = next_reversion(&mut reversions) => {
+						self.revert_block_head_to(block_height).await?;
+						break;
+					}
+					blob = stream.next() => match blob {
+						Some(blob) => self.read_block_from_da(blob?).await?,
+						None => return Ok(()),
+					},
+				}
+			}
 		}
-		.into_inner();
-
-		while let Some(blob) = stream.next().await {
-			debug!("Got blob: {:?}", blob);
-
-			// get the block
-			let (block_bytes, block_timestamp, block_id, da_height) = match blob?
-				.blob
-				.ok_or(anyhow::anyhow!("No blob in response"))?
-				.blob_type
-				.ok_or(anyhow::anyhow!("No blob type in response"))?
-			{
-				blob_response::BlobType::SequencedBlobBlock(blob) => {
-					(blob.data, blob.timestamp, blob.blob_id, blob.height)
+	}
+
+	async fn read_block_from_da(
+		&self,
+		blob: StreamReadFromHeightResponse,
+	) -> Result<(), anyhow::Error> {
+		debug!("Got blob: {:?}", blob);
+
+		// get the block
+		let (block_bytes, block_timestamp, block_id, da_height) = match blob
+			.blob
+			.ok_or(anyhow::anyhow!("No blob in response"))?
+			.blob_type
+			.ok_or(anyhow::anyhow!("No blob type in response"))?
+		{
+			blob_response::BlobType::SequencedBlobBlock(blob) => {
+				(blob.data, blob.timestamp, blob.blob_id, blob.height)
+			}
+			_ => {
+				anyhow::bail!("Invalid blob type in response")
+			}
+		};
+
+		// check if the block has already been executed
+		if self.has_executed_block(block_id.clone()).await? {
+			warn!("Block already executed: {:#?}. It will be skipped", block_id);
+			return Ok(());
+		}
+
+		// the da height must be greater than 1
+		if da_height < 2 {
+			anyhow::bail!("Invalid DA height: {:?}", da_height);
+		}
+
+		// the light node has already decoded the block bytes with the codec in their header
+		let mut block = tokio::task::spawn_blocking(move || {
+			let block: Block = bcs::from_bytes(&block_bytes[..])?;
+			Ok::<Block, anyhow::Error>(block)
+		})
+		.await??;
+
+		// only accept blocks from the scheduled proposer
+		let proposal = match &self.proposer_schedule {
+			Some(proposer_schedule) => {
+				// block timestamps are the Celestia header time of their height, in microseconds,
+				// so that nodes following live and nodes replaying accept the same blocks
+				let da_timestamp_ms = block_timestamp / 1000;
+				let mut proposer_schedule = proposer_schedule.write().await;
+				if let Err(e) = proposer_schedule.try_accept(&block, da_timestamp_ms) {
+					warn!(
+						"Block {:#?} was not proposed by the scheduled proposer: {:?}",
+						block_id, e
+					);
+					return Ok(());
 				}
+				proposer_schedule
+					.last_accepted()
+					.map(|(slot, proposer)| (slot, proposer.clone()))
+			}
+			None => None,
+		};
+
+		// the first block from the DA carries the genesis, which every node checks against the one
+		// it booted from. The genesis block takes a timestamp from the first block so that every
+		// node rolls over genesis alike, and the transactions of the genesis come first in it.
+		// Anyone may post to the DA, so blocks before the first one carrying the genesis are
+		// skipped rather than stopping the node.
+		let genesis_block_id = self.executor.genesis_block_id()?;
+		let genesis = GenesisPayload::take_from_block(&mut block, genesis_block_id);
+		let span = info_span!(target: "movement_timing", "execute_block", id = %block_id);
+		let commitment = if self.executor.get_block_head_height().await? == 0 {
+			let genesis = match genesis {
+				Some(genesis) if block.parent == Id::genesis_block().to_vec() => genesis,
 				_ => {
-					anyhow::bail!("Invalid blob type in response")
+					warn!(
+						"Skipping block {:#?}, which does not carry genesis {}",
+						block_id, genesis_block_id
+					);
+					return Ok(());
 				}
 			};
-
-			// check if the block has already been executed
-			if self.has_executed_block(block_id.clone()).await? {
-				warn!("Block already executed: {:#?}. It will be skipped", block_id);
-				continue;
+			block.transactions.splice(0..0, genesis.try_into_block_transactions()?);
+			self.execute_first_block(block, block_timestamp).instrument(span).await?
+		} else {
+			if let Some(genesis) = genesis {
+				warn!(
+					"Ignoring genesis {} carried by block {:#?}",
+					genesis.genesis_block_id, block_id
+				);
 			}
+			self.execute_block_with_retries(block, block_timestamp).instrument(span).await?
+		};
 
-			// the da height must be greater than 1
-			if da_height < 2 {
-				anyhow::bail!("Invalid DA height: {:?}", da_height);
-			}
+		// mark the da_height - 1 as synced
+		// we can't mark this height as synced because we must allow for the possibility of multiple blocks at the same height according to the m1 da specifications (which currently is built on celestia which itself allows more than one block at the same height)
+		self.set_synced_height(da_height - 1).await?;
 
-			// decompress the block bytes
-			let block = tokio::task::spawn_blocking(move || {
-				let decompressed_block_bytes = zstd::decode_all(&block_bytes[..])?;
-				let block: Block = bcs::from_bytes(&decompressed_block_bytes)?;
-				Ok::<Block, anyhow::Error>(block)
-			})
-			.await??;
+		// set the block as executed
+		if let Some(rejected_id) = self.da_db.get_rejected_block(commitment.height).await? {
+			info!(
+				"Executed block {} at height {}, where block {} was rejected",
+				block_id, commitment.height, rejected_id
+			);
+		}
+		self.add_executed_block(block_id.to_string()).await?;
+		self.da_db
+			.add_executed_height(
+				commitment.height,
+				ExecutedBlock { id: block_id, da_height, proposal },
+			)
+			.await?;
 
-			// get the transactions
-			let span = info_span!(target: "movement_timing", "execute_block", id = %block_id);
-			let commitment =
-				self.execute_block_with_retries(block, block_timestamp).instrument(span).await?;
+		// todo: this needs defaults
+		if self.config.mcr.should_settle() {
+			info!("Posting block commitment via settlement manager");
+			match self.settlement_manager.post_block_commitment(commitment).await {
+				Ok(_) => {}
+				Err(e) => {
+					error!("Failed to post block commitment: {:?}", e);
+				}
+			}
+		} else {
+			info!("Skipping settlement");
+		}
 
-			// mark the da_height - 1 as synced
-			// we can't mark this height as synced because we must allow for the possibility of multiple blocks at the same height according to the m1 da specifications (which currently is built on celestia which itself allows more than one block at the same height)
-			self.set_synced_height(da_height - 1).await?;
+		Ok(())
+	}
 
-			// set the block as executed
-			self.add_executed_block(block_id.to_string()).await?;
+	/// Reverts the executor to a block height, and marks the blocks above it as rejected so that
+	/// they are read from the DA and executed again.
+	///
+	/// The proposer schedule is rewound with the executor, so that it accepts the blocks again.
+	async fn revert_block_head_to(&self, block_height: u64) -> Result<(), anyhow::Error> {
+		warn!("Reverting to block height {} after a rejected commitment", block_height);
+		self.executor.revert_block_head_to(block_height).await?;
+		let rejected = self.da_db.reject_blocks_above(block_height).await?;
+		for (height, block) in &rejected {
+			warn!("Rejected block {} at height {}", block.id, height);
+		}
 
-			// todo: this needs defaults
-			if self.config.mcr.should_settle() {
-				info!("Posting block commitment via settlement manager");
-				match self.settlement_manager.post_block_commitment(commitment).await {
-					Ok(_) => {}
-					Err(e) => {
-						error!("Failed to post block commitment: {:?}", e);
-					}
-				}
-			} else {
-				info!("Skipping settlement");
+		match &self.proposer_schedule {
+			Some(proposer_schedule) if !rejected.is_empty() => {
+				let last_kept = self.da_db.get_executed_block(block_height).await?;
+				proposer_schedule
+					.write()
+					.await
+					.revert_to(last_kept.and_then(|block| block.proposal));
 			}
+			_ => {}
 		}
+		Ok(())
+	}
+
+	/// Submits the genesis to the DA ahead of any other transaction if the chain has not started
+	/// yet, with the transactions of the genesis block if this node holds the root key.
+	async fn submit_genesis(&self) -> Result<(), anyhow::Error> {
+		if self.get_synced_height().await? > 0 || self.executor.get_block_head_height().await? > 0 {
+			return Ok(());
+		}
+		let genesis = GenesisPayload::new(
+			self.executor.genesis_block_id()?,
+			self.executor.genesis_transactions()?,
+		);
 
+		// the genesis transactions left out of the first block are written again like any other
+		let mut written_transactions = self.written_transactions.lock().await;
+		for transaction in &genesis.transactions {
+			written_transactions.insert(transaction.committed_hash());
+		}
+		drop(written_transactions);
+
+		info!(
+			"Submitting genesis {} with {} transactions",
+			genesis.genesis_block_id,
+			genesis.transactions.len()
+		);
+		let mut light_node_client = self.light_node_client.clone();
+		light_node_client
+			.submit_transactions(SubmitTransactionsRequest {
+				transactions: vec![genesis.try_to_light_node_transaction()?],
+			})
+			.await?;
 		Ok(())
 	}
 
+	/// Bootstraps a new node from a snapshot with the trusted hash, after which blocks are read
+	/// from the DA height it was taken at. Nodes which have already read from the DA are not
+	/// bootstrapped again.
+	pub async fn bootstrap_from_snapshot(
+		&self,
+		path: &Path,
+		trusted_hash: HashValue,
+	) -> Result<(), anyhow::Error> {
+		if self.get_synced_height().await? > 0 {
+			info!("DA already synced, not bootstrapping from the snapshot at {:?}", path);
+			return Ok(());
+		}
+		snapshot::import_snapshot(&self.executor, &self.da_db, path, trusted_hash).await?;
+		Ok(())
+	}
+
+	/// Rolls over the genesis block and executes the first block as a single step.
+	/// If the first block fails to execute, the rollover is reverted, so that the genesis block is
+	/// rolled over again when the first block is read again.
+	async fn execute_first_block(
+		&self,
+		block: Block,
+		block_timestamp: u64,
+	) -> anyhow::Result<BlockCommitment> {
+		self.executor.rollover_genesis_block(block_timestamp.saturating_sub(1)).await?;
+		match self.execute_block_with_retries(block, block_timestamp).await {
+			Ok(commitment) => Ok(commitment),
+			Err(e) => {
+				self.executor.revert_block_head_to(0).await?;
+				Err(e)
+			}
+		}
+	}
+
 	/// Retries executing a block several times.
 	/// This can be valid behavior if the block timestamps are too tightly clustered for the full node execution.
 	/// However, this has to be deterministic, otherwise nodes will not be able to agree on the block commitment.
@@ -290,7 +537,7 @@ where
 		block_transactions.push(block_metadata_transaction);
 
 		for transaction in block.transactions {
-			let signed_transaction: SignedTransaction = serde_json::from_slice(&transaction.data)?;
+			let signed_transaction: SignedTransaction = bcs::from_bytes(&transaction.data)?;
 			let signature_verified_transaction = SignatureVerifiedTransaction::Valid(
 				Transaction::UserTransaction(signed_transaction),
 			);
@@ -303,17 +550,61 @@ where
 		// form the executable block and execute it
 		let executable_block = ExecutableBlock::new(block_hash, block);
 		let block_id = executable_block.block_id;
-		let commitment = self.executor.execute_block_opt(executable_block).await?;
+		let (commitment, report) =
+			self.executor.execute_block_opt_with_report(executable_block).await?;
 
 		info!("Executed block: {}", block_id);
 
+		self.write_retried_transactions(report.retried).await?;
 		Ok(commitment)
 	}
+
+	/// Queues again the transactions which this node wrote to the DA and a block left out once it
+	/// reached its gas limit, so that they are written to the DA again in order.
+	///
+	/// Every node executes the block alike, so only the node which wrote a transaction writes it
+	/// again.
+	async fn write_retried_transactions(
+		&self,
+		retried: Vec<SignedTransaction>,
+	) -> Result<(), anyhow::Error> {
+		let mut written = Vec::new();
+		{
+			let mut written_transactions = self.written_transactions.lock().await;
+			for transaction in retried {
+				if written_transactions.remove(&transaction.committed_hash()) {
+					written.push(transaction);
+				}
+			}
+		}
+		if !written.is_empty() {
+			info!("Writing {} transactions left out of the block again", written.len());
+		}
+		for transaction in written {
+			self.transaction_queue.push(transaction.sender(), transaction).await?;
+		}
+		Ok(())
+	}
+}
+
+/// Waits for the next block height to revert to, or forever once no more can be sent.
+///
+/// Of the reversions which queued up meanwhile, the lowest height is reverted to.
+async fn next_reversion(reversions: &mut mpsc::UnboundedReceiver<u64>) -> u64 {
+	let mut block_height = match reversions.recv().await {
+		Some(block_height) => block_height,
+		None => std::future::pending().await,
+	};
+	while let Ok(queued) = reversions.try_recv() {
+		block_height = block_height.min(queued);
+	}
+	block_height
 }
 
 pub async fn read_commitment_events<T>(
 	mut stream: CommitmentEventStream,
 	executor: T,
+	reversions: mpsc::UnboundedSender<u64>,
 ) -> anyhow::Result<()>
 where
 	T: DynOptFinExecutor + Send + Sync,
@@ -338,7 +629,10 @@ where
 			}
 			BlockCommitmentEvent::Rejected { height, reason } => {
 				debug!("Commitment rejected: {:?} {:?}", height, reason);
-				// TODO: block reversion
+				// settlement accepts heights in order, so the height below is the last accepted
+				if let Err(e) = reversions.send(height.saturating_sub(1)) {
+					error!("Failed to revert the rejected block: {:?}", e);
+				}
 			}
 		}
 	}
@@ -367,8 +661,7 @@ where
 	// ! Currently this only implements opt.
 	/// Runs the executor until crash or shutdown.
 	async fn run_executor(&self) -> Result<(), anyhow::Error> {
-		// ! todo: this is a temporary solution to rollover the genesis block, really this (a) needs to be read from the DA and (b) requires modifications to Aptos Core.
-		self.executor.rollover_genesis_block().await?;
+		self.submit_genesis().await?;
 		// wait for both tasks to finish
 		tokio::try_join!(self.write_transactions_to_da(), self.read_blocks_from_da())?;
 
@@ -384,85 +677,23 @@ where
 
 impl<T> SuzukaPartialNode<T> {
 	pub async fn create_or_get_da_db(config: &suzuka_config::Config) -> Result<DB, anyhow::Error> {
-		let path = config.da_db.da_db_path.clone();
-
-		let mut options = Options::default();
-		options.create_if_missing(true);
-		options.create_missing_column_families(true);
-
-		let synced_height = ColumnFamilyDescriptor::new("synced_height", Options::default());
-		let executed_blocks = ColumnFamilyDescriptor::new("executed_blocks", Options::default());
-
-		let db = DB::open_cf_descriptors(&options, path, vec![synced_height, executed_blocks])
-			.map_err(|e| anyhow::anyhow!("Failed to open DA DB: {:?}", e))?;
-
-		Ok(db)
+		DaDB::open(&config.da_db.da_db_path)
 	}
 
 	pub async fn set_synced_height(&self, height: u64) -> Result<(), anyhow::Error> {
-		// This is heavy for this purpose, but progressively the contents of the DA DB will be used for more things
-		let da_db = self.da_db.clone();
-		tokio::task::spawn_blocking(move || {
-			let cf = da_db
-				.cf_handle("synced_height")
-				.ok_or(anyhow::anyhow!("No synced_height column family"))?;
-			let height = serde_json::to_string(&height)
-				.map_err(|e| anyhow::anyhow!("Failed to serialize synced height: {:?}", e))?;
-			da_db
-				.put_cf(&cf, "synced_height", height)
-				.map_err(|e| anyhow::anyhow!("Failed to set synced height: {:?}", e))
-		})
-		.await??;
-		Ok(())
+		self.da_db.set_synced_height(height).await
 	}
 
 	pub async fn get_synced_height(&self) -> Result<u64, anyhow::Error> {
-		// This is heavy for this purpose, but progressively the contents of the DA DB will be used for more things
-		let da_db = self.da_db.clone();
-		let height = tokio::task::spawn_blocking(move || {
-			let cf = da_db
-				.cf_handle("synced_height")
-				.ok_or(anyhow::anyhow!("No synced_height column family"))?;
-			let height = da_db
-				.get_cf(&cf, "synced_height")
-				.map_err(|e| anyhow::anyhow!("Failed to get synced height: {:?}", e))?;
-			let height = match height {
-				Some(height) => serde_json::from_slice(&height)
-					.map_err(|e| anyhow::anyhow!("Failed to deserialize synced height: {:?}", e))?,
-				None => 0,
-			};
-			Ok::<u64, anyhow::Error>(height)
-		})
-		.await??;
-		Ok(height)
+		self.da_db.get_synced_height().await
 	}
 
 	pub async fn add_executed_block(&self, id: String) -> Result<(), anyhow::Error> {
-		let da_db = self.da_db.clone();
-		tokio::task::spawn_blocking(move || {
-			let cf = da_db
-				.cf_handle("executed_blocks")
-				.ok_or(anyhow::anyhow!("No executed_blocks column family"))?;
-			da_db
-				.put_cf(&cf, id.clone(), id)
-				.map_err(|e| anyhow::anyhow!("Failed to add executed block: {:?}", e))
-		})
-		.await??;
-		Ok(())
+		self.da_db.add_executed_block(id).await
 	}
 
 	pub async fn has_executed_block(&self, id: String) -> Result<bool, anyhow::Error> {
-		let da_db = self.da_db.clone();
-		let id = tokio::task::spawn_blocking(move || {
-			let cf = da_db
-				.cf_handle("executed_blocks")
-				.ok_or(anyhow::anyhow!("No executed_blocks column family"))?;
-			da_db
-				.get_cf(&cf, id)
-				.map_err(|e| anyhow::anyhow!("Failed to get executed block: {:?}", e))
-		})
-		.await??;
-		Ok(id.is_some())
+		self.da_db.has_executed_block(id).await
 	}
 }
 
@@ -472,26 +703,17 @@ impl SuzukaPartialNode<Executor> {
 	) -> Result<(Self, impl Future<Output = Result<(), anyhow::Error>> + Send), anyhow::Error> {
 		let (tx, _) = async_channel::unbounded();
 
-		// todo: extract into getter
-		let light_node_connection_hostname = config
-			.m1_da_light_node
-			.m1_da_light_node_config
-			.m1_da_light_node_connection_hostname();
-
-		// todo: extract into getter
-		let light_node_connection_port = config
-			.m1_da_light_node
-			.m1_da_light_node_config
-			.m1_da_light_node_connection_port();
-		// todo: extract into getter
-		debug!(
-			"Connecting to light node at {}:{}",
-			light_node_connection_hostname, light_node_connection_port
-		);
-		let light_node_client = LightNodeServiceClient::connect(format!(
-			"http://{}:{}",
-			light_node_connection_hostname, light_node_connection_port
-		))
+		let light_node_config = &config.m1_da_light_node.m1_da_light_node_config;
+		let light_node_connection_url = light_node_config.m1_da_light_node_connection_url();
+		debug!("Connecting to light node at {}", light_node_connection_url);
+		let light_node_connection_token = light_node_config.m1_da_light_node_connection_token();
+		let light_node_connection_ca_cert_path =
+			light_node_config.m1_da_light_node_connection_ca_cert_path();
+		let light_node_client = connect(
+			light_node_connection_url,
+			light_node_connection_token.as_deref(),
+			light_node_connection_ca_cert_path.as_deref().map(Path::new),
+		)
 		.await
 		.context("Failed to connect to light node")?;
 
@@ -513,9 +735,52 @@ impl SuzukaPartialNode<Executor> {
 			.await
 			.context("Failed to create or get DA DB")?;
 
-		Self::bound(executor, light_node_client, settlement_client, movement_rest, &config, da_db)
-			.context(
-			"Failed to bind the executor, light node client, settlement client, and movement rest",
+		let (node, background_task) = Self::bound(
+			executor,
+			light_node_client,
+			settlement_client,
+			movement_rest,
+			&config,
+			da_db,
 		)
+		.context(
+			"Failed to bind the executor, light node client, settlement client, and movement rest",
+		)?;
+
+		if let Some(snapshot_path) = &config.da_db.snapshot_path {
+			let snapshot_hash = config
+				.da_db
+				.snapshot_hash
+				.as_deref()
+				.ok_or(anyhow::anyhow!("A snapshot is only imported with its trusted hash"))?;
+			let snapshot_hash = HashValue::from_hex(snapshot_hash.trim_start_matches("0x"))
+				.context("Invalid snapshot hash")?;
+			node.bootstrap_from_snapshot(Path::new(snapshot_path), snapshot_hash)
+				.await
+				.context("Failed to bootstrap from the snapshot")?;
+		}
+
+		Ok((node, background_task))
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_written_transactions() {
+		let mut written_transactions = WrittenTransactions::new(2);
+		let hashes: Vec<HashValue> = (0..3).map(|_| HashValue::random()).collect();
+		for hash in &hashes {
+			written_transactions.insert(*hash);
+		}
+
+		// the oldest is forgotten beyond the capacity
+		assert!(!written_transactions.remove(&hashes[0]));
+		assert!(written_transactions.remove(&hashes[1]));
+		assert!(!written_transactions.remove(&hashes[1]));
+		assert!(written_transactions.remove(&hashes[2]));
 	}
 }
@@ -0,0 +1,152 @@
+use crate::da_db::{DaDB, ExecutedBlock};
+use maptos_dof_execution::{DynOptFinExecutor, HashValue};
+use movement_types::BlockCommitment;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+const STATE_DIR: &str = "state";
+const DA_POSITION_FILE: &str = "da.json";
+
+/// The position in the DA of a snapshot, from which a node bootstrapped from it reads blocks.
+///
+/// Its hash is published by the operator of the exporting node, and authenticates the position
+/// and, through the commitment, the state of the snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaPosition {
+	/// The commitment of the block the snapshot was taken at.
+	pub commitment: BlockCommitment,
+	/// The DA height the node had synced to.
+	pub synced_height: u64,
+	/// The blocks executed above the synced height, which are read again but not executed.
+	pub executed_blocks: Vec<(u64, ExecutedBlock)>,
+}
+
+impl DaPosition {
+	pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+		let bytes = std::fs::read(path.join(DA_POSITION_FILE))?;
+		Ok(serde_json::from_slice(&bytes)?)
+	}
+
+	pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+		std::fs::create_dir_all(path)?;
+		std::fs::write(path.join(DA_POSITION_FILE), serde_json::to_vec_pretty(self)?)?;
+		Ok(())
+	}
+
+	/// Gets the hash a node is configured with to import the snapshot.
+	pub fn hash(&self) -> Result<HashValue, anyhow::Error> {
+		Ok(HashValue::sha3_256_of(&bcs::to_bytes(self)?))
+	}
+}
+
+/// Exports a snapshot of the executor's state and the matching DA position into a directory.
+///
+/// The node must not be reading blocks meanwhile, so that the state and the position agree.
+pub async fn export_snapshot<T>(
+	executor: &T,
+	da_db: &DaDB,
+	path: &Path,
+) -> Result<DaPosition, anyhow::Error>
+where
+	T: DynOptFinExecutor + Send + Sync,
+{
+	let synced_height = da_db.get_synced_height().await?;
+	let executed_blocks = da_db.get_executed_blocks_above_da_height(synced_height).await?;
+	let commitment = executor.export_state_snapshot(&path.join(STATE_DIR)).await?;
+
+	// the last executed block must be the one the state was taken at
+	if let Some((last_height, _)) = executed_blocks.last() {
+		if *last_height != commitment.height {
+			anyhow::bail!(
+				"Executed block height {} does not match the block head {}",
+				last_height,
+				commitment.height
+			);
+		}
+	}
+
+	let position = DaPosition { commitment, synced_height, executed_blocks };
+	position.write(path)?;
+	info!(
+		"Exported a snapshot at block height {} and DA height {} with hash {}",
+		position.commitment.height,
+		synced_height,
+		position.hash()?
+	);
+	Ok(position)
+}
+
+/// Imports the snapshot in a directory into the executor, and sets the DA DB to its position so
+/// that blocks are read from there.
+///
+/// The snapshot must have the trusted hash, which covers its DA position and its commitment.
+pub async fn import_snapshot<T>(
+	executor: &T,
+	da_db: &DaDB,
+	path: &Path,
+	trusted_hash: HashValue,
+) -> Result<DaPosition, anyhow::Error>
+where
+	T: DynOptFinExecutor + Send + Sync,
+{
+	let position = DaPosition::read(path)?;
+	let hash = position.hash()?;
+	if hash != trusted_hash {
+		anyhow::bail!("Snapshot hash {} is not the trusted {}", hash, trusted_hash);
+	}
+
+	let block_height = executor
+		.import_state_snapshot(&path.join(STATE_DIR), &position.commitment)
+		.await?;
+	if block_height != position.commitment.height {
+		anyhow::bail!(
+			"Imported block height {} does not match the DA position at {}",
+			block_height,
+			position.commitment.height
+		);
+	}
+
+	for (height, block) in &position.executed_blocks {
+		da_db.add_executed_block(block.id.clone()).await?;
+		da_db.add_executed_height(*height, block.clone()).await?;
+	}
+	da_db.set_synced_height(position.synced_height).await?;
+	info!(
+		"Imported a snapshot at block height {} and DA height {}",
+		block_height, position.synced_height
+	);
+	Ok(position)
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use movement_types::{Commitment, Id};
+
+	#[test]
+	fn test_da_position_read_write() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let position = DaPosition {
+			commitment: BlockCommitment {
+				height: 4,
+				block_id: Id::test(),
+				commitment: Commitment::test(),
+			},
+			synced_height: 6,
+			executed_blocks: vec![(
+				4,
+				ExecutedBlock { id: "c".to_string(), da_height: 7, proposal: None },
+			)],
+		};
+		position.write(dir.path())?;
+		assert_eq!(DaPosition::read(dir.path())?, position);
+
+		// the hash covers the position
+		let mut moved = position.clone();
+		moved.synced_height += 1;
+		assert_ne!(moved.hash()?, position.hash()?);
+		Ok(())
+	}
+}
@@ -0,0 +1,7 @@
+use clap::Parser;
+use m1_da_light_node_client::cli::Cli;
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+	Cli::parse().run().await
+}
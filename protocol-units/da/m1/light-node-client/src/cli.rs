@@ -0,0 +1,225 @@
+use crate::connect::{connect, AuthenticatedClient};
+use crate::*;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tokio_stream::StreamExt;
+
+/// A command line client for the M1 DA Light Node. Prints its results as JSON.
+#[derive(Clone, Debug, Parser)]
+#[clap(name = "m1-da-light-node-cli")]
+pub struct Cli {
+	/// The URL of the light node
+	#[clap(long, env = "M1_DA_LIGHT_NODE_URL", default_value = "http://0.0.0.0:30730")]
+	pub url: String,
+
+	/// The bearer token to authenticate with
+	#[clap(long, env = "M1_DA_LIGHT_NODE_TOKEN")]
+	pub token: Option<String>,
+
+	/// The PEM certificate of the CA to verify the light node's TLS certificate with
+	#[clap(long)]
+	pub ca_cert: Option<PathBuf>,
+
+	/// The namespace to read from or write to. The default namespace if unset.
+	#[clap(long, default_value = "")]
+	pub namespace: String,
+
+	#[clap(subcommand)]
+	pub command: Command,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+	/// Reads the blobs at a height
+	ReadHeight { height: u64 },
+	/// Streams the blobs from a height on, printing one blob per line
+	StreamFromHeight { height: u64 },
+	/// Writes the contents of a file as a blob
+	WriteBlob { file: PathBuf },
+	/// Gets the verification parameters
+	GetVerificationParameters,
+	/// Sets the verification parameters. Requires the admin role if authentication is enabled.
+	SetVerificationParameters {
+		#[clap(long, value_enum)]
+		mode: Mode,
+		#[clap(long)]
+		signers: Vec<String>,
+		#[clap(long, default_value_t = 0)]
+		m: u32,
+		#[clap(long, default_value_t = 0)]
+		n: u32,
+	},
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+	Cowboy,
+	ValidatorIn,
+	MOfN,
+}
+
+impl From<Mode> for VerificationMode {
+	fn from(mode: Mode) -> Self {
+		match mode {
+			Mode::Cowboy => VerificationMode::Cowboy,
+			Mode::ValidatorIn => VerificationMode::ValidatorIn,
+			Mode::MOfN => VerificationMode::MOfN,
+		}
+	}
+}
+
+pub type CliClient = AuthenticatedClient;
+
+impl Cli {
+	pub async fn connect(&self) -> Result<CliClient, anyhow::Error> {
+		connect(self.url.clone(), self.token.as_deref(), self.ca_cert.as_deref()).await
+	}
+
+	/// Runs the command, printing each result as a line of JSON.
+	pub async fn run(&self) -> Result<(), anyhow::Error> {
+		let mut client = self.connect().await?;
+		let namespace = self.namespace.clone();
+
+		match &self.command {
+			Command::ReadHeight { height } => {
+				let response = client
+					.read_at_height(ReadAtHeightRequest { height: *height, namespace })
+					.await?
+					.into_inner();
+				let blobs: Vec<_> = response.blobs.iter().map(blob_response_to_json).collect();
+				println!("{}", json!({ "height": height, "blobs": blobs }));
+			}
+			Command::StreamFromHeight { height } => {
+				let mut stream = client
+					.stream_read_from_height(StreamReadFromHeightRequest {
+						height: *height,
+						namespace,
+					})
+					.await?
+					.into_inner();
+				while let Some(response) = stream.next().await {
+					if let Some(blob) = response?.blob {
+						println!("{}", blob_response_to_json(&blob));
+					}
+				}
+			}
+			Command::WriteBlob { file } => {
+				let data = std::fs::read(file)?;
+				let response = client
+					.batch_write(BatchWriteRequest { blobs: vec![BlobWrite { data }], namespace })
+					.await?
+					.into_inner();
+				for blob in &response.blobs {
+					println!("{}", blob_response_to_json(blob));
+				}
+			}
+			Command::GetVerificationParameters => {
+				let response = client
+					.get_verification_parameters(GetVerificationParametersRequest {})
+					.await?
+					.into_inner();
+				println!("{}", json!({ "mode": response.mode().as_str_name() }));
+			}
+			Command::SetVerificationParameters { mode, signers, m, n } => {
+				let request = UpdateVerificationParametersRequest {
+					mode: VerificationMode::from(*mode).into(),
+					signers: signers.clone(),
+					m: *m,
+					n: *n,
+				};
+				let response = client.update_verification_parameters(request).await?.into_inner();
+				println!("{}", json!({ "mode": response.mode().as_str_name() }));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Converts a blob response to JSON, with the blob data in hex.
+pub fn blob_response_to_json(response: &BlobResponse) -> Value {
+	let (blob_type, blob) = match &response.blob_type {
+		Some(blob_response::BlobType::PassedThroughBlob(blob)) => ("passed_through_blob", blob),
+		Some(blob_response::BlobType::SequencedBlobIntent(blob)) => ("sequenced_blob_intent", blob),
+		Some(blob_response::BlobType::SequencedBlobBlock(blob)) => ("sequenced_blob_block", blob),
+		None => return Value::Null,
+	};
+	json!({
+		"blob_type": blob_type,
+		"blob_id": blob.blob_id,
+		"height": blob.height,
+		"timestamp": blob.timestamp,
+		"data": hex::encode(&blob.data),
+	})
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn verify_cli() {
+		use clap::CommandFactory;
+		Cli::command().debug_assert();
+	}
+
+	#[test]
+	fn test_parses_subcommands() -> Result<(), anyhow::Error> {
+		let cli = Cli::try_parse_from([
+			"m1-da-light-node-cli",
+			"--namespace",
+			"governance",
+			"set-verification-parameters",
+			"--mode",
+			"m-of-n",
+			"--signers",
+			"a",
+			"--signers",
+			"b",
+			"--m",
+			"2",
+			"--n",
+			"3",
+		])?;
+		assert_eq!(cli.namespace, "governance");
+		match cli.command {
+			Command::SetVerificationParameters { mode, signers, m, n } => {
+				assert_eq!(VerificationMode::from(mode), VerificationMode::MOfN);
+				assert_eq!(signers, vec!["a".to_string(), "b".to_string()]);
+				assert_eq!((m, n), (2, 3));
+			}
+			other => anyhow::bail!("Unexpected command {:?}", other),
+		}
+
+		let cli = Cli::try_parse_from(["m1-da-light-node-cli", "read-height", "10"])?;
+		assert_eq!(cli.namespace, "");
+		assert!(matches!(cli.command, Command::ReadHeight { height: 10 }));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_blob_response_to_json() {
+		let response = BlobResponse {
+			blob_type: Some(blob_response::BlobType::SequencedBlobBlock(Blob {
+				blob_id: "id".to_string(),
+				data: vec![0xab, 0xcd],
+				height: 3,
+				timestamp: 4,
+			})),
+		};
+		assert_eq!(
+			blob_response_to_json(&response),
+			json!({
+				"blob_type": "sequenced_blob_block",
+				"blob_id": "id",
+				"height": 3,
+				"timestamp": 4,
+				"data": "abcd",
+			})
+		);
+		assert_eq!(blob_response_to_json(&BlobResponse { blob_type: None }), Value::Null);
+	}
+}
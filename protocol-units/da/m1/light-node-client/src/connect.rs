@@ -0,0 +1,51 @@
+use crate::LightNodeServiceClient;
+use std::path::Path;
+use tonic::{
+	metadata::{Ascii, MetadataValue},
+	service::{interceptor::InterceptedService, Interceptor},
+	transport::{Certificate, Channel, ClientTlsConfig},
+	Request, Status,
+};
+
+/// Authenticates requests with a bearer token, if one is set.
+#[derive(Clone, Debug)]
+pub struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl BearerToken {
+	pub fn try_new(token: Option<&str>) -> Result<Self, anyhow::Error> {
+		let token = match token {
+			Some(token) => Some(format!("Bearer {}", token).parse()?),
+			None => None,
+		};
+		Ok(Self(token))
+	}
+}
+
+impl Interceptor for BearerToken {
+	fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+		if let Some(token) = &self.0 {
+			request.metadata_mut().insert("authorization", token.clone());
+		}
+		Ok(request)
+	}
+}
+
+/// A light node client which authenticates its requests with a bearer token.
+pub type AuthenticatedClient = LightNodeServiceClient<InterceptedService<Channel, BearerToken>>;
+
+/// Connects to the light node at the URL, verifying its TLS certificate with the CA certificate
+/// at `ca_cert_path` if set.
+pub async fn connect(
+	url: String,
+	token: Option<&str>,
+	ca_cert_path: Option<&Path>,
+) -> Result<AuthenticatedClient, anyhow::Error> {
+	let mut endpoint = Channel::from_shared(url)?;
+	if let Some(ca_cert_path) = ca_cert_path {
+		let ca_cert = std::fs::read(ca_cert_path)?;
+		endpoint = endpoint
+			.tls_config(ClientTlsConfig::new().ca_certificate(Certificate::from_pem(ca_cert)))?;
+	}
+	let token = BearerToken::try_new(token)?;
+	Ok(LightNodeServiceClient::with_interceptor(endpoint.connect().await?, token))
+}
@@ -8,7 +8,8 @@ async fn test_light_node_submits_blob_over_stream() -> Result<(), anyhow::Error>
 	let mut client = LightNodeServiceClient::connect("http://0.0.0.0:30730").await?;
 
 	let blob_write = BlobWrite { data: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9] };
-	let request = StreamWriteBlobRequest { blob: Some(blob_write.clone()) };
+	let request =
+		StreamWriteBlobRequest { blob: Some(blob_write.clone()), namespace: String::new() };
 
 	let (tx, rx) = tokio::sync::mpsc::channel(32);
 
@@ -48,7 +49,7 @@ async fn test_submit_and_read() -> Result<(), anyhow::Error> {
 
 	let data = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 	let blob_write = BlobWrite { data: data.clone() };
-	let request = BatchWriteRequest { blobs: vec![blob_write.clone()] };
+	let request = BatchWriteRequest { blobs: vec![blob_write.clone()], namespace: String::new() };
 
 	let write = client.batch_write(request).await?.into_inner();
 	let first = write.blobs[0].clone();
@@ -60,7 +61,7 @@ async fn test_submit_and_read() -> Result<(), anyhow::Error> {
 			anyhow::bail!("Invalid blob type in response");
 		}
 	};
-	let read_request = ReadAtHeightRequest { height };
+	let read_request = ReadAtHeightRequest { height, namespace: String::new() };
 
 	let read = client.read_at_height(read_request).await?.into_inner();
 	let first = read.blobs[0].clone();
@@ -8,13 +8,16 @@ async fn test_light_node_submits_blob_over_stream() -> Result<(), anyhow::Error>
 
 	let data = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 	let blob_write = BlobWrite { data: data.clone() };
-	let batch_write_request = BatchWriteRequest { blobs: vec![blob_write.clone()] };
+	let batch_write_request =
+		BatchWriteRequest { blobs: vec![blob_write.clone()], namespace: String::new() };
 	client.batch_write(batch_write_request).await?;
 
 	let mut log_lines = Vec::new();
 
 	for _ in 0..16 {
-		let stream = client.stream_read_latest(StreamReadLatestRequest {}).await?;
+		let stream = client
+			.stream_read_latest(StreamReadLatestRequest { namespace: String::new() })
+			.await?;
 
 		let back = stream
 			.into_inner()
@@ -51,3 +54,38 @@ async fn test_light_node_submits_blob_over_stream() -> Result<(), anyhow::Error>
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_light_node_submits_transactions() -> Result<(), anyhow::Error> {
+	let mut client = LightNodeServiceClient::connect("http://0.0.0.0:30730").await?;
+
+	let data = vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
+	let transaction = Transaction { data: data.clone(), sequence_number: 0 };
+	let request = SubmitTransactionsRequest { transactions: vec![transaction] };
+	let response = client.submit_transactions(request).await?.into_inner();
+
+	// the sequencer assigns the same id as a movement transaction with the same contents
+	let expected = movement_types::Transaction::new(data, 0);
+	assert_eq!(response.transaction_ids, vec![expected.id().to_vec()]);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_light_node_reports_status() -> Result<(), anyhow::Error> {
+	let mut client = LightNodeServiceClient::connect("http://0.0.0.0:30730").await?;
+
+	let status = client.status(StatusRequest {}).await?.into_inner();
+	let celestia_connection = status
+		.celestia_connection
+		.ok_or(anyhow::anyhow!("No Celestia connection status"))?;
+	assert!(celestia_connection.healthy, "{}", celestia_connection.detail);
+	assert!(status.network_head_height > 0);
+
+	// the block proposer is only reported in sequencer mode
+	let block_proposer =
+		status.block_proposer.ok_or(anyhow::anyhow!("No block proposer status"))?;
+	assert!(block_proposer.healthy, "{}", block_proposer.detail);
+
+	Ok(())
+}
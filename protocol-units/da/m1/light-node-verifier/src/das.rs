@@ -0,0 +1,584 @@
+use crate::Verifier;
+use celestia_rpc::{Client, HeaderClient, ShareClient};
+use celestia_types::{nmt::NamespaceId, ExtendedHeader};
+use futures::stream::{self, StreamExt};
+use m1_da_light_node_grpc::VerificationMode;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+/// How many shares are sampled at once.
+pub const CONCURRENT_SAMPLES: usize = 16;
+
+/// The most heights whose sampling outcome is remembered.
+pub const MAX_SAMPLED_HEIGHTS: usize = 1024;
+
+/// The coordinate of a share in the extended data square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coordinate {
+	pub row: u16,
+	pub column: u16,
+}
+
+/// Fetches the shares of the extended data square of a block.
+#[tonic::async_trait]
+pub trait ShareSampler {
+	/// The header of a block, which commits to its extended data square.
+	type Header: Send + Sync;
+
+	/// Gets the header at the height.
+	async fn header(&self, height: u64) -> Result<Self::Header, anyhow::Error>;
+
+	/// Gets the width of the extended data square the header commits to.
+	fn square_width(&self, header: &Self::Header) -> u16;
+
+	/// Samples the share at the coordinate.
+	///
+	/// Returns false if the share does not match the header, and fails if it could not be fetched.
+	async fn sample(
+		&self,
+		header: &Self::Header,
+		coordinate: Coordinate,
+	) -> Result<bool, anyhow::Error>;
+}
+
+/// Gets the least fraction of the extended data square which must be withheld for the original
+/// data to be unrecoverable: any `k + 1` rows and `k + 1` columns of a `2k` wide square.
+pub fn min_unavailable_fraction(width: u16) -> f64 {
+	let width = f64::from(width);
+	let k = (width / 2.0).floor();
+	((k + 1.0) * (k + 1.0) / (width * width)).min(1.0)
+}
+
+/// Gets the number of samples which all have to be available for the original data to be
+/// recoverable with the confidence.
+pub fn samples_for_confidence(width: u16, confidence: f64) -> usize {
+	let fraction = min_unavailable_fraction(width);
+	if fraction >= 1.0 {
+		return 1;
+	}
+	((1.0 - confidence).ln() / (1.0 - fraction).ln()).ceil() as usize
+}
+
+/// Gets the confidence that the original data is recoverable once the samples were all available.
+///
+/// Samples are drawn without replacement, so this bound is conservative.
+pub fn confidence_of_samples(width: u16, samples: usize) -> f64 {
+	let fraction = min_unavailable_fraction(width);
+	1.0 - (1.0 - fraction).powi(samples.min(i32::MAX as usize) as i32)
+}
+
+/// The outcome of sampling the extended data square of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplingReport {
+	pub width: u16,
+	pub sampled: Vec<Coordinate>,
+	pub unavailable: Vec<Coordinate>,
+}
+
+impl SamplingReport {
+	/// Whether every sampled share was available.
+	pub fn is_available(&self) -> bool {
+		self.unavailable.is_empty()
+	}
+
+	/// The confidence that the original data is recoverable, which is zero if any sample failed.
+	pub fn confidence(&self) -> f64 {
+		if self.is_available() {
+			confidence_of_samples(self.width, self.sampled.len())
+		} else {
+			0.0
+		}
+	}
+}
+
+/// Samples the extended data square of a block until the availability confidence target is
+/// reached, taking at least the configured number of samples.
+#[derive(Debug, Clone)]
+pub struct DataAvailabilitySampler<S> {
+	sampler: S,
+	samples: usize,
+	confidence: f64,
+}
+
+impl<S> DataAvailabilitySampler<S>
+where
+	S: ShareSampler + Send + Sync,
+{
+	pub fn try_new(sampler: S, samples: usize, confidence: f64) -> Result<Self, anyhow::Error> {
+		if confidence.is_nan() || confidence <= 0.0 || confidence >= 1.0 {
+			anyhow::bail!("The sampling confidence must be between 0 and 1, not {}", confidence);
+		}
+		Ok(Self { sampler, samples, confidence })
+	}
+
+	/// Gets the number of distinct shares sampled in a square of the width.
+	pub fn sample_count(&self, width: u16) -> usize {
+		let shares = usize::from(width) * usize::from(width);
+		self.samples.max(samples_for_confidence(width, self.confidence)).min(shares)
+	}
+
+	/// Picks distinct coordinates to sample uniformly from a square of the width.
+	pub fn coordinates<R: Rng + ?Sized>(&self, width: u16, rng: &mut R) -> Vec<Coordinate> {
+		let shares = usize::from(width) * usize::from(width);
+		let mut coordinates: Vec<_> =
+			rand::seq::index::sample(rng, shares, self.sample_count(width))
+				.into_iter()
+				.map(|index| Coordinate {
+					row: (index / usize::from(width)) as u16,
+					column: (index % usize::from(width)) as u16,
+				})
+				.collect();
+		coordinates.sort();
+		coordinates
+	}
+
+	/// Samples the extended data square at the height at random coordinates.
+	pub async fn sample(&self, height: u64) -> Result<SamplingReport, anyhow::Error> {
+		let header = self.sampler.header(height).await?;
+		let width = self.sampler.square_width(&header);
+		let coordinates = self.coordinates(width, &mut rand::thread_rng());
+		self.sample_coordinates(&header, coordinates).await
+	}
+
+	/// Samples the extended data square the header commits to at the coordinates.
+	pub async fn sample_coordinates(
+		&self,
+		header: &S::Header,
+		coordinates: Vec<Coordinate>,
+	) -> Result<SamplingReport, anyhow::Error> {
+		let width = self.sampler.square_width(header);
+		if let Some(coordinate) = coordinates.iter().find(|c| c.row >= width || c.column >= width) {
+			anyhow::bail!("Coordinate {:?} is outside of a square of width {}", coordinate, width);
+		}
+
+		let results: Vec<_> = stream::iter(coordinates.iter().copied())
+			.map(
+				|coordinate| async move { (coordinate, self.sampler.sample(header, coordinate).await) },
+			)
+			.buffer_unordered(CONCURRENT_SAMPLES)
+			.collect()
+			.await;
+
+		let mut unavailable = Vec::new();
+		for (coordinate, available) in results {
+			if !available? {
+				unavailable.push(coordinate);
+			}
+		}
+		unavailable.sort();
+
+		Ok(SamplingReport { width, sampled: coordinates, unavailable })
+	}
+}
+
+/// Samples shares from a Celestia node.
+#[derive(Clone)]
+pub struct CelestiaSampler {
+	pub client: Arc<Client>,
+	/// How long to wait for a share before considering it unavailable.
+	pub timeout: Duration,
+}
+
+#[tonic::async_trait]
+impl ShareSampler for CelestiaSampler {
+	type Header = ExtendedHeader;
+
+	async fn header(&self, height: u64) -> Result<ExtendedHeader, anyhow::Error> {
+		self.client.header_wait_for_height(height).await?;
+		Ok(self.client.header_get_by_height(height).await?)
+	}
+
+	fn square_width(&self, header: &ExtendedHeader) -> u16 {
+		header.dah.square_len() as u16
+	}
+
+	/// Shares of the original data are checked against the row root of the header with the
+	/// namespace proof of their row. The node does not serve proofs of parity shares, so those
+	/// are only resolved through the row root by the node.
+	async fn sample(
+		&self,
+		header: &ExtendedHeader,
+		coordinate: Coordinate,
+	) -> Result<bool, anyhow::Error> {
+		let (row, column) = (usize::from(coordinate.row), usize::from(coordinate.column));
+		let share = tokio::time::timeout(
+			self.timeout,
+			self.client.share_get_share(header, row as u64, column as u64),
+		)
+		.await
+		.map_err(|_| anyhow::anyhow!("Timed out sampling the share at {:?}", coordinate))??;
+
+		let half = usize::from(self.square_width(header) / 2);
+		if row >= half || column >= half {
+			return Ok(true);
+		}
+		let row_root = header
+			.dah
+			.row_root(row)
+			.ok_or(anyhow::anyhow!("No row root found for {:?}", coordinate))?;
+		let namespace = share.namespace();
+		let namespace_id: NamespaceId = namespace.into();
+		if namespace_id < row_root.min_namespace() || row_root.max_namespace() < namespace_id {
+			return Ok(false);
+		}
+
+		// the namespace has one proven row for each row its range covers, in order
+		let namespace_rows: Vec<usize> = (0..)
+			.map_while(|row| header.dah.row_root(row).map(|root| (row, root)))
+			.filter(|(_, root)| {
+				root.min_namespace() <= namespace_id && namespace_id <= root.max_namespace()
+			})
+			.map(|(row, _)| row)
+			.collect();
+		let position = namespace_rows
+			.iter()
+			.position(|namespace_row| *namespace_row == row)
+			.ok_or(anyhow::anyhow!("Row {} does not contain namespace {:?}", row, namespace))?;
+		let namespaced = tokio::time::timeout(
+			self.timeout,
+			self.client.share_get_shares_by_namespace(header, namespace),
+		)
+		.await
+		.map_err(|_| anyhow::anyhow!("Timed out proving the share at {:?}", coordinate))??;
+		let proven_row = namespaced.rows.get(position).ok_or(anyhow::anyhow!(
+			"No proof of namespace {:?} in row {}",
+			namespace,
+			row
+		))?;
+		if proven_row
+			.proof
+			.verify_complete_namespace(&row_root, &proven_row.shares, namespace_id)
+			.is_err()
+		{
+			return Ok(false);
+		}
+
+		let start = proven_row.proof.start_idx() as usize;
+		let proven_share = column.checked_sub(start).and_then(|i| proven_row.shares.get(i));
+		Ok(proven_share.map_or(false, |proven| proven.as_ref() == share.as_ref()))
+	}
+}
+
+/// Verifies blobs with an inner verifier and samples the block they are in for availability.
+///
+/// Each block is sampled once, however many of its blobs are verified.
+pub struct DasVerifier<V, S> {
+	pub inner: V,
+	pub sampler: DataAvailabilitySampler<S>,
+	/// Whether each recently sampled block was available, by height.
+	sampled: Mutex<BTreeMap<u64, Arc<OnceCell<bool>>>>,
+}
+
+impl<V, S> DasVerifier<V, S>
+where
+	S: ShareSampler + Send + Sync,
+{
+	pub fn new(inner: V, sampler: DataAvailabilitySampler<S>) -> Self {
+		Self { inner, sampler, sampled: Mutex::new(BTreeMap::new()) }
+	}
+
+	/// Samples the block at the height unless it was already sampled, and returns whether it
+	/// was available. Failed attempts are not remembered.
+	pub async fn is_available(&self, height: u64) -> Result<bool, anyhow::Error> {
+		let cell = {
+			let mut sampled = self
+				.sampled
+				.lock()
+				.map_err(|e| anyhow::anyhow!("Sampled heights poisoned: {}", e))?;
+			let cell = sampled.entry(height).or_default().clone();
+			while sampled.len() > MAX_SAMPLED_HEIGHTS {
+				sampled.pop_first();
+			}
+			cell
+		};
+
+		Ok(*cell.get_or_try_init(|| self.sample(height)).await?)
+	}
+
+	async fn sample(&self, height: u64) -> Result<bool, anyhow::Error> {
+		let report = self.sampler.sample(height).await?;
+		if !report.is_available() {
+			warn!(
+				"Block at height {} failed availability sampling: {} of {} samples unavailable",
+				height,
+				report.unavailable.len(),
+				report.sampled.len()
+			);
+		}
+		Ok(report.is_available())
+	}
+}
+
+#[tonic::async_trait]
+impl<V, S> Verifier for DasVerifier<V, S>
+where
+	V: Verifier + Send + Sync,
+	S: ShareSampler + Send + Sync,
+{
+	async fn verify(
+		&self,
+		verification_mode: VerificationMode,
+		blob: &[u8],
+		height: u64,
+	) -> Result<bool, anyhow::Error> {
+		if !self.inner.verify(verification_mode, blob, height).await? {
+			return Ok(false);
+		}
+
+		self.is_available(height).await
+	}
+
+	async fn verify_cowboy(
+		&self,
+		verification_mode: VerificationMode,
+		blob: &[u8],
+		height: u64,
+	) -> Result<bool, anyhow::Error> {
+		self.inner.verify_cowboy(verification_mode, blob, height).await
+	}
+
+	async fn verifiy_validator_in(
+		&self,
+		verification_mode: VerificationMode,
+		blob: &[u8],
+		height: u64,
+	) -> Result<bool, anyhow::Error> {
+		self.inner.verifiy_validator_in(verification_mode, blob, height).await
+	}
+
+	async fn verify_m_of_n(
+		&self,
+		verification_mode: VerificationMode,
+		blob: &[u8],
+		height: u64,
+	) -> Result<bool, anyhow::Error> {
+		self.inner.verify_m_of_n(verification_mode, blob, height).await
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use rand::{rngs::StdRng, SeedableRng};
+	use sha2::{Digest, Sha256};
+	use std::collections::BTreeSet;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	const SHARE_SIZE: usize = 512;
+
+	/// A square of synthetic shares, committed to by the hash of each share.
+	struct SyntheticSquare {
+		width: u16,
+		shares: Vec<Vec<u8>>,
+		commitments: Vec<[u8; 32]>,
+	}
+
+	impl SyntheticSquare {
+		fn new(width: u16) -> Self {
+			let shares: Vec<Vec<u8>> = (0..usize::from(width) * usize::from(width))
+				.map(|i| (0..SHARE_SIZE).map(|j| (i * 31 + j) as u8).collect())
+				.collect();
+			let commitments = shares.iter().map(|share| Sha256::digest(share).into()).collect();
+			Self { width, shares, commitments }
+		}
+
+		fn index(&self, coordinate: Coordinate) -> usize {
+			usize::from(coordinate.row) * usize::from(self.width) + usize::from(coordinate.column)
+		}
+
+		/// Withholds a `k + 1` by `k + 1` block of shares, which makes the square unrecoverable.
+		fn withhold_unrecoverable(&mut self) {
+			let half = self.width / 2;
+			for row in 0..=half {
+				for column in 0..=half {
+					let index = self.index(Coordinate { row, column });
+					self.shares[index].clear();
+				}
+			}
+		}
+	}
+
+	/// Serves the shares of a synthetic square which are not withheld.
+	struct SyntheticSampler {
+		square: SyntheticSquare,
+		/// The number of headers requested, one for each block sampled.
+		headers: AtomicUsize,
+	}
+
+	impl SyntheticSampler {
+		fn new(square: SyntheticSquare) -> Self {
+			Self { square, headers: AtomicUsize::new(0) }
+		}
+	}
+
+	/// Accepts every blob.
+	struct AcceptAll;
+
+	#[tonic::async_trait]
+	impl Verifier for AcceptAll {
+		async fn verifiy_validator_in(
+			&self,
+			_verification_mode: VerificationMode,
+			_blob: &[u8],
+			_height: u64,
+		) -> Result<bool, anyhow::Error> {
+			Ok(true)
+		}
+
+		async fn verify_m_of_n(
+			&self,
+			_verification_mode: VerificationMode,
+			_blob: &[u8],
+			_height: u64,
+		) -> Result<bool, anyhow::Error> {
+			Ok(true)
+		}
+	}
+
+	#[tonic::async_trait]
+	impl ShareSampler for SyntheticSampler {
+		type Header = Vec<[u8; 32]>;
+
+		async fn header(&self, _height: u64) -> Result<Self::Header, anyhow::Error> {
+			self.headers.fetch_add(1, Ordering::SeqCst);
+			Ok(self.square.commitments.clone())
+		}
+
+		fn square_width(&self, header: &Self::Header) -> u16 {
+			(header.len() as f64).sqrt() as u16
+		}
+
+		async fn sample(
+			&self,
+			header: &Self::Header,
+			coordinate: Coordinate,
+		) -> Result<bool, anyhow::Error> {
+			let index = self.square.index(coordinate);
+			let share = &self.square.shares[index];
+			Ok(!share.is_empty() && <[u8; 32]>::from(Sha256::digest(share)) == header[index])
+		}
+	}
+
+	#[test]
+	fn test_sample_count_reaches_confidence() -> Result<(), anyhow::Error> {
+		assert_eq!(min_unavailable_fraction(2), 1.0);
+		assert_eq!(samples_for_confidence(2, 0.99), 1);
+
+		for width in [4, 16, 128, 256] {
+			let samples = samples_for_confidence(width, 0.99);
+			assert!(confidence_of_samples(width, samples) >= 0.99);
+			assert!(confidence_of_samples(width, samples - 1) < 0.99);
+		}
+		// a quarter of a large square must be withheld, so each sample detects it with 1/4 odds
+		assert_eq!(samples_for_confidence(256, 0.99), 16);
+
+		let sampler = DataAvailabilitySampler::try_new(
+			SyntheticSampler::new(SyntheticSquare::new(4)),
+			20,
+			0.99,
+		)?;
+		assert_eq!(sampler.sample_count(256), 20);
+		assert_eq!(sampler.sample_count(4), 16);
+
+		assert!(DataAvailabilitySampler::try_new(
+			SyntheticSampler::new(SyntheticSquare::new(4)),
+			20,
+			1.0,
+		)
+		.is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_coordinates_are_distinct() -> Result<(), anyhow::Error> {
+		let sampler = DataAvailabilitySampler::try_new(
+			SyntheticSampler::new(SyntheticSquare::new(16)),
+			64,
+			0.99,
+		)?;
+		let mut rng = StdRng::seed_from_u64(0);
+		let coordinates = sampler.coordinates(16, &mut rng);
+
+		assert_eq!(coordinates.len(), 64);
+		assert_eq!(coordinates.iter().collect::<BTreeSet<_>>().len(), 64);
+		assert!(coordinates.iter().all(|c| c.row < 16 && c.column < 16));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_available_square_passes() -> Result<(), anyhow::Error> {
+		let sampler = DataAvailabilitySampler::try_new(
+			SyntheticSampler::new(SyntheticSquare::new(16)),
+			16,
+			0.99,
+		)?;
+
+		let report = sampler.sample(1).await?;
+		assert!(report.is_available());
+		assert_eq!(report.sampled.len(), sampler.sample_count(16));
+		assert!(report.confidence() >= 0.99);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_withheld_square_is_detected() -> Result<(), anyhow::Error> {
+		let mut square = SyntheticSquare::new(16);
+		square.withhold_unrecoverable();
+		let sampler = DataAvailabilitySampler::try_new(SyntheticSampler::new(square), 16, 0.999)?;
+
+		// with 99.9% confidence per run, every seeded run detects the withholding
+		let header = sampler.sampler.header(1).await?;
+		for seed in 0..20 {
+			let coordinates = sampler.coordinates(16, &mut StdRng::seed_from_u64(seed));
+			let report = sampler.sample_coordinates(&header, coordinates).await?;
+			assert!(!report.is_available());
+			assert!(report.unavailable.iter().all(|c| c.row <= 8 && c.column <= 8));
+			assert_eq!(report.confidence(), 0.0);
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tampered_share_is_detected() -> Result<(), anyhow::Error> {
+		let mut square = SyntheticSquare::new(4);
+		square.shares[5][0] ^= 1;
+		let sampler = DataAvailabilitySampler::try_new(SyntheticSampler::new(square), 16, 0.99)?;
+
+		let header = sampler.sampler.header(1).await?;
+		let tampered = Coordinate { row: 1, column: 1 };
+		let report = sampler.sample_coordinates(&header, vec![tampered]).await?;
+		assert_eq!(report.unavailable, vec![tampered]);
+
+		let outside = Coordinate { row: 4, column: 0 };
+		assert!(sampler.sample_coordinates(&header, vec![outside]).await.is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_samples_each_block_once() -> Result<(), anyhow::Error> {
+		let sampler = DataAvailabilitySampler::try_new(
+			SyntheticSampler::new(SyntheticSquare::new(4)),
+			16,
+			0.99,
+		)?;
+		let verifier = DasVerifier::new(AcceptAll, sampler);
+
+		for blob in [&b"first"[..], &b"second"[..], &b"third"[..]] {
+			assert!(verifier.verify(VerificationMode::MOfN, blob, 1).await?);
+		}
+		assert!(verifier.verify(VerificationMode::MOfN, b"other block", 2).await?);
+		assert_eq!(verifier.sampler.sampler.headers.load(Ordering::SeqCst), 2);
+
+		Ok(())
+	}
+}
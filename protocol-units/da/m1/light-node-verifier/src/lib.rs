@@ -1,3 +1,4 @@
+pub mod das;
 pub mod v1;
 
 pub use m1_da_light_node_grpc::*;
@@ -1,6 +1,9 @@
 use crate::Verifier;
 use celestia_rpc::{BlobClient, Client, HeaderClient};
-use celestia_types::{nmt::Namespace, Blob};
+use celestia_types::{
+	nmt::{Namespace, NamespaceId, NamespaceProof, NamespacedHash},
+	Blob, Share,
+};
 use m1_da_light_node_grpc::VerificationMode;
 use std::sync::Arc;
 
@@ -26,11 +29,11 @@ impl Verifier for V1Verifier {
 		// wait for the header to be at the correct height
 		self.client.header_wait_for_height(height).await?;
 
-		// get the root
+		// get the roots
 		let dah = self.client.header_get_by_height(height).await?.dah;
-		let root_hash = dah.row_root(0).ok_or(anyhow::anyhow!("No root hash found"))?;
+		let row_roots: Vec<_> = (0..).map_while(|row| dah.row_root(row)).collect();
 
-		// get the proof
+		// get the proofs, one for each row the blob spans
 		let proofs = self
 			.client
 			.blob_get_proof(height, self.namespace.clone(), celestia_blob.commitment)
@@ -40,11 +43,7 @@ impl Verifier for V1Verifier {
 		let leaves = celestia_blob.to_shares()?;
 
 		// check if included
-		for proof in proofs.iter() {
-			proof
-				.verify_complete_namespace(&root_hash, &leaves, self.namespace.into())
-				.map_err(|e| anyhow::anyhow!("Failed to verify proof: {:?}", e))?;
-		}
+		verify_blob_shares(&row_roots, self.namespace, &leaves, &proofs)?;
 
 		Ok(true)
 	}
@@ -77,6 +76,82 @@ impl Verifier for V1Verifier {
 	}
 }
 
+/// Verifies the shares of a blob against the row roots of the extended data square.
+///
+/// A blob spans consecutive rows within the rows its namespace occupies and has one proof for each
+/// row it spans, in order. The proofs are checked against each possible run of those rows.
+pub fn verify_blob_shares(
+	row_roots: &[NamespacedHash],
+	namespace: Namespace,
+	shares: &[Share],
+	proofs: &[NamespaceProof],
+) -> Result<(), anyhow::Error> {
+	let namespace_id: NamespaceId = namespace.into();
+	let rows: Vec<usize> = row_roots
+		.iter()
+		.enumerate()
+		.filter(|(_, root)| {
+			root.min_namespace() <= namespace_id && namespace_id <= root.max_namespace()
+		})
+		.map(|(row, _)| row)
+		.collect();
+
+	if rows.is_empty() {
+		anyhow::bail!("Namespace {:?} is in none of the rows", namespace);
+	}
+	if proofs.is_empty() || proofs.len() > rows.len() {
+		anyhow::bail!(
+			"Expected between 1 and {} proofs for the namespace, got {}",
+			rows.len(),
+			proofs.len()
+		);
+	}
+
+	let mut last_error = None;
+	for spanned in rows.windows(proofs.len()) {
+		match verify_spanned_rows(row_roots, spanned, namespace_id, shares, proofs) {
+			Ok(()) => return Ok(()),
+			Err(e) => last_error = Some(e),
+		}
+	}
+	Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No rows to verify the proofs against")))
+}
+
+/// Verifies consecutive proofs against consecutive rows, consuming the shares in order.
+fn verify_spanned_rows(
+	row_roots: &[NamespacedHash],
+	rows: &[usize],
+	namespace_id: NamespaceId,
+	shares: &[Share],
+	proofs: &[NamespaceProof],
+) -> Result<(), anyhow::Error> {
+	// the extended rows are twice as wide as the original data the blob is in
+	let width = row_roots.len() / 2;
+	let mut remaining = shares;
+
+	for (i, (row, proof)) in rows.iter().zip(proofs).enumerate() {
+		let (start, end) = (proof.start_idx() as usize, proof.end_idx() as usize);
+		// a blob fills the rows between its first and last row
+		if (i > 0 && start != 0) || (i + 1 < proofs.len() && end != width) || end <= start {
+			anyhow::bail!("Proof for shares {}..{} of row {} is not contiguous", start, end, row);
+		}
+		if end - start > remaining.len() {
+			anyhow::bail!("Proofs cover more shares than the blob has");
+		}
+
+		let (row_shares, rest) = remaining.split_at(end - start);
+		proof
+			.verify_range(&row_roots[*row], row_shares, namespace_id)
+			.map_err(|e| anyhow::anyhow!("Failed to verify proof for row {}: {:?}", row, e))?;
+		remaining = rest;
+	}
+
+	if !remaining.is_empty() {
+		anyhow::bail!("{} shares of the blob are not covered by the proofs", remaining.len());
+	}
+	Ok(())
+}
+
 #[cfg(all(test, feature = "integration-tests"))]
 mod tests {
 	use super::*;
@@ -166,3 +241,117 @@ mod tests {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use celestia_types::nmt::Nmt;
+
+	/// The width of the original data square of the fixture.
+	const WIDTH: usize = 4;
+
+	fn namespace(id: u8) -> Result<Namespace, anyhow::Error> {
+		Ok(Namespace::new_v0(&[id])?)
+	}
+
+	fn shares(namespace: Namespace, size: usize, fill: u8) -> Result<Vec<Share>, anyhow::Error> {
+		Ok(Blob::new(namespace, vec![fill; size])?.to_shares()?)
+	}
+
+	fn push_parity(tree: &mut Nmt) -> Result<(), anyhow::Error> {
+		tree.push_leaf(&[0xff; 512], Namespace::PARITY_SHARE.into())
+			.map_err(|e| anyhow::anyhow!("Failed to push parity share: {}", e))
+	}
+
+	/// The row trees of an extended data square, built like Celestia's row trees.
+	struct Square {
+		trees: Vec<Nmt>,
+	}
+
+	impl Square {
+		/// Lays out the shares row by row and extends each row with parity shares.
+		fn new(shares: &[Share]) -> Result<Self, anyhow::Error> {
+			let mut trees = Vec::new();
+			for row in shares.chunks(WIDTH) {
+				let mut tree = Nmt::new();
+				for share in row {
+					tree.push_leaf(share.as_ref(), share.namespace().into())
+						.map_err(|e| anyhow::anyhow!("Failed to push share: {}", e))?;
+				}
+				for _ in 0..WIDTH {
+					push_parity(&mut tree)?;
+				}
+				trees.push(tree);
+			}
+			// the parity rows hold no namespaced data
+			for _ in 0..WIDTH {
+				let mut tree = Nmt::new();
+				for _ in 0..2 * WIDTH {
+					push_parity(&mut tree)?;
+				}
+				trees.push(tree);
+			}
+			Ok(Self { trees })
+		}
+
+		fn row_roots(&mut self) -> Vec<NamespacedHash> {
+			self.trees.iter_mut().map(|tree| tree.root()).collect()
+		}
+
+		fn prove(&mut self, row: usize, shares: std::ops::Range<usize>) -> NamespaceProof {
+			self.trees[row].get_range_with_proof(shares).1.into()
+		}
+	}
+
+	/// The namespace, shares and row proofs of a blob, and the row roots of its square.
+	type Fixture = (Namespace, Vec<Share>, Vec<NamespaceProof>, Vec<NamespacedHash>);
+
+	/// Lays out a blob of seven shares after two shares of a smaller namespace, so that it spans
+	/// shares 2..4 of row 0, all of row 1 and share 0 of row 2.
+	fn fixture() -> Result<Fixture, anyhow::Error> {
+		let blob_namespace = namespace(2)?;
+		let before = shares(namespace(1)?, 800, 1)?;
+		let blob = shares(blob_namespace, 3000, 2)?;
+		let after = shares(namespace(3)?, 3000, 3)?;
+		assert_eq!((before.len(), blob.len(), after.len()), (2, 7, 7));
+
+		let mut square = Square::new(&[before, blob.clone(), after].concat())?;
+		let proofs = vec![square.prove(0, 2..4), square.prove(1, 0..4), square.prove(2, 0..1)];
+		Ok((blob_namespace, blob, proofs, square.row_roots()))
+	}
+
+	#[test]
+	fn test_multi_row_blob_verifies() -> Result<(), anyhow::Error> {
+		let (namespace, blob, proofs, row_roots) = fixture()?;
+
+		verify_blob_shares(&row_roots, namespace, &blob, &proofs)?;
+
+		// checking every proof against the first row, as before, does not verify the blob
+		let first_row = vec![row_roots[0].clone(); row_roots.len()];
+		assert!(verify_blob_shares(&first_row, namespace, &blob, &proofs).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_misplaced_proofs_do_not_verify() -> Result<(), anyhow::Error> {
+		let (blob_namespace, blob, proofs, row_roots) = fixture()?;
+
+		// a missing row leaves shares unproven
+		assert!(verify_blob_shares(&row_roots, blob_namespace, &blob, &proofs[..2]).is_err());
+
+		// rows out of order
+		let swapped = vec![proofs[1].clone(), proofs[0].clone(), proofs[2].clone()];
+		assert!(verify_blob_shares(&row_roots, blob_namespace, &blob, &swapped).is_err());
+
+		// other data of the same size
+		let other = shares(blob_namespace, 3000, 4)?;
+		assert!(verify_blob_shares(&row_roots, blob_namespace, &other, &proofs).is_err());
+
+		// a namespace which is in none of the rows
+		assert!(verify_blob_shares(&row_roots, namespace(9)?, &blob, &proofs).is_err());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,120 @@
+//! Compares the blob codecs on blocks of transfer transactions.
+//!
+//! The transactions are shaped like the JSON serialized Aptos transactions which the full node
+//! submits: a small set of senders calling `0x1::aptos_account::transfer`, with incompressible
+//! keys and signatures.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use m1_da_light_node::v1::codec::{dictionary_id, train_dictionary, BlobCodec, Codec};
+use movement_types::{Block, Transaction};
+use sha2::{Digest, Sha256};
+
+const ACCOUNTS: u64 = 64;
+
+fn hex_hash(parts: &[&[u8]]) -> String {
+	let mut hasher = Sha256::new();
+	for part in parts {
+		hasher.update(part);
+	}
+	format!("0x{}", hex::encode(hasher.finalize()))
+}
+
+fn transfer_transaction(i: u64) -> Transaction {
+	let sender = i % ACCOUNTS;
+	let sequence_number = i / ACCOUNTS;
+	let recipient = hex_hash(&[b"recipient", &(i % 1000).to_le_bytes()]);
+	let transaction = serde_json::json!({
+		"raw_txn": {
+			"sender": hex_hash(&[b"sender", &sender.to_le_bytes()]),
+			"sequence_number": sequence_number,
+			"payload": { "EntryFunction": {
+				"module": { "address": "0x1", "name": "aptos_account" },
+				"function": "transfer",
+				"ty_args": [],
+				"args": [
+					hex::decode(&recipient[2..]).unwrap_or_default(),
+					(1_000 + i % 10_000).to_le_bytes().to_vec(),
+				],
+			}},
+			"max_gas_amount": 5_000,
+			"gas_unit_price": 100,
+			"expiration_timestamp_secs": 1_720_000_000 + i,
+			"chain_id": 27,
+		},
+		"authenticator": { "Ed25519": {
+			"public_key": hex_hash(&[b"public_key", &sender.to_le_bytes()]),
+			"signature": format!(
+				"{}{}",
+				hex_hash(&[b"signature", &i.to_le_bytes()]),
+				&hex_hash(&[b"signature", &i.to_be_bytes()])[2..]
+			),
+		}},
+	});
+	let data = serde_json::to_vec(&transaction).unwrap_or_default();
+	Transaction::new(data, sequence_number)
+}
+
+fn block_bytes(offset: u64, size: u64) -> Vec<u8> {
+	let transactions = (offset..offset + size).map(transfer_transaction).collect();
+	let block = Block::new(Default::default(), vec![0; 32], transactions);
+	bcs::to_bytes(&block).unwrap_or_default()
+}
+
+fn codecs() -> Vec<(String, BlobCodec)> {
+	// the dictionary is trained on earlier blocks than the ones compressed
+	let samples: Vec<_> = (0..200).map(|i| block_bytes(i * 10, 10)).collect();
+	let dictionary = train_dictionary(&samples, 16 * 1024).expect("dictionary training failed");
+	let dictionary_id = dictionary_id(&dictionary).expect("dictionary has no id");
+
+	[
+		("none".to_string(), Codec::None),
+		("zstd-0".to_string(), Codec::Zstd { level: 0 }),
+		("zstd-3".to_string(), Codec::Zstd { level: 3 }),
+		("zstd-19".to_string(), Codec::Zstd { level: 19 }),
+		("zstd-dictionary-3".to_string(), Codec::ZstdDictionary { level: 3, dictionary_id }),
+	]
+	.into_iter()
+	.map(|(name, codec)| {
+		let codec = BlobCodec::try_new(codec, vec![dictionary.clone()]).expect("invalid codec");
+		(name, codec)
+	})
+	.collect()
+}
+
+fn bench_codecs(c: &mut Criterion) {
+	let codecs = codecs();
+
+	for block_size in [10, 100, 1000] {
+		let block = block_bytes(1_000_000, block_size);
+
+		let mut encode = c.benchmark_group(format!("encode_block_of_{}", block_size));
+		encode.throughput(Throughput::Bytes(block.len() as u64));
+		for (name, codec) in &codecs {
+			let blob = codec.encode(&block).expect("encoding failed");
+			println!(
+				"{} transactions with {}: {} -> {} bytes ({:.2}x)",
+				block_size,
+				name,
+				block.len(),
+				blob.len(),
+				block.len() as f64 / blob.len() as f64
+			);
+			encode.bench_with_input(BenchmarkId::from_parameter(name), &block, |b, block| {
+				b.iter(|| codec.encode(block).expect("encoding failed"));
+			});
+		}
+		encode.finish();
+
+		let mut decode = c.benchmark_group(format!("decode_block_of_{}", block_size));
+		decode.throughput(Throughput::Bytes(block.len() as u64));
+		for (name, codec) in &codecs {
+			let blob = codec.encode(&block).expect("encoding failed");
+			decode.bench_with_input(BenchmarkId::from_parameter(name), &blob, |b, blob| {
+				b.iter(|| codec.decode(blob).expect("decoding failed"));
+			});
+		}
+		decode.finish();
+	}
+}
+
+criterion_group!(benches, bench_codecs);
+criterion_main!(benches);
@@ -0,0 +1,259 @@
+use anyhow::Context;
+use m1_da_light_node_util::config::Config;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{
+	service::Interceptor,
+	transport::{Certificate, Identity, ServerTlsConfig},
+	Request, Status,
+};
+use tracing::warn;
+
+/// The roles a caller of the light node may be authenticated with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+	/// May read and write blobs and submit transactions.
+	Client,
+	/// May additionally call admin RPCs, such as updating the verification parameters.
+	Admin,
+}
+
+/// Authenticates the callers of the light node by bearer token or client certificate.
+///
+/// Authentication is disabled unless tokens or a client CA are configured,
+/// in which case every caller is an admin.
+#[derive(Debug, Clone, Default)]
+pub struct Authenticator {
+	client_tokens: Arc<HashSet<String>>,
+	admin_tokens: Arc<HashSet<String>>,
+	admin_cert_fingerprints: Arc<HashSet<Vec<u8>>>,
+	mtls: bool,
+}
+
+impl Authenticator {
+	pub fn new(
+		client_tokens: HashSet<String>,
+		admin_tokens: HashSet<String>,
+		admin_cert_fingerprints: HashSet<Vec<u8>>,
+		mtls: bool,
+	) -> Self {
+		Self {
+			client_tokens: Arc::new(client_tokens),
+			admin_tokens: Arc::new(admin_tokens),
+			admin_cert_fingerprints: Arc::new(admin_cert_fingerprints),
+			mtls,
+		}
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.mtls || !self.client_tokens.is_empty() || !self.admin_tokens.is_empty()
+	}
+
+	/// Gets the role of the caller, or None if it could not be authenticated.
+	pub fn authenticate<T>(&self, request: &Request<T>) -> Option<Role> {
+		if !self.is_enabled() {
+			return Some(Role::Admin);
+		}
+
+		let token_role = bearer_token(request).and_then(|token| {
+			if self.admin_tokens.contains(token) {
+				Some(Role::Admin)
+			} else if self.client_tokens.contains(token) {
+				Some(Role::Client)
+			} else {
+				None
+			}
+		});
+
+		token_role.max(self.certificate_role(request))
+	}
+
+	/// Gets the role of a caller which presented a client certificate verified by the TLS layer.
+	fn certificate_role<T>(&self, request: &Request<T>) -> Option<Role> {
+		if !self.mtls {
+			return None;
+		}
+		let certificates = request.peer_certs()?;
+		let certificate = certificates.first()?;
+		if self.admin_cert_fingerprints.contains(&fingerprint(certificate)) {
+			Some(Role::Admin)
+		} else {
+			Some(Role::Client)
+		}
+	}
+}
+
+impl Interceptor for Authenticator {
+	/// Authenticates the caller and records its role in the request extensions.
+	fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+		let role = self
+			.authenticate(&request)
+			.ok_or_else(|| Status::unauthenticated("Missing or invalid credentials"))?;
+		request.extensions_mut().insert(role);
+		Ok(request)
+	}
+}
+
+/// Whether the caller was authenticated with at least the given role.
+///
+/// Requests which did not pass through the [Authenticator] have no role.
+pub fn has_role<T>(request: &Request<T>, role: Role) -> bool {
+	request.extensions().get::<Role>().is_some_and(|caller| *caller >= role)
+}
+
+fn bearer_token<T>(request: &Request<T>) -> Option<&str> {
+	request.metadata().get("authorization")?.to_str().ok()?.strip_prefix("Bearer ")
+}
+
+/// Gets the SHA-256 fingerprint of a DER encoded certificate.
+pub fn fingerprint(certificate: &Certificate) -> Vec<u8> {
+	Sha256::digest(certificate.get_ref()).to_vec()
+}
+
+/// Parses a hex fingerprint, as printed by `openssl x509 -fingerprint -sha256` or without colons.
+pub fn try_parse_fingerprint(fingerprint: &str) -> Result<Vec<u8>, anyhow::Error> {
+	hex::decode(fingerprint.replace(':', "").to_lowercase())
+		.map_err(|e| anyhow::anyhow!("Invalid certificate fingerprint {}: {}", fingerprint, e))
+}
+
+/// The TLS and authentication settings of the light node server.
+#[derive(Debug, Clone, Default)]
+pub struct ServerAuth {
+	pub tls: Option<ServerTlsConfig>,
+	pub authenticator: Authenticator,
+}
+
+impl ServerAuth {
+	pub fn try_from_config(config: &Config) -> Result<Self, anyhow::Error> {
+		let config = config.m1_da_light_node_config();
+		let client_tokens: HashSet<String> =
+			config.m1_da_light_node_client_tokens.iter().cloned().collect();
+		let admin_tokens: HashSet<String> =
+			config.m1_da_light_node_admin_tokens.iter().cloned().collect();
+		let admin_cert_fingerprints = config
+			.m1_da_light_node_admin_cert_fingerprints
+			.iter()
+			.map(|fingerprint| try_parse_fingerprint(fingerprint))
+			.collect::<Result<HashSet<_>, _>>()?;
+
+		let tls =
+			match (&config.m1_da_light_node_tls_cert_path, &config.m1_da_light_node_tls_key_path) {
+				(Some(cert_path), Some(key_path)) => {
+					let cert = std::fs::read(cert_path)
+						.with_context(|| format!("Failed to read TLS certificate {}", cert_path))?;
+					let key = std::fs::read(key_path)
+						.with_context(|| format!("Failed to read TLS key {}", key_path))?;
+					let tls = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+					match &config.m1_da_light_node_tls_client_ca_path {
+						Some(ca_path) => {
+							let ca = std::fs::read(ca_path).with_context(|| {
+								format!("Failed to read TLS client CA certificate {}", ca_path)
+							})?;
+							// callers with tokens do not need a client certificate
+							let tokens = !client_tokens.is_empty() || !admin_tokens.is_empty();
+							Some(
+								tls.client_ca_root(Certificate::from_pem(ca))
+									.client_auth_optional(tokens),
+							)
+						}
+						None => Some(tls),
+					}
+				}
+				(None, None) => None,
+				_ => anyhow::bail!("Both the TLS certificate and key must be set to enable TLS"),
+			};
+
+		let mtls = config.m1_da_light_node_tls_client_ca_path.is_some();
+		if mtls && tls.is_none() {
+			anyhow::bail!("A TLS client CA requires the TLS certificate and key to be set");
+		}
+		if tls.is_none() && (!client_tokens.is_empty() || !admin_tokens.is_empty()) {
+			warn!("Bearer tokens are configured without TLS and will be sent in plain text");
+		}
+
+		Ok(Self {
+			tls,
+			authenticator: Authenticator::new(
+				client_tokens,
+				admin_tokens,
+				admin_cert_fingerprints,
+				mtls,
+			),
+		})
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	fn authenticator() -> Authenticator {
+		Authenticator::new(
+			HashSet::from(["client".to_string()]),
+			HashSet::from(["admin".to_string()]),
+			HashSet::new(),
+			false,
+		)
+	}
+
+	fn request_with_token(token: &str) -> Result<Request<()>, anyhow::Error> {
+		let mut request = Request::new(());
+		request
+			.metadata_mut()
+			.insert("authorization", format!("Bearer {}", token).parse()?);
+		Ok(request)
+	}
+
+	#[test]
+	fn test_authenticates_by_token() -> Result<(), anyhow::Error> {
+		let authenticator = authenticator();
+
+		assert_eq!(authenticator.authenticate(&request_with_token("client")?), Some(Role::Client));
+		assert_eq!(authenticator.authenticate(&request_with_token("admin")?), Some(Role::Admin));
+		assert_eq!(authenticator.authenticate(&request_with_token("other")?), None);
+		assert_eq!(authenticator.authenticate(&Request::new(())), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_disabled_authenticator_allows_admin() -> Result<(), anyhow::Error> {
+		let authenticator = Authenticator::default();
+		assert!(!authenticator.is_enabled());
+		assert_eq!(authenticator.authenticate(&Request::new(())), Some(Role::Admin));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_admin_rpcs_require_admin_role() -> Result<(), anyhow::Error> {
+		let mut authenticator = authenticator();
+
+		let client = authenticator.call(request_with_token("client")?)?;
+		assert!(has_role(&client, Role::Client));
+		assert!(!has_role(&client, Role::Admin));
+
+		let admin = authenticator.call(request_with_token("admin")?)?;
+		assert!(has_role(&admin, Role::Admin));
+
+		// callers without credentials are rejected by the interceptor
+		let rejected = authenticator.call(Request::new(())).unwrap_err();
+		assert_eq!(rejected.code(), tonic::Code::Unauthenticated);
+
+		// requests which did not pass through the interceptor have no role
+		assert!(!has_role(&Request::new(()), Role::Client));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_fingerprint() -> Result<(), anyhow::Error> {
+		assert_eq!(try_parse_fingerprint("AB:cd:01")?, vec![0xab, 0xcd, 0x01]);
+		assert_eq!(try_parse_fingerprint("abcd01")?, vec![0xab, 0xcd, 0x01]);
+		assert!(try_parse_fingerprint("xyz").is_err());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,252 @@
+use m1_da_light_node_grpc::VerificationMode;
+use m1_da_light_node_util::config::Config;
+use rocksdb::{IteratorMode, Options, DB};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// A blob is identified by the height it was included at and its commitment, and verified
+/// separately in each verification mode.
+pub type VerificationKey = (u64, [u8; 32], VerificationMode);
+
+/// Positive verification results persisted to RocksDB, bounded to the same capacity as the cache.
+#[derive(Debug)]
+struct Persistence {
+	db: DB,
+	/// The number of persisted results, which only this cache writes.
+	count: Mutex<usize>,
+}
+
+/// A bounded cache of blob verification results.
+///
+/// Concurrent reads of the same blob wait on a single verification. Errors are not cached, so a
+/// blob is verified again once its verification errored. Negative results are only kept in memory,
+/// so a blob rejected before a restart is verified again after it. When the cache is full, the
+/// results at the lowest heights are evicted first.
+#[derive(Debug, Clone)]
+pub struct VerificationCache {
+	capacity: usize,
+	entries: Arc<Mutex<BTreeMap<VerificationKey, Arc<OnceCell<bool>>>>>,
+	persistence: Option<Arc<Persistence>>,
+}
+
+impl VerificationCache {
+	/// Creates a cache of at most `capacity` results, which caches nothing if the capacity is zero.
+	pub fn new(capacity: usize) -> Self {
+		Self { capacity, entries: Arc::new(Mutex::new(BTreeMap::new())), persistence: None }
+	}
+
+	/// Creates a cache which also persists its results to the RocksDB database at the path.
+	pub fn try_new_persistent(capacity: usize, path: &str) -> Result<Self, anyhow::Error> {
+		let mut options = Options::default();
+		options.create_if_missing(true);
+		let db = DB::open(&options, path)
+			.map_err(|e| anyhow::anyhow!("Failed to open verification cache {}: {}", path, e))?;
+		let count = db.iterator(IteratorMode::Start).count();
+
+		let persistence = Persistence { db, count: Mutex::new(count) };
+		Ok(Self { persistence: Some(Arc::new(persistence)), ..Self::new(capacity) })
+	}
+
+	pub fn try_from_config(config: &Config) -> Result<Self, anyhow::Error> {
+		let capacity = config.m1_da_light_node_verification_cache_size() as usize;
+		match config.m1_da_light_node_verification_cache_path() {
+			Some(path) => Self::try_new_persistent(capacity, &path),
+			None => Ok(Self::new(capacity)),
+		}
+	}
+
+	/// Gets the cached result for the blob, or verifies it and caches the result.
+	pub async fn get_or_verify<F, Fut>(
+		&self,
+		key: VerificationKey,
+		verify: F,
+	) -> Result<bool, anyhow::Error>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = Result<bool, anyhow::Error>>,
+	{
+		if self.capacity == 0 {
+			return verify().await;
+		}
+
+		let cell = self.cell(key)?;
+		let verified = cell
+			.get_or_try_init(|| async {
+				if let Some(verified) = self.load(key).await? {
+					return Ok(verified);
+				}
+				let verified = verify().await?;
+				self.store(key, verified).await?;
+				Ok::<_, anyhow::Error>(verified)
+			})
+			.await?;
+		Ok(*verified)
+	}
+
+	/// Gets the number of results held in memory, including verifications in progress.
+	pub fn len(&self) -> usize {
+		self.entries.lock().map(|entries| entries.len()).unwrap_or_default()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn cell(&self, key: VerificationKey) -> Result<Arc<OnceCell<bool>>, anyhow::Error> {
+		let mut entries = self
+			.entries
+			.lock()
+			.map_err(|_| anyhow::anyhow!("Verification cache poisoned"))?;
+		let cell = entries.entry(key).or_default().clone();
+		while entries.len() > self.capacity {
+			entries.pop_first();
+		}
+		Ok(cell)
+	}
+
+	async fn load(&self, key: VerificationKey) -> Result<Option<bool>, anyhow::Error> {
+		let persistence = match &self.persistence {
+			Some(persistence) => persistence.clone(),
+			None => return Ok(None),
+		};
+		tokio::task::spawn_blocking(move || {
+			let value = persistence.db.get(encode_key(key))?;
+			Ok(value.map(|value| value == [1]))
+		})
+		.await?
+	}
+
+	async fn store(&self, key: VerificationKey, verified: bool) -> Result<(), anyhow::Error> {
+		let persistence = match &self.persistence {
+			Some(persistence) if verified => persistence.clone(),
+			_ => return Ok(()),
+		};
+		let capacity = self.capacity;
+		tokio::task::spawn_blocking(move || {
+			let mut count = persistence
+				.count
+				.lock()
+				.map_err(|_| anyhow::anyhow!("Verification cache poisoned"))?;
+			let encoded = encode_key(key);
+			if persistence.db.get(&encoded)?.is_none() {
+				*count += 1;
+			}
+			persistence.db.put(&encoded, [1])?;
+
+			// keys sort by height, so the lowest heights are pruned first
+			let excess: Vec<_> = persistence
+				.db
+				.iterator(IteratorMode::Start)
+				.take(count.saturating_sub(capacity))
+				.map(|item| item.map(|(key, _)| key))
+				.collect::<Result<_, _>>()?;
+			for key in excess {
+				persistence.db.delete(key)?;
+				*count -= 1;
+			}
+			Ok(())
+		})
+		.await?
+	}
+}
+
+fn encode_key((height, commitment, mode): VerificationKey) -> Vec<u8> {
+	[&height.to_be_bytes()[..], &commitment[..], &i32::from(mode).to_be_bytes()[..]].concat()
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	fn key(height: u64) -> VerificationKey {
+		(height, [height as u8; 32], VerificationMode::MOfN)
+	}
+
+	#[tokio::test]
+	async fn test_verifies_each_blob_once() -> Result<(), anyhow::Error> {
+		let cache = VerificationCache::new(16);
+		let verifications = AtomicUsize::new(0);
+		let verify = || async {
+			verifications.fetch_add(1, Ordering::SeqCst);
+			tokio::task::yield_now().await;
+			Ok(true)
+		};
+
+		let results =
+			futures::future::join_all((0..8).map(|_| cache.get_or_verify(key(1), verify)))
+				.await
+				.into_iter()
+				.collect::<Result<Vec<_>, _>>()?;
+		assert_eq!(results, vec![true; 8]);
+		assert!(cache.get_or_verify(key(1), verify).await?);
+		assert_eq!(verifications.load(Ordering::SeqCst), 1);
+
+		// errors are not cached
+		assert!(cache
+			.get_or_verify(key(2), || async { anyhow::bail!("unreachable") })
+			.await
+			.is_err());
+		assert!(!cache.get_or_verify(key(2), || async { Ok(false) }).await?);
+
+		// each verification mode has its own result
+		let (height, commitment, _) = key(1);
+		let cowboy = (height, commitment, VerificationMode::Cowboy);
+		assert!(!cache.get_or_verify(cowboy, || async { Ok(false) }).await?);
+		assert!(cache.get_or_verify(key(1), verify).await?);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_evicts_lowest_heights() -> Result<(), anyhow::Error> {
+		let cache = VerificationCache::new(2);
+		for height in [3, 1, 2] {
+			cache.get_or_verify(key(height), || async { Ok(true) }).await?;
+		}
+		assert_eq!(cache.len(), 2);
+
+		// the lowest height was evicted and is verified again
+		assert!(!cache.get_or_verify(key(1), || async { Ok(false) }).await?);
+		assert!(cache.get_or_verify(key(3), || async { Ok(false) }).await?);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_persists_results() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().to_str().ok_or(anyhow::anyhow!("Invalid path"))?;
+
+		{
+			let cache = VerificationCache::try_new_persistent(2, path)?;
+			for height in 1..=4 {
+				cache.get_or_verify(key(height), || async { Ok(height != 2) }).await?;
+			}
+		}
+
+		let cache = VerificationCache::try_new_persistent(2, path)?;
+		let unreachable = || async { anyhow::bail!("unreachable") };
+		assert!(cache.get_or_verify(key(3), unreachable).await?);
+		assert!(cache.get_or_verify(key(4), unreachable).await?);
+		// negative results are not persisted, so the rejected blob is verified again
+		assert!(cache.get_or_verify(key(2), || async { Ok(true) }).await?);
+		// only the capacity is persisted, so the lowest height was pruned
+		assert!(!cache.get_or_verify(key(1), || async { Ok(false) }).await?);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_zero_capacity_caches_nothing() -> Result<(), anyhow::Error> {
+		let cache = VerificationCache::new(0);
+		assert!(cache.get_or_verify(key(1), || async { Ok(true) }).await?);
+		assert!(!cache.get_or_verify(key(1), || async { Ok(false) }).await?);
+		assert!(cache.is_empty());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,367 @@
+use crate::v1::namespace::{Namespaces, DEFAULT_NAMESPACE};
+use m1_da_light_node_util::config::Config;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Arc;
+
+/// Marks blob data which starts with a codec header.
+pub const MAGIC: [u8; 4] = *b"MVBC";
+
+/// The version of the codec header.
+pub const VERSION: u8 = 1;
+
+/// The magic number of a zstd frame, which starts the blobs written before the codec header.
+pub const ZSTD_FRAME_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The largest blob data which will be decoded, to guard against decompression bombs.
+pub const MAX_DECODED_SIZE: u64 = 256 * 1024 * 1024;
+
+const CODEC_NONE: u8 = 0;
+const CODEC_ZSTD: u8 = 1;
+const CODEC_ZSTD_DICTIONARY: u8 = 2;
+
+/// How blob data is encoded before it is submitted to Celestia.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+	None,
+	Zstd {
+		level: i32,
+	},
+	/// zstd with a trained dictionary, identified by its dictionary id.
+	ZstdDictionary {
+		level: i32,
+		dictionary_id: u32,
+	},
+}
+
+impl Default for Codec {
+	fn default() -> Self {
+		Codec::Zstd { level: 0 }
+	}
+}
+
+/// Encodes blob data with a codec header and decodes blob data encoded with the same codec.
+///
+/// Blobs written before the codec header was introduced are decoded by detecting their codec.
+#[derive(Debug, Clone, Default)]
+pub struct BlobCodec {
+	codec: Codec,
+	dictionaries: Arc<BTreeMap<u32, Vec<u8>>>,
+}
+
+impl BlobCodec {
+	pub fn try_new(codec: Codec, dictionaries: Vec<Vec<u8>>) -> Result<Self, anyhow::Error> {
+		let mut by_id = BTreeMap::new();
+		for dictionary in dictionaries {
+			by_id.insert(dictionary_id(&dictionary)?, dictionary);
+		}
+		if let Codec::ZstdDictionary { dictionary_id, .. } = codec {
+			if !by_id.contains_key(&dictionary_id) {
+				anyhow::bail!("Unknown zstd dictionary {}", dictionary_id);
+			}
+		}
+		Ok(Self { codec, dictionaries: Arc::new(by_id) })
+	}
+
+	/// Creates the codec configured for the namespace with the selector.
+	pub fn try_from_config(config: &Config, selector: &str) -> Result<Self, anyhow::Error> {
+		let dictionary =
+			match config.m1_da_light_node_blob_zstd_dictionary_path() {
+				Some(path) => Some(std::fs::read(&path).map_err(|e| {
+					anyhow::anyhow!("Failed to read zstd dictionary {}: {}", path, e)
+				})?),
+				None => None,
+			};
+		let level = config.m1_da_light_node_blob_zstd_level();
+		let name = if selector == DEFAULT_NAMESPACE {
+			config.m1_da_light_node_blob_codec()
+		} else {
+			config
+				.m1_da_light_node_blob_codecs()
+				.remove(selector)
+				.ok_or(anyhow::anyhow!("Namespace {} has no blob codec configured", selector))?
+		};
+		let codec = match name.as_str() {
+			"none" => Codec::None,
+			"zstd" => Codec::Zstd { level },
+			"zstd-dictionary" => {
+				let dictionary = dictionary.as_ref().ok_or(anyhow::anyhow!(
+					"The zstd-dictionary codec requires a zstd dictionary path"
+				))?;
+				Codec::ZstdDictionary { level, dictionary_id: dictionary_id(dictionary)? }
+			}
+			other => anyhow::bail!("Unknown blob codec {}", other),
+		};
+		Self::try_new(codec, dictionary.into_iter().collect())
+	}
+
+	pub fn codec(&self) -> Codec {
+		self.codec
+	}
+
+	/// Encodes the data with the codec, prefixed by the codec header.
+	pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+		let mut blob = Vec::with_capacity(data.len() + 10);
+		blob.extend_from_slice(&MAGIC);
+		blob.push(VERSION);
+		match self.codec {
+			Codec::None => {
+				blob.push(CODEC_NONE);
+				blob.extend_from_slice(data);
+			}
+			Codec::Zstd { level } => {
+				blob.push(CODEC_ZSTD);
+				zstd::stream::copy_encode(data, &mut blob, level)?;
+			}
+			Codec::ZstdDictionary { level, dictionary_id } => {
+				blob.push(CODEC_ZSTD_DICTIONARY);
+				blob.extend_from_slice(&dictionary_id.to_be_bytes());
+				let mut compressor = zstd::bulk::Compressor::with_dictionary(
+					level,
+					self.dictionary(dictionary_id)?,
+				)?;
+				blob.extend_from_slice(&compressor.compress(data)?);
+			}
+		}
+		Ok(blob)
+	}
+
+	/// Decodes blob data, failing if its header names a codec other than this codec.
+	///
+	/// Data without a header is either a bare zstd frame or was not encoded at all.
+	pub fn decode(&self, blob: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+		let header = match blob.strip_prefix(&MAGIC) {
+			Some(header) => header,
+			None if blob.starts_with(&ZSTD_FRAME_MAGIC) => {
+				return read_bounded(zstd::stream::read::Decoder::with_buffer(blob)?);
+			}
+			None => return Ok(blob.to_vec()),
+		};
+
+		let (version, codec, payload) = match header {
+			[version, codec, payload @ ..] => (*version, *codec, payload),
+			_ => anyhow::bail!("Truncated codec header"),
+		};
+		if version != VERSION {
+			anyhow::bail!("Unsupported codec header version {}", version);
+		}
+
+		match (codec, self.codec) {
+			(CODEC_NONE, Codec::None) => Ok(payload.to_vec()),
+			(CODEC_ZSTD, Codec::Zstd { .. }) => {
+				read_bounded(zstd::stream::read::Decoder::with_buffer(payload)?)
+			}
+			(CODEC_ZSTD_DICTIONARY, Codec::ZstdDictionary { dictionary_id: expected, .. }) => {
+				let (dictionary_id, payload) = match payload {
+					[a, b, c, d, payload @ ..] => (u32::from_be_bytes([*a, *b, *c, *d]), payload),
+					_ => anyhow::bail!("Truncated codec header"),
+				};
+				if dictionary_id != expected {
+					anyhow::bail!(
+						"Blob uses zstd dictionary {}, expected {}",
+						dictionary_id,
+						expected
+					);
+				}
+				let decoder = zstd::stream::read::Decoder::with_dictionary(
+					payload,
+					self.dictionary(dictionary_id)?,
+				)?;
+				read_bounded(decoder)
+			}
+			(CODEC_NONE | CODEC_ZSTD | CODEC_ZSTD_DICTIONARY, expected) => {
+				anyhow::bail!("Blob uses codec {}, expected {:?}", codec, expected)
+			}
+			(other, _) => anyhow::bail!("Unknown codec {}", other),
+		}
+	}
+
+	fn dictionary(&self, dictionary_id: u32) -> Result<&[u8], anyhow::Error> {
+		self.dictionaries
+			.get(&dictionary_id)
+			.map(|dictionary| dictionary.as_slice())
+			.ok_or(anyhow::anyhow!("Unknown zstd dictionary {}", dictionary_id))
+	}
+}
+
+/// The codecs of the namespaces, by namespace selector.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceCodecs {
+	codecs: Arc<BTreeMap<String, BlobCodec>>,
+}
+
+impl NamespaceCodecs {
+	pub fn new(codecs: BTreeMap<String, BlobCodec>) -> Self {
+		Self { codecs: Arc::new(codecs) }
+	}
+
+	/// Creates the codecs configured for the namespaces, failing if one has none configured.
+	pub fn try_from_config(
+		config: &Config,
+		namespaces: &Namespaces,
+	) -> Result<Self, anyhow::Error> {
+		let mut codecs = BTreeMap::new();
+		for (selector, _) in namespaces.iter() {
+			codecs.insert(selector.to_string(), BlobCodec::try_from_config(config, selector)?);
+		}
+		if let Some(selector) = config
+			.m1_da_light_node_blob_codecs()
+			.keys()
+			.find(|selector| !codecs.contains_key(*selector))
+		{
+			anyhow::bail!("A blob codec is configured for unknown namespace {:?}", selector);
+		}
+		Ok(Self::new(codecs))
+	}
+
+	/// Gets the codec of the namespace selected by name.
+	pub fn get(&self, selector: &str) -> Result<&BlobCodec, anyhow::Error> {
+		self.codecs
+			.get(selector)
+			.ok_or_else(|| anyhow::anyhow!("Unknown namespace {:?}", selector))
+	}
+}
+
+fn read_bounded(reader: impl Read) -> Result<Vec<u8>, anyhow::Error> {
+	let mut data = Vec::new();
+	reader.take(MAX_DECODED_SIZE + 1).read_to_end(&mut data)?;
+	if data.len() as u64 > MAX_DECODED_SIZE {
+		anyhow::bail!("Decoded blob exceeds {} bytes", MAX_DECODED_SIZE);
+	}
+	Ok(data)
+}
+
+/// Gets the id of a zstd dictionary. Raw content dictionaries have no id and are not supported.
+pub fn dictionary_id(dictionary: &[u8]) -> Result<u32, anyhow::Error> {
+	zstd::zstd_safe::get_dict_id_from_dict(dictionary)
+		.map(|id| id.get())
+		.ok_or(anyhow::anyhow!("zstd dictionary has no dictionary id"))
+}
+
+/// Trains a zstd dictionary of at most `max_size` bytes on sample blob data.
+pub fn train_dictionary(samples: &[Vec<u8>], max_size: usize) -> Result<Vec<u8>, anyhow::Error> {
+	Ok(zstd::dict::from_samples(samples, max_size)?)
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	fn samples() -> Vec<Vec<u8>> {
+		(0..1000u32)
+			.map(|i| {
+				format!(
+					"{{\"sender\":\"0x{:064x}\",\"sequence_number\":{},\"function\":\"0x1::aptos_account::transfer\"}}",
+					i % 7,
+					i
+				)
+				.into_bytes()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_round_trips_each_codec() -> Result<(), anyhow::Error> {
+		let samples = samples();
+		let dictionary = train_dictionary(&samples, 4096)?;
+		let dictionary_id = dictionary_id(&dictionary)?;
+		let data = samples.concat();
+
+		for codec in [
+			Codec::None,
+			Codec::Zstd { level: 0 },
+			Codec::Zstd { level: 19 },
+			Codec::ZstdDictionary { level: 3, dictionary_id },
+		] {
+			let encoder = BlobCodec::try_new(codec, vec![dictionary.clone()])?;
+			let blob = encoder.encode(&data)?;
+			assert!(blob.starts_with(&MAGIC));
+			assert_eq!(encoder.decode(&blob)?, data);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_decodes_blobs_without_header() -> Result<(), anyhow::Error> {
+		let codec = BlobCodec::default();
+		let data = b"written before the codec header".to_vec();
+
+		let legacy = zstd::encode_all(data.as_slice(), 0)?;
+		assert_eq!(codec.decode(&legacy)?, data);
+		assert_eq!(codec.decode(&data)?, data);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_unknown_dictionary() -> Result<(), anyhow::Error> {
+		let samples = samples();
+		let dictionary = train_dictionary(&samples, 4096)?;
+		let dictionary_id = dictionary_id(&dictionary)?;
+
+		let encoder = BlobCodec::try_new(
+			Codec::ZstdDictionary { level: 3, dictionary_id },
+			vec![dictionary.clone()],
+		)?;
+		let blob = encoder.encode(&samples[0])?;
+		assert!(BlobCodec::default().decode(&blob).is_err());
+
+		assert!(
+			BlobCodec::try_new(Codec::ZstdDictionary { level: 3, dictionary_id }, vec![]).is_err()
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_other_codecs() -> Result<(), anyhow::Error> {
+		let samples = samples();
+		let dictionary = train_dictionary(&samples, 4096)?;
+		let dictionary_id = dictionary_id(&dictionary)?;
+		let data = samples.concat();
+
+		let codecs = [
+			BlobCodec::try_new(Codec::None, vec![])?,
+			BlobCodec::try_new(Codec::Zstd { level: 0 }, vec![])?,
+			BlobCodec::try_new(
+				Codec::ZstdDictionary { level: 3, dictionary_id },
+				vec![dictionary.clone()],
+			)?,
+		];
+		for encoder in &codecs {
+			let blob = encoder.encode(&data)?;
+			for decoder in &codecs {
+				assert_eq!(decoder.decode(&blob).is_ok(), encoder.codec() == decoder.codec());
+			}
+		}
+
+		// the level is not part of the header
+		let blob = BlobCodec::try_new(Codec::Zstd { level: 19 }, vec![])?.encode(&data)?;
+		assert_eq!(codecs[1].decode(&blob)?, data);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_namespace_codecs() -> Result<(), anyhow::Error> {
+		let codecs = NamespaceCodecs::new(BTreeMap::from([
+			(DEFAULT_NAMESPACE.to_string(), BlobCodec::default()),
+			("raw".to_string(), BlobCodec::try_new(Codec::None, vec![])?),
+		]));
+		assert_eq!(codecs.get(DEFAULT_NAMESPACE)?.codec(), Codec::default());
+		assert_eq!(codecs.get("raw")?.codec(), Codec::None);
+		assert!(codecs.get("unknown").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_malformed_headers() {
+		let codec = BlobCodec::default();
+		assert!(codec.decode(&MAGIC).is_err());
+		assert!(codec.decode(&[&MAGIC[..], &[VERSION + 1, CODEC_NONE]].concat()).is_err());
+		assert!(codec.decode(&[&MAGIC[..], &[VERSION, 42]].concat()).is_err());
+	}
+}
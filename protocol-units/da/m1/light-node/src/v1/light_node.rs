@@ -1,8 +1,16 @@
 use m1_da_light_node_grpc::light_node_service_server::{LightNodeService, LightNodeServiceServer};
+use m1_da_light_node_grpc::StatusResponse;
 use m1_da_light_node_util::config::Config;
-use tonic::transport::Server;
+use std::time::Duration;
+use tonic::{server::NamedService, transport::Server};
+use tonic_health::{server::HealthReporter, ServingStatus};
 use tracing::info;
 
+use crate::v1::{auth::ServerAuth, metrics::Metrics, status::is_healthy};
+
+/// How often the status of the light node is reported to the gRPC health service.
+pub const HEALTH_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
 pub trait LightNodeV1Operations: LightNodeService + Send + Sync + Sized + Clone {
 	/// Initializes from environment variables.
 	async fn try_from_config(config: Config) -> Result<Self, anyhow::Error>;
@@ -13,25 +21,79 @@ pub trait LightNodeV1Operations: LightNodeService + Send + Sync + Sized + Clone
 	/// Tries to get the service address
 	fn try_service_address(&self) -> Result<String, anyhow::Error>;
 
+	/// Gets the TLS and authentication settings of the server.
+	fn server_auth(&self) -> &ServerAuth;
+
+	/// Gets the metrics the light node reports into.
+	fn metrics(&self) -> &Metrics;
+
+	/// Tries to get the address to serve the metrics on
+	fn try_metrics_address(&self) -> Result<String, anyhow::Error>;
+
+	/// Gets the status of the Celestia connection, sync and, in sequencer mode, the block proposer.
+	async fn node_status(&self) -> StatusResponse;
+
 	/// Runs the server
 	async fn run_server(&self) -> Result<(), anyhow::Error> {
 		let reflection = tonic_reflection::server::Builder::configure()
 			.register_encoded_file_descriptor_set(m1_da_light_node_grpc::FILE_DESCRIPTOR_SET)
+			.register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
 			.build()?;
 
+		let (health_reporter, health_service) = tonic_health::server::health_reporter();
+
+		let auth = self.server_auth();
+		let mut builder = Server::builder();
+		if let Some(tls) = &auth.tls {
+			builder = builder.tls_config(tls.clone())?;
+		}
+
 		let address = self.try_service_address()?;
 		info!("Server listening on: {}", address);
-		Server::builder()
-			.accept_http1(true)
-			.add_service(LightNodeServiceServer::new(self.clone()))
-			.add_service(reflection)
-			.serve(address.parse()?)
-			.await?;
+		let server = async {
+			builder
+				.accept_http1(true)
+				.add_service(LightNodeServiceServer::with_interceptor(
+					self.clone(),
+					auth.authenticator.clone(),
+				))
+				.add_service(health_service)
+				.add_service(reflection)
+				.serve(address.parse()?)
+				.await?;
+			Ok::<_, anyhow::Error>(())
+		};
+
+		tokio::try_join!(server, self.run_health_reporter(health_reporter))?;
 
 		Ok(())
 	}
 
-	/// Runs the server and the background tasks.
+	/// Reports the status of the light node to the gRPC health service.
+	async fn run_health_reporter(&self, mut reporter: HealthReporter) -> Result<(), anyhow::Error> {
+		loop {
+			let serving_status = if is_healthy(&self.node_status().await) {
+				ServingStatus::Serving
+			} else {
+				ServingStatus::NotServing
+			};
+			// the empty service name reports the health of the server as a whole
+			reporter.set_service_status("", serving_status).await;
+			reporter
+				.set_service_status(LightNodeServiceServer::<Self>::NAME, serving_status)
+				.await;
+
+			tokio::time::sleep(HEALTH_REPORT_INTERVAL).await;
+		}
+	}
+
+	/// Runs the metrics server
+	async fn run_metrics_server(&self) -> Result<(), anyhow::Error> {
+		let address = self.try_metrics_address()?;
+		self.metrics().run_server(address).await
+	}
+
+	/// Runs the server, the metrics server and the background tasks.
 	async fn run(self) -> Result<(), anyhow::Error> {
 		let background_handle = self.run_background_tasks();
 
@@ -40,9 +102,10 @@ pub trait LightNodeV1Operations: LightNodeService + Send + Sync + Sized + Clone
 			Ok::<_, anyhow::Error>(())
 		};
 		let server = self.run_server();
+		let metrics_server = self.run_metrics_server();
 
-		info!("Running server and background tasks.");
-		tokio::try_join!(server, background_tasks)?;
+		info!("Running server, metrics server and background tasks.");
+		tokio::try_join!(server, metrics_server, background_tasks)?;
 
 		Ok(())
 	}
@@ -0,0 +1,243 @@
+use movement_algs::grouping_heuristic::GroupingOutcome;
+use poem::{get, handler, listener::TcpListener, web::Data, EndpointExt, Response, Route, Server};
+use prometheus::{
+	exponential_buckets, Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter,
+	IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use std::time::Duration;
+use tracing::info;
+
+/// The reasons a blob read from Celestia fails verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationFailure {
+	/// The verifier found the blob is not included.
+	Rejected,
+	/// The verifier failed before coming to a decision. The blob is still passed on.
+	Error,
+}
+
+impl VerificationFailure {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			VerificationFailure::Rejected => "rejected",
+			VerificationFailure::Error => "error",
+		}
+	}
+}
+
+/// The Prometheus metrics of the light node.
+///
+/// Cloning shares the underlying metrics, so all clones report into the same registry.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+	registry: Registry,
+	blobs_submitted: IntCounter,
+	blobs_read: IntCounter,
+	submit_failures: IntCounter,
+	submit_latency: HistogramVec,
+	blob_bytes: Histogram,
+	verification_failures: IntCounterVec,
+	decode_failures: IntCounter,
+	mempool_depth: IntGauge,
+	blocks_built: IntCounter,
+	grouping_outcomes: IntCounterVec,
+}
+
+impl Metrics {
+	pub const NAMESPACE: &'static str = "m1_da_light_node";
+
+	pub fn try_new() -> Result<Self, anyhow::Error> {
+		let registry = Registry::new_custom(Some(Self::NAMESPACE.to_string()), None)?;
+
+		let blobs_submitted =
+			IntCounter::new("blobs_submitted_total", "Number of blobs submitted to Celestia")?;
+		let blobs_read =
+			IntCounter::new("blobs_read_total", "Number of verified blobs read from Celestia")?;
+		let submit_failures = IntCounter::new(
+			"submit_failures_total",
+			"Number of batches of blobs which failed to be submitted to Celestia",
+		)?;
+		let submit_latency = HistogramVec::new(
+			HistogramOpts::new(
+				"submit_latency_seconds",
+				"Time taken to submit a batch of blobs to Celestia, by outcome",
+			)
+			.buckets(exponential_buckets(0.05, 2.0, 12)?),
+			&["outcome"],
+		)?;
+		let blob_bytes = Histogram::with_opts(
+			HistogramOpts::new("blob_bytes", "Size of the blobs submitted to Celestia")
+				.buckets(exponential_buckets(256.0, 4.0, 10)?),
+		)?;
+		let verification_failures = IntCounterVec::new(
+			Opts::new(
+				"verification_failures_total",
+				"Number of blobs read from Celestia which failed verification",
+			),
+			&["reason"],
+		)?;
+		let decode_failures = IntCounter::new(
+			"decode_failures_total",
+			"Number of verified blobs read from Celestia which could not be decoded",
+		)?;
+		let mempool_depth =
+			IntGauge::new("mempool_depth", "Estimated number of transactions in the mempool")?;
+		// blocks built per second is the rate of this counter
+		let blocks_built = IntCounter::new("blocks_built_total", "Number of blocks built")?;
+		let grouping_outcomes = IntCounterVec::new(
+			Opts::new(
+				"grouping_outcomes_total",
+				"Number of blocks by the outcome of submitting their group to Celestia",
+			),
+			&["outcome"],
+		)?;
+
+		registry.register(Box::new(blobs_submitted.clone()))?;
+		registry.register(Box::new(blobs_read.clone()))?;
+		registry.register(Box::new(submit_failures.clone()))?;
+		registry.register(Box::new(submit_latency.clone()))?;
+		registry.register(Box::new(blob_bytes.clone()))?;
+		registry.register(Box::new(verification_failures.clone()))?;
+		registry.register(Box::new(decode_failures.clone()))?;
+		registry.register(Box::new(mempool_depth.clone()))?;
+		registry.register(Box::new(blocks_built.clone()))?;
+		registry.register(Box::new(grouping_outcomes.clone()))?;
+
+		Ok(Self {
+			registry,
+			blobs_submitted,
+			blobs_read,
+			submit_failures,
+			submit_latency,
+			blob_bytes,
+			verification_failures,
+			decode_failures,
+			mempool_depth,
+			blocks_built,
+			grouping_outcomes,
+		})
+	}
+
+	/// Records a batch of blobs submitted to Celestia.
+	pub fn observe_submission(
+		&self,
+		blob_sizes: impl IntoIterator<Item = usize>,
+		latency: Duration,
+	) {
+		for size in blob_sizes {
+			self.blobs_submitted.inc();
+			self.blob_bytes.observe(size as f64);
+		}
+		self.submit_latency
+			.with_label_values(&["success"])
+			.observe(latency.as_secs_f64());
+	}
+
+	/// Records a batch of blobs which failed to be submitted to Celestia.
+	pub fn observe_failed_submission(&self, latency: Duration) {
+		self.submit_failures.inc();
+		self.submit_latency
+			.with_label_values(&["failure"])
+			.observe(latency.as_secs_f64());
+	}
+
+	/// Records verified blobs read from Celestia.
+	pub fn observe_blobs_read(&self, count: usize) {
+		self.blobs_read.inc_by(count as u64);
+	}
+
+	/// Records a blob which failed verification.
+	pub fn observe_verification_failure(&self, reason: VerificationFailure) {
+		self.verification_failures.with_label_values(&[reason.as_str()]).inc();
+	}
+
+	/// Records a verified blob which could not be decoded.
+	pub fn observe_decode_failure(&self) {
+		self.decode_failures.inc();
+	}
+
+	/// Records the current depth of the mempool.
+	pub fn set_mempool_depth(&self, depth: u64) {
+		self.mempool_depth.set(depth as i64);
+	}
+
+	/// Records a block built by the sequencer.
+	pub fn observe_block_built(&self) {
+		self.blocks_built.inc();
+	}
+
+	/// Records the outcome of submitting a group of blocks.
+	///
+	/// Members which did not succeed are counted as failures, whether they will be retried or not.
+	pub fn observe_grouping_outcome<T>(&self, outcome: &GroupingOutcome<T>) {
+		for elemental in &outcome.0 {
+			let label = if elemental.is_success() { "success" } else { "failure" };
+			self.grouping_outcomes.with_label_values(&[label]).inc();
+		}
+	}
+
+	/// Renders the metrics in the Prometheus text format.
+	pub fn render(&self) -> Result<String, anyhow::Error> {
+		let mut buffer = Vec::new();
+		TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+		Ok(String::from_utf8(buffer)?)
+	}
+
+	pub fn create_routes(&self) -> impl EndpointExt {
+		Route::new().at("/metrics", get(serve_metrics)).data(self.clone())
+	}
+
+	/// Serves the metrics over HTTP at `/metrics`.
+	pub async fn run_server(&self, address: String) -> Result<(), anyhow::Error> {
+		info!("Metrics server listening on: {}", address);
+		Server::new(TcpListener::bind(address)).run(self.create_routes()).await?;
+		Ok(())
+	}
+}
+
+#[handler]
+async fn serve_metrics(metrics: Data<&Metrics>) -> Result<Response, anyhow::Error> {
+	let body = metrics.render()?;
+	Ok(Response::builder().content_type(TextEncoder::new().format_type()).body(body))
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use poem::test::TestClient;
+
+	#[tokio::test]
+	async fn test_serves_metrics() -> Result<(), anyhow::Error> {
+		let metrics = Metrics::try_new()?;
+		metrics.observe_submission(vec![100, 2_000], Duration::from_millis(300));
+		metrics.observe_failed_submission(Duration::from_secs(30));
+		metrics.observe_verification_failure(VerificationFailure::Rejected);
+		metrics.observe_decode_failure();
+		metrics.observe_grouping_outcome(&GroupingOutcome::<()>::new_all_success(2));
+		metrics.set_mempool_depth(7);
+
+		let client = TestClient::new(metrics.create_routes());
+		let response = client.get("/metrics").send().await;
+		response.assert_status_is_ok();
+		let body = response.0.into_body().into_string().await?;
+
+		assert!(body.contains("m1_da_light_node_blobs_submitted_total 2"));
+		assert!(body.contains("m1_da_light_node_blob_bytes_count 2"));
+		assert!(body.contains("m1_da_light_node_submit_failures_total 1"));
+		assert!(
+			body.contains("m1_da_light_node_submit_latency_seconds_count{outcome=\"success\"} 1")
+		);
+		assert!(
+			body.contains("m1_da_light_node_submit_latency_seconds_count{outcome=\"failure\"} 1")
+		);
+		assert!(
+			body.contains("m1_da_light_node_verification_failures_total{reason=\"rejected\"} 1")
+		);
+		assert!(body.contains("m1_da_light_node_decode_failures_total 1"));
+		assert!(body.contains("m1_da_light_node_grouping_outcomes_total{outcome=\"success\"} 2"));
+		assert!(body.contains("m1_da_light_node_mempool_depth 7"));
+
+		Ok(())
+	}
+}
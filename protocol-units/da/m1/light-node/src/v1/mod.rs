@@ -1,9 +1,21 @@
+pub mod auth;
+
+pub mod cache;
+
+pub mod codec;
+
 pub mod passthrough;
 #[cfg(feature = "sequencer")]
 pub mod sequencer;
 
 pub mod light_node;
 
+pub mod metrics;
+
+pub mod namespace;
+
+pub mod status;
+
 pub mod manager;
 
 #[cfg(not(feature = "sequencer"))]
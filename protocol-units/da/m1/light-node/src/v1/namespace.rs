@@ -0,0 +1,98 @@
+use celestia_types::nmt::Namespace;
+use m1_da_light_node_util::config::Config;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// The namespace selector of the default namespace, which carries the blocks.
+pub const DEFAULT_NAMESPACE: &str = "";
+
+/// The Celestia namespaces served by the light node, selected by name in requests.
+///
+/// The default namespace is selected by [DEFAULT_NAMESPACE].
+#[derive(Debug, Clone)]
+pub struct Namespaces {
+	namespaces: Arc<BTreeMap<String, Namespace>>,
+}
+
+impl Namespaces {
+	pub fn try_new(
+		default: Namespace,
+		named: BTreeMap<String, Namespace>,
+	) -> Result<Self, anyhow::Error> {
+		let mut namespaces = BTreeMap::new();
+		namespaces.insert(DEFAULT_NAMESPACE.to_string(), default);
+		for (name, namespace) in named {
+			if name.is_empty() {
+				anyhow::bail!("Named namespaces must not have an empty name");
+			}
+			// reads would be served for both names, so a blob's name would be ambiguous
+			if let Some((other, _)) = namespaces.iter().find(|(_, other)| **other == namespace) {
+				anyhow::bail!("Namespace {} is the same as namespace {:?}", name, other);
+			}
+			namespaces.insert(name, namespace);
+		}
+		Ok(Self { namespaces: Arc::new(namespaces) })
+	}
+
+	pub fn try_from_config(config: &Config) -> Result<Self, anyhow::Error> {
+		Self::try_new(config.celestia_namespace(), config.celestia_namespaces())
+	}
+
+	/// Gets the default namespace, which carries the blocks.
+	pub fn default_namespace(&self) -> Namespace {
+		// the default namespace is always inserted on construction
+		self.namespaces[DEFAULT_NAMESPACE]
+	}
+
+	/// Gets the namespace selected by name, failing if it is not served.
+	pub fn resolve(&self, selector: &str) -> Result<Namespace, anyhow::Error> {
+		self.namespaces
+			.get(selector)
+			.copied()
+			.ok_or_else(|| anyhow::anyhow!("Unknown namespace {:?}", selector))
+	}
+
+	/// Iterates over the selectors and namespaces served, starting with the default.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, Namespace)> {
+		self.namespaces.iter().map(|(name, namespace)| (name.as_str(), *namespace))
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	fn namespace(name: &[u8]) -> Result<Namespace, anyhow::Error> {
+		Ok(Namespace::new_v0(name)?)
+	}
+
+	#[test]
+	fn test_resolves_namespaces() -> Result<(), anyhow::Error> {
+		let namespaces = Namespaces::try_new(
+			namespace(b"blocks")?,
+			BTreeMap::from([("governance".to_string(), namespace(b"governance")?)]),
+		)?;
+
+		assert_eq!(namespaces.resolve(DEFAULT_NAMESPACE)?, namespace(b"blocks")?);
+		assert_eq!(namespaces.default_namespace(), namespace(b"blocks")?);
+		assert_eq!(namespaces.resolve("governance")?, namespace(b"governance")?);
+		assert!(namespaces.resolve("other").is_err());
+
+		let selectors: Vec<_> = namespaces.iter().map(|(name, _)| name).collect();
+		assert_eq!(selectors, vec![DEFAULT_NAMESPACE, "governance"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_ambiguous_namespaces() -> Result<(), anyhow::Error> {
+		let empty_name = BTreeMap::from([(String::new(), namespace(b"other")?)]);
+		assert!(Namespaces::try_new(namespace(b"blocks")?, empty_name).is_err());
+
+		let duplicate = BTreeMap::from([("governance".to_string(), namespace(b"blocks")?)]);
+		assert!(Namespaces::try_new(namespace(b"blocks")?, duplicate).is_err());
+
+		Ok(())
+	}
+}
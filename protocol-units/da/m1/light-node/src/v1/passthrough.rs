@@ -1,10 +1,12 @@
 use anyhow::Context;
+use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use tokio::sync::RwLock;
 use tokio_stream::{Stream, StreamExt};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use celestia_rpc::{BlobClient, Client, HeaderClient};
 use celestia_types::{blob::GasPrice, nmt::Namespace, Blob as CelestiaBlob};
@@ -13,23 +15,42 @@ use celestia_types::{blob::GasPrice, nmt::Namespace, Blob as CelestiaBlob};
 use m1_da_light_node_grpc::light_node_service_server::LightNodeService;
 use m1_da_light_node_grpc::*;
 use m1_da_light_node_util::config::Config;
-use m1_da_light_node_verifier::{v1::V1Verifier, Verifier};
-
+use m1_da_light_node_verifier::{
+	das::{CelestiaSampler, DasVerifier, DataAvailabilitySampler},
+	v1::V1Verifier,
+	Verifier,
+};
+
+use crate::v1::auth::{has_role, Role, ServerAuth};
+use crate::v1::cache::VerificationCache;
+use crate::v1::codec::NamespaceCodecs;
+use crate::v1::metrics::{Metrics, VerificationFailure};
+use crate::v1::namespace::{Namespaces, DEFAULT_NAMESPACE};
+use crate::v1::status::{caught_up_status, healthy, unhealthy, StatusTracker};
 use crate::v1::LightNodeV1Operations;
 
+/// How long to wait for the Celestia node when reporting its status.
+const CELESTIA_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
 pub struct LightNodeV1 {
 	pub config: Config,
-	pub celestia_namespace: Namespace,
+	pub namespaces: Namespaces,
 	pub default_client: Arc<Client>,
 	pub verification_mode: Arc<RwLock<VerificationMode>>,
-	pub verifier: Arc<Box<dyn Verifier + Send + Sync>>,
+	/// The verifiers of the namespaces, by namespace selector.
+	pub verifiers: Arc<BTreeMap<String, Box<dyn Verifier + Send + Sync>>>,
+	pub verification_cache: VerificationCache,
+	pub metrics: Metrics,
+	pub status: StatusTracker,
+	pub server_auth: ServerAuth,
+	pub codecs: NamespaceCodecs,
 }
 
 impl Debug for LightNodeV1 {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		f.debug_struct("LightNodeV1")
-			.field("celestia_namespace", &self.config.celestia_namespace())
+			.field("namespaces", &self.namespaces)
 			.finish()
 	}
 }
@@ -38,19 +59,42 @@ impl LightNodeV1Operations for LightNodeV1 {
 	/// Tries to create a new LightNodeV1 instance from the toml config file.
 	async fn try_from_config(config: Config) -> Result<Self, anyhow::Error> {
 		let client = Arc::new(config.connect_celestia().await?);
+		let namespaces = Namespaces::try_from_config(&config)?;
+		let das_enabled = config.m1_da_light_node_das_enabled();
+		let das_samples = config.m1_da_light_node_das_samples() as usize;
+		let das_confidence = f64::from(config.m1_da_light_node_das_confidence_ppm()) / 1_000_000.0;
+		let das_timeout = Duration::from_millis(config.m1_da_light_node_das_sample_timeout_ms());
+		let verifiers = namespaces
+			.iter()
+			.map(|(selector, namespace)| {
+				let v1_verifier = V1Verifier { client: client.clone(), namespace };
+				let verifier: Box<dyn Verifier + Send + Sync> = if das_enabled {
+					let sampler = CelestiaSampler { client: client.clone(), timeout: das_timeout };
+					Box::new(DasVerifier::new(
+						v1_verifier,
+						DataAvailabilitySampler::try_new(sampler, das_samples, das_confidence)?,
+					))
+				} else {
+					Box::new(v1_verifier)
+				};
+				Ok((selector.to_string(), verifier))
+			})
+			.collect::<Result<BTreeMap<_, _>, anyhow::Error>>()?;
 
 		Ok(Self {
 			config: config.clone(),
-			celestia_namespace: config.celestia_namespace(),
+			namespaces,
 			default_client: client.clone(),
 			verification_mode: Arc::new(RwLock::new(
 				VerificationMode::from_str_name("M_OF_N")
 					.context("Failed to parse verification mode")?,
 			)),
-			verifier: Arc::new(Box::new(V1Verifier {
-				client,
-				namespace: config.celestia_namespace(),
-			})),
+			verifiers: Arc::new(verifiers),
+			verification_cache: VerificationCache::try_from_config(&config)?,
+			metrics: Metrics::try_new()?,
+			status: StatusTracker::new(),
+			server_auth: ServerAuth::try_from_config(&config)?,
+			codecs: NamespaceCodecs::try_from_config(&config, &namespaces)?,
 		})
 	}
 
@@ -58,6 +102,22 @@ impl LightNodeV1Operations for LightNodeV1 {
 		Ok(self.config.m1_da_light_node_service())
 	}
 
+	fn server_auth(&self) -> &ServerAuth {
+		&self.server_auth
+	}
+
+	fn metrics(&self) -> &Metrics {
+		&self.metrics
+	}
+
+	fn try_metrics_address(&self) -> Result<String, anyhow::Error> {
+		Ok(self.config.m1_da_light_node_metrics_service())
+	}
+
+	async fn node_status(&self) -> StatusResponse {
+		self.celestia_status().await
+	}
+
 	/// Runs background tasks for the LightNodeV1 instance.
 	async fn run_background_tasks(&self) -> Result<(), anyhow::Error> {
 		Ok(())
@@ -65,21 +125,19 @@ impl LightNodeV1Operations for LightNodeV1 {
 }
 
 impl LightNodeV1 {
-	/// Creates a new blob instance with the provided data.
-	pub fn create_new_celestia_blob(&self, data: Vec<u8>) -> Result<CelestiaBlob, anyhow::Error> {
-		CelestiaBlob::new(self.celestia_namespace, data)
+	/// Creates a new blob instance with the provided data in the given namespace.
+	pub fn create_new_celestia_blob(
+		&self,
+		data: Vec<u8>,
+		namespace: Namespace,
+	) -> Result<CelestiaBlob, anyhow::Error> {
+		CelestiaBlob::new(namespace, data)
 			.map_err(|e| anyhow::anyhow!("Failed to create a blob: {}", e))
 	}
 
 	/// Submits a CelestiaNlob to the Celestia node.
 	pub async fn submit_celestia_blob(&self, blob: CelestiaBlob) -> Result<u64, anyhow::Error> {
-		let height = self
-			.default_client
-			.blob_submit(&[blob], GasPrice::default())
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed submitting the blob: {}", e))?;
-
-		Ok(height)
+		self.submit_celestia_blobs(&[blob]).await
 	}
 
 	/// Submits Celestia blobs to the Celestia node.
@@ -87,28 +145,56 @@ impl LightNodeV1 {
 		&self,
 		blobs: &[CelestiaBlob],
 	) -> Result<u64, anyhow::Error> {
-		let height = self
-			.default_client
-			.blob_submit(blobs, GasPrice::default())
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed submitting the blob: {}", e))?;
+		let start = Instant::now();
+		let height = match self.default_client.blob_submit(blobs, GasPrice::default()).await {
+			Ok(height) => height,
+			Err(e) => {
+				self.metrics.observe_failed_submission(start.elapsed());
+				anyhow::bail!("Failed submitting the blob: {}", e);
+			}
+		};
+		self.metrics
+			.observe_submission(blobs.iter().map(|blob| blob.data.len()), start.elapsed());
+		self.status.record_submission(height);
 
 		Ok(height)
 	}
 
-	/// Submits a blob to the Celestia node.
-	pub async fn submit_blob(&self, data: Vec<u8>) -> Result<Blob, anyhow::Error> {
-		let celestia_blob = self.create_new_celestia_blob(data)?;
+	/// Submits a blob to the Celestia node in the selected namespace, encoded with its codec.
+	pub async fn submit_blob(&self, data: Vec<u8>, namespace: &str) -> Result<Blob, anyhow::Error> {
+		let encoded = self.codecs.get(namespace)?.encode(&data)?;
+		let namespace = self.namespaces.resolve(namespace)?;
+		let celestia_blob = self.create_new_celestia_blob(encoded, namespace)?;
 		let height = self.submit_celestia_blob(celestia_blob.clone()).await?;
-		Ok(Self::celestia_blob_to_blob(celestia_blob, height)?)
+		let timestamp = self.get_timestamp_at_height(height).await?;
+		let mut blob = Self::celestia_blob_to_blob(celestia_blob, height, timestamp)?;
+		blob.data = data;
+		Ok(blob)
 	}
 
-	/// Gets the blobs at a given height.
+	/// Gets the time of the Celestia header at a given height, in microseconds.
+	/// Unlike the time a blob is read at, every reader of the height gets the same timestamp.
+	pub async fn get_timestamp_at_height(&self, height: u64) -> Result<u64, anyhow::Error> {
+		let header = self.default_client.header_get_by_height(height).await?;
+		let timestamp = header.time().unix_timestamp_nanos() / 1000;
+		u64::try_from(timestamp)
+			.map_err(|_| anyhow::anyhow!("Header at height {} predates the epoch", height))
+	}
+
+	/// Gets the blobs at a given height in the selected namespace.
 	pub async fn get_celestia_blobs_at_height(
 		&self,
 		height: u64,
+		namespace: &str,
 	) -> Result<Vec<CelestiaBlob>, anyhow::Error> {
-		let blobs = self.default_client.blob_get_all(height, &[self.celestia_namespace]).await;
+		let verifier = self
+			.verifiers
+			.get(namespace)
+			.ok_or_else(|| anyhow::anyhow!("Unknown namespace {:?}", namespace))?;
+		let blobs = self
+			.default_client
+			.blob_get_all(height, &[self.namespaces.resolve(namespace)?])
+			.await;
 
 		if let Err(e) = &blobs {
 			debug!("Error getting blobs: {:?}", e);
@@ -121,15 +207,25 @@ impl LightNodeV1 {
 			debug!("Verifying blob");
 
 			let blob_data = blob.data.clone();
+			let verification_mode = *self.verification_mode.read().await;
 
 			// todo: improve error boundary here to detect crashes
 			let verified = self
-				.verifier
-				.verify(*self.verification_mode.read().await, &blob_data, height)
+				.verification_cache
+				.get_or_verify((height, blob.commitment.0, verification_mode), || {
+					verifier.verify(verification_mode, &blob_data, height)
+				})
 				.await;
 
-			if let Err(e) = &verified {
-				debug!("Error verifying blob: {:?}", e);
+			match &verified {
+				Ok(false) => {
+					self.metrics.observe_verification_failure(VerificationFailure::Rejected);
+				}
+				Err(e) => {
+					debug!("Error verifying blob: {:?}", e);
+					self.metrics.observe_verification_failure(VerificationFailure::Error);
+				}
+				Ok(true) => {}
 			}
 
 			// FIXME: check the implications of treating errors as verification success.
@@ -142,16 +238,73 @@ impl LightNodeV1 {
 				verified_blobs.push(blob);
 			}
 		}
+		self.metrics.observe_blobs_read(verified_blobs.len());
 
 		Ok(verified_blobs)
 	}
 
+	/// Gets the status of the connection to the Celestia node and of its sync.
+	pub async fn celestia_status(&self) -> StatusResponse {
+		let heads = async {
+			let local_head_height: u64 =
+				self.default_client.header_local_head().await?.height().into();
+			let network_head_height: u64 =
+				self.default_client.header_network_head().await?.height().into();
+			Ok::<_, anyhow::Error>((local_head_height, network_head_height))
+		};
+		let heads = tokio::time::timeout(CELESTIA_STATUS_TIMEOUT, heads)
+			.await
+			.unwrap_or_else(|_| Err(anyhow::anyhow!("Timed out requesting the Celestia heads")));
+
+		let last_submission_height = self.status.last_submission_height();
+		match heads {
+			Ok((local_head_height, network_head_height)) => StatusResponse {
+				celestia_connection: Some(healthy()),
+				caught_up: Some(caught_up_status(local_head_height, network_head_height)),
+				block_proposer: None,
+				local_head_height,
+				network_head_height,
+				last_submission_height,
+			},
+			Err(e) => StatusResponse {
+				celestia_connection: Some(unhealthy(e.to_string())),
+				caught_up: Some(unhealthy("Celestia node is unreachable")),
+				block_proposer: None,
+				local_head_height: 0,
+				network_head_height: 0,
+				last_submission_height,
+			},
+		}
+	}
+
 	#[tracing::instrument(target = "movement_timing", level = "debug")]
-	async fn get_blobs_at_height(&self, height: u64) -> Result<Vec<Blob>, anyhow::Error> {
-		let celestia_blobs = self.get_celestia_blobs_at_height(height).await?;
+	async fn get_blobs_at_height(
+		&self,
+		height: u64,
+		namespace: &str,
+	) -> Result<Vec<Blob>, anyhow::Error> {
+		let codec = self.codecs.get(namespace)?;
+		let celestia_blobs = self.get_celestia_blobs_at_height(height, namespace).await?;
+		if celestia_blobs.is_empty() {
+			return Ok(Vec::new());
+		}
+		let timestamp = self.get_timestamp_at_height(height).await?;
 		let mut blobs = Vec::new();
 		for celestia_blob in celestia_blobs {
-			let blob = Self::celestia_blob_to_blob(celestia_blob, height)?;
+			let mut blob = Self::celestia_blob_to_blob(celestia_blob, height, timestamp)?;
+			// anyone may post to the namespace, so a blob which does not decode is skipped
+			// rather than failing the other blobs at the height
+			blob.data = match codec.decode(&blob.data) {
+				Ok(data) => data,
+				Err(e) => {
+					self.metrics.observe_decode_failure();
+					warn!(
+						"Skipping undecodable blob {} at height {} in namespace {:?}: {}",
+						blob.blob_id, height, namespace, e
+					);
+					continue;
+				}
+			};
 			debug!(blob_id = %blob.blob_id, "got blob");
 			blobs.push(blob);
 		}
@@ -163,6 +316,7 @@ impl LightNodeV1 {
 		&self,
 		start_height: u64,
 		end_height: Option<u64>,
+		namespace: String,
 	) -> Result<
 		std::pin::Pin<Box<dyn Stream<Item = Result<Blob, anyhow::Error>> + Send>>,
 		anyhow::Error,
@@ -177,7 +331,7 @@ impl LightNodeV1 {
 					break;
 				}
 
-				let blobs = me.get_blobs_at_height(height).await?;
+				let blobs = me.get_blobs_at_height(height, &namespace).await?;
 				for blob in blobs {
 					yield blob;
 				}
@@ -190,9 +344,10 @@ impl LightNodeV1 {
 	}
 
 	/// Streams the latest blobs that can subscribed to.
-	async fn stream_blobs_from_height_on(
+	pub async fn stream_blobs_from_height_on(
 		&self,
 		start_height: Option<u64>,
+		namespace: String,
 	) -> Result<
 		std::pin::Pin<Box<dyn Stream<Item = Result<Blob, anyhow::Error>> + Send>>,
 		anyhow::Error,
@@ -213,7 +368,7 @@ impl LightNodeV1 {
 				// back fetch the blobs
 				if first_flag && (height > start_height) {
 
-					let mut blob_stream = me.stream_blobs_in_range(start_height, Some(height)).await?;
+					let mut blob_stream = me.stream_blobs_in_range(start_height, Some(height), namespace.clone()).await?;
 
 					while let Some(blob) = blob_stream.next().await {
 
@@ -225,7 +380,7 @@ impl LightNodeV1 {
 				}
 				first_flag = false;
 
-				let blobs = me.get_blobs_at_height(height).await?;
+				let blobs = me.get_blobs_at_height(height, &namespace).await?;
 				for blob in blobs {
 
 					debug!("Stream got blob: {:?}", blob);
@@ -239,9 +394,12 @@ impl LightNodeV1 {
 			as std::pin::Pin<Box<dyn Stream<Item = Result<Blob, anyhow::Error>> + Send>>)
 	}
 
-	pub fn celestia_blob_to_blob(blob: CelestiaBlob, height: u64) -> Result<Blob, anyhow::Error> {
-		let timestamp = chrono::Utc::now().timestamp_micros() as u64;
-
+	/// Converts a Celestia blob included at a height whose header has the given timestamp.
+	pub fn celestia_blob_to_blob(
+		blob: CelestiaBlob,
+		height: u64,
+		timestamp: u64,
+	) -> Result<Blob, anyhow::Error> {
 		Ok(Blob {
 			data: blob.data,
 			blob_id: serde_json::to_string(&blob.commitment)
@@ -255,16 +413,27 @@ impl LightNodeV1 {
 		Ok(BlobResponse { blob_type: Some(blob_response::BlobType::PassedThroughBlob(blob)) })
 	}
 
-	pub fn blob_to_blob_read_response(blob: Blob) -> Result<BlobResponse, anyhow::Error> {
-		#[cfg(feature = "sequencer")]
-		{
-			Ok(BlobResponse { blob_type: Some(blob_response::BlobType::SequencedBlobBlock(blob)) })
+	/// Converts a blob read from the selected namespace to a response.
+	/// Only the default namespace carries sequenced blocks.
+	pub fn blob_to_blob_read_response(
+		blob: Blob,
+		namespace: &str,
+	) -> Result<BlobResponse, anyhow::Error> {
+		if cfg!(feature = "sequencer") && namespace == DEFAULT_NAMESPACE {
+			return Ok(BlobResponse {
+				blob_type: Some(blob_response::BlobType::SequencedBlobBlock(blob)),
+			});
 		}
 
-		#[cfg(not(feature = "sequencer"))]
-		{
-			Ok(BlobResponse { blob_type: Some(blob_response::BlobType::PassedThroughBlob(blob)) })
-		}
+		Ok(BlobResponse { blob_type: Some(blob_response::BlobType::PassedThroughBlob(blob)) })
+	}
+
+	/// Checks that the namespace selected by a request is served.
+	fn check_namespace(&self, namespace: &str) -> Result<(), tonic::Status> {
+		self.namespaces
+			.resolve(namespace)
+			.map(|_| ())
+			.map_err(|e| tonic::Status::invalid_argument(e.to_string()))
 	}
 }
 
@@ -283,16 +452,17 @@ impl LightNodeService for LightNodeV1 {
 		request: tonic::Request<StreamReadFromHeightRequest>,
 	) -> std::result::Result<tonic::Response<Self::StreamReadFromHeightStream>, tonic::Status> {
 		let me = Arc::new(self.clone());
-		let height = request.into_inner().height;
+		let StreamReadFromHeightRequest { height, namespace } = request.into_inner();
+		self.check_namespace(&namespace)?;
 
 		let output = async_stream::try_stream! {
 
-			let mut blob_stream = me.stream_blobs_from_height_on(Some(height)).await.map_err(|e| tonic::Status::internal(e.to_string()))?;
+			let mut blob_stream = me.stream_blobs_from_height_on(Some(height), namespace.clone()).await.map_err(|e| tonic::Status::internal(e.to_string()))?;
 
 			while let Some(blob) = blob_stream.next().await {
 				let blob = blob.map_err(|e| tonic::Status::internal(e.to_string()))?;
 				let response = StreamReadFromHeightResponse {
-					blob : Some(Self::blob_to_blob_read_response(blob).map_err(|e| tonic::Status::internal(e.to_string()))?)
+					blob : Some(Self::blob_to_blob_read_response(blob, &namespace).map_err(|e| tonic::Status::internal(e.to_string()))?)
 				};
 				yield response;
 			}
@@ -310,17 +480,19 @@ impl LightNodeService for LightNodeV1 {
 	/// Stream the latest blobs.
 	async fn stream_read_latest(
 		&self,
-		_request: tonic::Request<StreamReadLatestRequest>,
+		request: tonic::Request<StreamReadLatestRequest>,
 	) -> std::result::Result<tonic::Response<Self::StreamReadLatestStream>, tonic::Status> {
 		let me = Arc::new(self.clone());
+		let namespace = request.into_inner().namespace;
+		self.check_namespace(&namespace)?;
 
 		let output = async_stream::try_stream! {
 
-			let mut blob_stream = me.stream_blobs_from_height_on(None).await.map_err(|e| tonic::Status::internal(e.to_string()))?;
+			let mut blob_stream = me.stream_blobs_from_height_on(None, namespace.clone()).await.map_err(|e| tonic::Status::internal(e.to_string()))?;
 			while let Some(blob) = blob_stream.next().await {
 				let blob = blob.map_err(|e| tonic::Status::internal(e.to_string()))?;
 				let response = StreamReadLatestResponse {
-					blob : Some(Self::blob_to_blob_read_response(blob).map_err(|e| tonic::Status::internal(e.to_string()))?)
+					blob : Some(Self::blob_to_blob_read_response(blob, &namespace).map_err(|e| tonic::Status::internal(e.to_string()))?)
 				};
 				yield response;
 			}
@@ -345,12 +517,13 @@ impl LightNodeService for LightNodeV1 {
 
 			while let Some(request) = stream.next().await {
 				let request = request?;
+				me.check_namespace(&request.namespace)?;
 				let blob_data = request.blob.ok_or(tonic::Status::invalid_argument("No blob in request"))?.data;
 
-				let blob = me.submit_blob(blob_data).await.map_err(|e| tonic::Status::internal(e.to_string()))?;
+				let blob = me.submit_blob(blob_data, &request.namespace).await.map_err(|e| tonic::Status::internal(e.to_string()))?;
 
 				let write_response = StreamWriteBlobResponse {
-					blob : Some(Self::blob_to_blob_read_response(blob).map_err(|e| tonic::Status::internal(e.to_string()))?)
+					blob : Some(Self::blob_to_blob_read_response(blob, &request.namespace).map_err(|e| tonic::Status::internal(e.to_string()))?)
 				};
 
 				yield write_response;
@@ -365,9 +538,10 @@ impl LightNodeService for LightNodeV1 {
 		&self,
 		request: tonic::Request<ReadAtHeightRequest>,
 	) -> std::result::Result<tonic::Response<ReadAtHeightResponse>, tonic::Status> {
-		let height = request.into_inner().height;
+		let ReadAtHeightRequest { height, namespace } = request.into_inner();
+		self.check_namespace(&namespace)?;
 		let blobs = self
-			.get_blobs_at_height(height)
+			.get_blobs_at_height(height, &namespace)
 			.await
 			.map_err(|e| tonic::Status::internal(e.to_string()))?;
 
@@ -378,7 +552,7 @@ impl LightNodeService for LightNodeV1 {
 		let mut blob_responses = Vec::new();
 		for blob in blobs {
 			blob_responses.push(
-				Self::blob_to_blob_read_response(blob)
+				Self::blob_to_blob_read_response(blob, &namespace)
 					.map_err(|e| tonic::Status::internal(e.to_string()))?,
 			);
 		}
@@ -393,11 +567,12 @@ impl LightNodeService for LightNodeV1 {
 		&self,
 		request: tonic::Request<BatchReadRequest>,
 	) -> std::result::Result<tonic::Response<BatchReadResponse>, tonic::Status> {
-		let heights = request.into_inner().heights;
+		let BatchReadRequest { heights, namespace } = request.into_inner();
+		self.check_namespace(&namespace)?;
 		let mut responses = Vec::with_capacity(heights.len());
 		for height in heights {
 			let blobs = self
-				.get_blobs_at_height(height)
+				.get_blobs_at_height(height, &namespace)
 				.await
 				.map_err(|e| tonic::Status::internal(e.to_string()))?;
 
@@ -408,7 +583,7 @@ impl LightNodeService for LightNodeV1 {
 			let mut blob_responses = Vec::new();
 			for blob in blobs {
 				blob_responses.push(
-					Self::blob_to_blob_read_response(blob)
+					Self::blob_to_blob_read_response(blob, &namespace)
 						.map_err(|e| tonic::Status::internal(e.to_string()))?,
 				);
 			}
@@ -424,11 +599,12 @@ impl LightNodeService for LightNodeV1 {
 		&self,
 		request: tonic::Request<BatchWriteRequest>,
 	) -> std::result::Result<tonic::Response<BatchWriteResponse>, tonic::Status> {
-		let blobs = request.into_inner().blobs;
+		let BatchWriteRequest { blobs, namespace } = request.into_inner();
+		self.check_namespace(&namespace)?;
 		let mut responses = Vec::with_capacity(blobs.len());
 		for data in blobs {
 			let blob = self
-				.submit_blob(data.data)
+				.submit_blob(data.data, &namespace)
 				.await
 				.map_err(|e| tonic::Status::internal(e.to_string()))?;
 			responses.push(blob);
@@ -444,11 +620,40 @@ impl LightNodeService for LightNodeV1 {
 
 		Ok(tonic::Response::new(BatchWriteResponse { blobs: blob_responses }))
 	}
+
+	/// Submit transactions to the sequencer.
+	async fn submit_transactions(
+		&self,
+		_request: tonic::Request<SubmitTransactionsRequest>,
+	) -> std::result::Result<tonic::Response<SubmitTransactionsResponse>, tonic::Status> {
+		Err(tonic::Status::unimplemented("Submitting transactions requires sequencer mode"))
+	}
+
+	/// Report the health of the Celestia connection, sync and block proposer.
+	async fn status(
+		&self,
+		_request: tonic::Request<StatusRequest>,
+	) -> std::result::Result<tonic::Response<StatusResponse>, tonic::Status> {
+		Ok(tonic::Response::new(self.node_status().await))
+	}
+
+	/// Get the verification parameters.
+	async fn get_verification_parameters(
+		&self,
+		_request: tonic::Request<GetVerificationParametersRequest>,
+	) -> std::result::Result<tonic::Response<GetVerificationParametersResponse>, tonic::Status> {
+		let mode = *self.verification_mode.read().await;
+		Ok(tonic::Response::new(GetVerificationParametersResponse { mode: mode.into() }))
+	}
+
 	/// Update and manage verification parameters.
 	async fn update_verification_parameters(
 		&self,
 		request: tonic::Request<UpdateVerificationParametersRequest>,
 	) -> std::result::Result<tonic::Response<UpdateVerificationParametersResponse>, tonic::Status> {
+		if !has_role(&request, Role::Admin) {
+			return Err(tonic::Status::permission_denied("Requires the admin role"));
+		}
 		let verification_mode = request.into_inner().mode();
 		let mut mode = self.verification_mode.write().await;
 		*mode = verification_mode;
@@ -1,29 +1,46 @@
 use std::{
-	sync::{atomic::AtomicU64, Arc},
+	collections::HashSet,
+	sync::{
+		atomic::{AtomicU64, AtomicUsize},
+		Arc, Mutex,
+	},
 	time::Duration,
 };
-use tokio_stream::Stream;
+use tokio_stream::{Stream, StreamExt};
 use tracing::{debug, info};
 
 use celestia_rpc::HeaderClient;
 use m1_da_light_node_grpc::light_node_service_server::LightNodeService;
 use m1_da_light_node_util::config::Config;
-use std::{fmt::Debug, path::PathBuf};
+use std::fmt::Debug;
 // FIXME: glob imports are bad style
 use m1_da_light_node_grpc::*;
 use memseq::{Sequencer, Transaction};
+use movement_algs::batching::{BatchLimits, FairQueue};
 use movement_algs::grouping_heuristic::{
 	apply::ToApply, binpacking::FirstFitBinpacking, drop_success::DropSuccess, skip::SkipFor,
-	splitting::Splitting, GroupingHeuristicStack, GroupingOutcome,
+	splitting::Splitting, GroupOrdering, GroupingHeuristicStack, GroupingOutcome,
 };
-use movement_types::Block;
+use movement_types::{Block, Id};
 use std::boxed::Box;
-use tokio::{
-	sync::mpsc::{Receiver, Sender},
-	time::timeout,
+
+use crate::v1::{
+	auth::ServerAuth, metrics::Metrics, namespace::DEFAULT_NAMESPACE,
+	passthrough::LightNodeV1 as LightNodeV1PassThrough, LightNodeV1Operations,
 };
 
-use crate::v1::{passthrough::LightNodeV1 as LightNodeV1PassThrough, LightNodeV1Operations};
+/// How long the block proposer may go without making progress before it is reported as unhealthy.
+const BLOCK_PROPOSER_LIVENESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How many built blocks may wait to be published before the block builder waits for them.
+const BLOCK_QUEUE_CAPACITY: usize = 1024;
+
+/// How long the rotation follower waits before following the DA again.
+/// Doubled on each consecutive failure.
+const ROTATION_FOLLOWER_MIN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The longest the rotation follower waits before following the DA again.
+const ROTATION_FOLLOWER_MAX_BACKOFF: Duration = Duration::from_secs(60);
 
 const LOGGING_UID: AtomicU64 = AtomicU64::new(0);
 
@@ -46,15 +63,7 @@ impl LightNodeV1Operations for LightNodeV1 {
 		let pass_through = LightNodeV1PassThrough::try_from_config(config.clone()).await?;
 		info!("Initialized pass through for LightNodeV1 in sequencer mode.");
 
-		let memseq_path = pass_through.config.try_memseq_path()?;
-		info!("Memseq path: {:?}", memseq_path);
-		let (max_block_size, build_time) = pass_through.config.try_block_building_parameters()?;
-
-		let memseq = Arc::new(memseq::Memseq::try_move_rocks(
-			PathBuf::from(memseq_path),
-			max_block_size,
-			build_time,
-		)?);
+		let memseq = Arc::new(memseq::Memseq::try_from_config(pass_through.config.memseq_config())?);
 		info!("Initialized Memseq with Move Rocks for LightNodeV1 in sequencer mode.");
 
 		Ok(Self { pass_through, memseq })
@@ -64,15 +73,34 @@ impl LightNodeV1Operations for LightNodeV1 {
 		self.pass_through.try_service_address()
 	}
 
+	fn server_auth(&self) -> &ServerAuth {
+		self.pass_through.server_auth()
+	}
+
+	fn metrics(&self) -> &Metrics {
+		self.pass_through.metrics()
+	}
+
+	fn try_metrics_address(&self) -> Result<String, anyhow::Error> {
+		self.pass_through.try_metrics_address()
+	}
+
+	async fn node_status(&self) -> StatusResponse {
+		let mut status = self.pass_through.node_status().await;
+		status.block_proposer =
+			Some(self.pass_through.status.proposer_status(BLOCK_PROPOSER_LIVENESS_TIMEOUT).await);
+		status
+	}
+
 	async fn run_background_tasks(&self) -> Result<(), anyhow::Error> {
-		self.run_block_proposer().await?;
+		futures::try_join!(self.run_block_proposer(), self.run_rotation_follower())?;
 
 		Ok(())
 	}
 }
 
 impl LightNodeV1 {
-	async fn tick_build_blocks(&self, sender: Sender<Block>) -> Result<(), anyhow::Error> {
+	async fn tick_build_blocks(&self, queue: &FairQueue<(), Block>) -> Result<(), anyhow::Error> {
 		let memseq = self.memseq.clone();
 
 		// this has an internal timeout based on its building time
@@ -80,10 +108,15 @@ impl LightNodeV1 {
 		let uid = LOGGING_UID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
 		debug!(target: "movement_timing", uid = %uid, "waiting_for_next_block",);
 		let block = memseq.wait_for_next_block().await?;
+		match memseq.mempool_depth().await {
+			Ok(depth) => self.metrics().set_mempool_depth(depth),
+			Err(e) => debug!("Error estimating mempool depth: {:?}", e),
+		}
 		match block {
 			Some(block) => {
 				info!(target: "movement_timing", block_id = %block.id(), uid = %uid, transaction_count = block.transactions.len(), "received_block");
-				sender.send(block).await?;
+				self.metrics().observe_block_built();
+				queue.push((), block).await?;
 				Ok(())
 			}
 			None => {
@@ -112,18 +145,41 @@ impl LightNodeV1 {
 		Ok(())
 	}
 
-	pub async fn submit_with_heuristic(&self, blocks: Vec<Block>) -> Result<(), anyhow::Error> {
+	/// Submits the blocks, regrouping those which failed to be submitted.
+	/// Returns the number of blocks which were submitted.
+	///
+	/// The transactions of the blocks which were not submitted are released by the memseq,
+	/// so that they may be published again.
+	pub async fn submit_with_heuristic(&self, blocks: Vec<Block>) -> Result<usize, anyhow::Error> {
+		let mut unpublished = HashSet::new();
 		for block in &blocks {
 			info!(target: "movement_timing", block_id = %block.id(), "submitting_block");
+			unpublished.extend(block.transactions.iter().map(Transaction::id));
 		}
+		let published = Mutex::new(HashSet::new());
+		let submitted = self.submit_grouped(blocks, &published).await;
+
+		let published = published.into_inner().unwrap_or_else(|e| e.into_inner());
+		unpublished.retain(|id| !published.contains(id));
+		self.memseq.release_unpublished(unpublished.into_iter().collect()).await;
+
+		submitted
+	}
 
+	/// Submits the blocks in groups, recording the ids of the transactions which were submitted.
+	async fn submit_grouped(
+		&self,
+		blocks: Vec<Block>,
+		published: &Mutex<HashSet<Id>>,
+	) -> Result<usize, anyhow::Error> {
 		// wrap the blocks in a struct that can be split and compressed
 		// spawn blocking because the compression is blocking and could be slow
-		let namespace = self.pass_through.celestia_namespace.clone();
+		let namespace = self.pass_through.namespaces.default_namespace();
+		let codec = self.pass_through.codecs.get(DEFAULT_NAMESPACE)?.clone();
 		let blocks = tokio::task::spawn_blocking(move || {
 			blocks
 				.into_iter()
-				.map(|block| block::WrappedBlock::try_new(block, namespace))
+				.map(|block| block::WrappedBlock::try_new(block, namespace, &codec))
 				.collect::<Result<Vec<_>, anyhow::Error>>()
 		})
 		.await??;
@@ -136,81 +192,56 @@ impl LightNodeV1 {
 				FirstFitBinpacking::boxed(1_700_000),
 			]);
 
+		// groups which must land in order are not submitted after a group before them failed
+		let config = &self.pass_through.config;
+		let ordering = if config.m1_da_light_node_ordered_submission() {
+			GroupOrdering::Ordered
+		} else {
+			GroupOrdering::Unordered
+		};
+
+		let submitted = &AtomicUsize::new(0);
 		let start_distribution = GroupingOutcome::new_apply_distribution(blocks);
 		let block_group_results = heuristic
-			.run_async_sequential_with_metadata(
+			.run_async_parallel(
 				start_distribution,
-				|index, grouping, mut flag| async move {
-					if index == 0 {
-						flag = false;
-					}
-
-					// if the flag is set then we are going to change this grouping outcome to failures and not run anything
-					if flag {
-						return Ok((grouping.to_failures_prefer_instrumental(), flag));
-					}
-
+				|_index, grouping| async move {
 					let blocks = grouping.into_original();
 					let outcome = match self.submit_blocks(&blocks).await {
-						Ok(_) => GroupingOutcome::new_all_success(blocks.len()),
-						Err(_) => {
-							flag = true;
-							GroupingOutcome::new_apply(blocks)
+						Ok(_) => {
+							submitted.fetch_add(blocks.len(), std::sync::atomic::Ordering::SeqCst);
+							published.lock().unwrap_or_else(|e| e.into_inner()).extend(
+								blocks.iter().flat_map(|block| {
+									block.block.transactions.iter().map(Transaction::id)
+								}),
+							);
+							// a long batch which keeps submitting groups is still making progress
+							self.pass_through.status.proposer_heartbeat().await;
+							GroupingOutcome::new_all_success(blocks.len())
 						}
+						Err(_) => GroupingOutcome::new_apply(blocks),
 					};
+					self.metrics().observe_grouping_outcome(&outcome);
 
-					Ok((outcome, flag))
+					Ok(outcome)
 				},
-				false,
+				config.m1_da_light_node_submission_concurrency() as usize,
+				&ordering,
 			)
 			.await?;
 
-		info!("block group results: {:?}", block_group_results);
 		for block_group_result in &block_group_results {
 			info!(target: "movement_timing", block_group_result = ?block_group_result, "block_group_result");
 		}
 
-		Ok(())
+		Ok(submitted.load(std::sync::atomic::Ordering::SeqCst))
 	}
 
-	/// Reads blobs from the receiver until the building time is exceeded
-	async fn read_blocks(
-		&self,
-		receiver: &mut Receiver<Block>,
-	) -> Result<Vec<Block>, anyhow::Error> {
+	/// Reads blocks from the queue until the building time is exceeded
+	async fn read_blocks(&self, queue: &FairQueue<(), Block>) -> Result<Vec<Block>, anyhow::Error> {
 		let half_building_time = self.memseq.building_time_ms();
-		let start = std::time::Instant::now();
-		let mut blocks = Vec::new();
-		loop {
-			let remaining = match half_building_time.checked_sub(start.elapsed().as_millis() as u64)
-			{
-				Some(remaining) => remaining,
-				None => {
-					// we have exceeded the half building time
-					break;
-				}
-			};
-			match timeout(Duration::from_millis(remaining), receiver.recv()).await {
-				Ok(Some(block)) => {
-					// Process the block
-					blocks.push(block);
-				}
-				Ok(None) => {
-					// The channel was closed
-					info!("sender dropped");
-					break;
-				}
-				Err(_) => {
-					// The operation timed out
-					debug!(
-						target: "movement_timing",
-						batch_size = blocks.len(),
-						"timed_out_building_block"
-					);
-					break;
-				}
-			}
-		}
+		let limits = BatchLimits::new(Duration::from_millis(half_building_time));
+		let blocks = queue.next_batch(&limits).await?;
 
 		info!(target: "movement_timing", block_count = blocks.len(), "read_blocks");
 
@@ -218,13 +249,13 @@ impl LightNodeV1 {
 	}
 
 	/// Ticks the block proposer to build blocks and submit them
-	async fn tick_publish_blobs(
-		&self,
-		receiver: &mut Receiver<Block>,
-	) -> Result<(), anyhow::Error> {
+	///
+	/// The proposer is live while this finds no blocks to publish or submits groups of them.
+	async fn tick_publish_blobs(&self, queue: &FairQueue<(), Block>) -> Result<(), anyhow::Error> {
 		// get some blocks in a batch
-		let blocks = self.read_blocks(receiver).await?;
+		let blocks = self.read_blocks(queue).await?;
 		if blocks.is_empty() {
+			self.pass_through.status.proposer_heartbeat().await;
 			return Ok(());
 		}
 		let ids = blocks.iter().map(|b| b.id()).collect::<Vec<_>>();
@@ -241,34 +272,32 @@ impl LightNodeV1 {
 		Ok(())
 	}
 
-	async fn run_block_builder(&self, sender: Sender<Block>) -> Result<(), anyhow::Error> {
+	async fn run_block_builder(&self, queue: &FairQueue<(), Block>) -> Result<(), anyhow::Error> {
 		loop {
-			self.tick_build_blocks(sender.clone()).await?;
+			self.tick_build_blocks(queue).await?;
 		}
 	}
 
-	async fn run_block_publisher(
-		&self,
-		receiver: &mut Receiver<Block>,
-	) -> Result<(), anyhow::Error> {
+	async fn run_block_publisher(&self, queue: &FairQueue<(), Block>) -> Result<(), anyhow::Error> {
 		loop {
-			self.tick_publish_blobs(receiver).await?;
+			self.tick_publish_blobs(queue).await?;
 		}
 	}
 
 	pub async fn run_block_proposer(&self) -> Result<(), anyhow::Error> {
-		let (sender, mut receiver) = tokio::sync::mpsc::channel(2 ^ 10);
+		let queue = FairQueue::new(BLOCK_QUEUE_CAPACITY);
 
 		loop {
 			match futures::try_join!(
-				self.run_block_builder(sender.clone()),
-				self.run_block_publisher(&mut receiver),
+				self.run_block_builder(&queue),
+				self.run_block_publisher(&queue),
 			) {
 				Ok(_) => {
 					info!("block proposer completed");
 				}
 				Err(e) => {
 					info!("block proposer failed: {:?}", e);
+					self.pass_through.status.proposer_failed(&e).await;
 				}
 			}
 		}
@@ -276,6 +305,61 @@ impl LightNodeV1 {
 		Ok(())
 	}
 
+	/// Observes the blocks the other members of the sequencer set publish to the DA.
+	/// Does nothing if the memseq is not part of a sequencer set.
+	pub async fn run_rotation_follower(&self) -> Result<(), anyhow::Error> {
+		let sequencer_id = match self.memseq.rotation() {
+			Some(rotation) => rotation.sequencer_id().clone(),
+			None => return Ok(()),
+		};
+
+		let mut backoff = ROTATION_FOLLOWER_MIN_BACKOFF;
+		loop {
+			match self.follow_rotation(&sequencer_id).await {
+				Ok(_) => {
+					info!("rotation follower completed");
+					backoff = ROTATION_FOLLOWER_MIN_BACKOFF;
+					tokio::time::sleep(backoff).await;
+				}
+				Err(e) => {
+					info!("rotation follower failed, retrying in {:?}: {:?}", backoff, e);
+					tokio::time::sleep(backoff).await;
+					backoff = (backoff * 2).min(ROTATION_FOLLOWER_MAX_BACKOFF);
+				}
+			}
+		}
+	}
+
+	async fn follow_rotation(&self, sequencer_id: &Id) -> Result<(), anyhow::Error> {
+		let mut blobs = self
+			.pass_through
+			.stream_blobs_from_height_on(None, DEFAULT_NAMESPACE.to_string())
+			.await?;
+		while let Some(blob) = blobs.next().await {
+			let blob = blob?;
+			let block = match block::WrappedBlock::try_block_from_blob_data(&blob.data) {
+				Ok(block) => block,
+				Err(e) => {
+					debug!("skipping blob which is not a block: {:?}", e);
+					continue;
+				}
+			};
+
+			// our own blocks were accepted when they were built
+			match block.metadata.proposer_slot() {
+				Some((proposer, _)) if proposer != sequencer_id => {}
+				_ => continue,
+			}
+
+			// blob timestamps are the Celestia header time of their height, in microseconds
+			if let Err(e) = self.memseq.observe_block(&block, blob.timestamp / 1000).await {
+				info!(block_id = %block.id(), "rejected block from the sequencer set: {:?}", e);
+			}
+		}
+
+		Ok(())
+	}
+
 	pub fn to_sequenced_blob_block(
 		blob_response: BlobResponse,
 	) -> Result<BlobResponse, anyhow::Error> {
@@ -367,10 +451,17 @@ impl LightNodeService for LightNodeV1 {
 	}
 
 	/// Batch write blobs.
+	///
+	/// Blobs for the default namespace are transactions to be sequenced,
+	/// blobs for other namespaces are passed through to Celestia.
 	async fn batch_write(
 		&self,
 		request: tonic::Request<BatchWriteRequest>,
 	) -> std::result::Result<tonic::Response<BatchWriteResponse>, tonic::Status> {
+		if request.get_ref().namespace != DEFAULT_NAMESPACE {
+			return self.pass_through.batch_write(request).await;
+		}
+
 		let blobs_for_intent = request.into_inner().blobs;
 		let blobs_for_submission = blobs_for_intent.clone();
 		let height: u64 = self
@@ -403,10 +494,49 @@ impl LightNodeService for LightNodeV1 {
 		memseq
 			.publish_many(transactions)
 			.await
-			.map_err(|e| tonic::Status::internal(e.to_string()))?;
+			.map_err(memseq::service::status_from_publish_error)?;
 
 		Ok(tonic::Response::new(BatchWriteResponse { blobs: intents }))
 	}
+
+	/// Submit transactions to the sequencer.
+	async fn submit_transactions(
+		&self,
+		request: tonic::Request<SubmitTransactionsRequest>,
+	) -> std::result::Result<tonic::Response<SubmitTransactionsResponse>, tonic::Status> {
+		let transactions = request
+			.into_inner()
+			.transactions
+			.into_iter()
+			.map(|transaction| Transaction::new(transaction.data, transaction.sequence_number))
+			.collect::<Vec<_>>();
+		let transaction_ids =
+			transactions.iter().map(|transaction| transaction.id().to_vec()).collect();
+
+		self.memseq
+			.publish_many(transactions)
+			.await
+			.map_err(memseq::service::status_from_publish_error)?;
+
+		Ok(tonic::Response::new(SubmitTransactionsResponse { transaction_ids }))
+	}
+
+	/// Report the health of the Celestia connection, sync and block proposer.
+	async fn status(
+		&self,
+		_request: tonic::Request<StatusRequest>,
+	) -> std::result::Result<tonic::Response<StatusResponse>, tonic::Status> {
+		Ok(tonic::Response::new(self.node_status().await))
+	}
+
+	/// Get the verification parameters.
+	async fn get_verification_parameters(
+		&self,
+		request: tonic::Request<GetVerificationParametersRequest>,
+	) -> std::result::Result<tonic::Response<GetVerificationParametersResponse>, tonic::Status> {
+		self.pass_through.get_verification_parameters(request).await
+	}
+
 	/// Update and manage verification parameters.
 	async fn update_verification_parameters(
 		&self,
@@ -418,40 +548,56 @@ impl LightNodeService for LightNodeV1 {
 
 mod block {
 
+	use crate::v1::codec::BlobCodec;
 	use celestia_types::{nmt::Namespace, Blob};
 	use movement_algs::grouping_heuristic::{binpacking::BinpackingWeighted, splitting::Splitable};
 	use movement_types::Block;
 
+	/// A block together with the blob it is submitted as.
 	#[derive(Debug)]
 	pub struct WrappedBlock {
 		pub block: Block,
 		pub blob: Blob,
+		// kept to encode the blobs of the blocks this block is split into
+		namespace: Namespace,
+		codec: BlobCodec,
 	}
 
 	impl WrappedBlock {
-		pub fn try_new(block: Block, namespace: Namespace) -> Result<Self, anyhow::Error> {
+		pub fn try_new(
+			block: Block,
+			namespace: Namespace,
+			codec: &BlobCodec,
+		) -> Result<Self, anyhow::Error> {
 			// first serialize the block
 			let block_bytes = bcs::to_bytes(&block)?;
 
-			// then compress the block bytes
-			let compressed_block_bytes = zstd::encode_all(block_bytes.as_slice(), 0)?;
+			// then encode the block bytes
+			let encoded_block_bytes = codec.encode(&block_bytes)?;
+
+			// then create a blob from the encoded block bytes
+			let blob = Blob::new(namespace, encoded_block_bytes)?;
 
-			// then create a blob from the compressed block bytes
-			let blob = Blob::new(namespace, compressed_block_bytes)?;
+			Ok(Self { block, blob, namespace, codec: codec.clone() })
+		}
 
-			Ok(Self { block, blob })
+		/// Decodes a block from the data of a blob created by [WrappedBlock::try_new],
+		/// once the light node has decoded the data with its codec.
+		pub fn try_block_from_blob_data(data: &[u8]) -> Result<Block, anyhow::Error> {
+			let block = bcs::from_bytes(data)?;
+			Ok(block)
 		}
 	}
 
 	impl Splitable for WrappedBlock {
+		/// Splits the block and encodes each of the split blocks into its own blob.
 		fn split(self, factor: usize) -> Result<Vec<Self>, anyhow::Error> {
-			let split_blocks = self.block.split(factor)?;
-			let mut wrapped_blocks = Vec::new();
-			for block in split_blocks {
-				let wrapped_block = WrappedBlock { block, blob: self.blob.clone() };
-				wrapped_blocks.push(wrapped_block);
-			}
-			Ok(wrapped_blocks)
+			let WrappedBlock { block, namespace, codec, .. } = self;
+			block
+				.split(factor)?
+				.into_iter()
+				.map(|block| WrappedBlock::try_new(block, namespace, &codec))
+				.collect()
 		}
 	}
 
@@ -460,4 +606,76 @@ mod block {
 			self.blob.data.len()
 		}
 	}
+
+	#[cfg(test)]
+	pub mod test {
+
+		use super::*;
+		use crate::v1::codec::Codec;
+		use movement_types::Transaction;
+
+		fn block(transactions: usize) -> Block {
+			let transactions = (0..transactions)
+				.map(|i| Transaction::new(vec![i as u8; 256], i as u64))
+				.collect();
+			Block::new(Default::default(), vec![0], transactions)
+		}
+
+		fn decode(wrapped_block: &WrappedBlock, codec: &BlobCodec) -> Result<Block, anyhow::Error> {
+			WrappedBlock::try_block_from_blob_data(&codec.decode(&wrapped_block.blob.data)?)
+		}
+
+		#[test]
+		fn test_split_blocks_have_their_own_blobs() -> Result<(), anyhow::Error> {
+			let namespace = Namespace::new_v0(b"movement")?;
+			for codec in [BlobCodec::default(), BlobCodec::try_new(Codec::None, vec![])?] {
+				let original = block(16);
+				let wrapped_block = WrappedBlock::try_new(original.clone(), namespace, &codec)?;
+				let weight = wrapped_block.weight();
+
+				let mut splits = Vec::new();
+				for split in wrapped_block.split(2)? {
+					splits.extend(split.split(2)?);
+				}
+				assert_eq!(splits.len(), 4);
+
+				let mut transactions = Vec::new();
+				for split in &splits {
+					// each split blob decodes to its own block
+					let decoded = decode(split, &codec)?;
+					assert_eq!(decoded, split.block);
+					assert!(split.weight() < weight);
+					transactions.extend(decoded.transactions);
+				}
+
+				// the split blobs carry exactly the transactions of the original block
+				assert_eq!(transactions, original.transactions);
+				let split_bytes: usize =
+					transactions.iter().map(|transaction| transaction.data.len()).sum();
+				assert_eq!(split_bytes, 16 * 256);
+			}
+
+			Ok(())
+		}
+
+		#[test]
+		fn test_split_weights_follow_blob_sizes() -> Result<(), anyhow::Error> {
+			let namespace = Namespace::new_v0(b"movement")?;
+			let codec = BlobCodec::try_new(Codec::None, vec![])?;
+			let wrapped_block = WrappedBlock::try_new(block(16), namespace, &codec)?;
+			let weight = wrapped_block.weight();
+
+			let splits = wrapped_block.split(2)?;
+			let split_weight: usize = splits.iter().map(|split| split.weight()).sum();
+
+			// without compression, the splits only add the overhead of the extra block and blob
+			assert!(split_weight > weight);
+			assert!(split_weight < weight + 256);
+			for split in &splits {
+				assert_eq!(split.weight(), split.blob.data.len());
+			}
+
+			Ok(())
+		}
+	}
 }
@@ -0,0 +1,168 @@
+use m1_da_light_node_grpc::{ComponentStatus, StatusResponse};
+use std::sync::{
+	atomic::{AtomicU64, Ordering},
+	Arc,
+};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// How many blocks the Celestia node may lag behind the network head and still be caught up.
+pub const CAUGHT_UP_TOLERANCE: u64 = 2;
+
+pub fn healthy() -> ComponentStatus {
+	ComponentStatus { healthy: true, detail: String::new() }
+}
+
+pub fn unhealthy(detail: impl Into<String>) -> ComponentStatus {
+	ComponentStatus { healthy: false, detail: detail.into() }
+}
+
+/// Whether all components reported in the status are healthy.
+pub fn is_healthy(status: &StatusResponse) -> bool {
+	[&status.celestia_connection, &status.caught_up, &status.block_proposer]
+		.into_iter()
+		.flatten()
+		.all(|component| component.healthy)
+}
+
+/// Gets the status of the Celestia node's sync given its local head and the network head.
+pub fn caught_up_status(local_head_height: u64, network_head_height: u64) -> ComponentStatus {
+	let behind = network_head_height.saturating_sub(local_head_height);
+	if behind > CAUGHT_UP_TOLERANCE {
+		unhealthy(format!(
+			"Local head {} is {} blocks behind the network head {}",
+			local_head_height, behind, network_head_height
+		))
+	} else {
+		healthy()
+	}
+}
+
+#[derive(Debug, Default)]
+struct ProposerLiveness {
+	last_heartbeat: Option<Instant>,
+	last_error: Option<String>,
+	restarts: u64,
+}
+
+/// Tracks the background work of the light node for the status RPC.
+///
+/// Cloning shares the tracked state.
+#[derive(Debug, Clone, Default)]
+pub struct StatusTracker {
+	last_submission_height: Arc<AtomicU64>,
+	proposer: Arc<RwLock<ProposerLiveness>>,
+}
+
+impl StatusTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Records a successful blob submission at the given height.
+	pub fn record_submission(&self, height: u64) {
+		self.last_submission_height.fetch_max(height, Ordering::SeqCst);
+	}
+
+	/// Gets the height of the last successful blob submission, zero if there has been none.
+	pub fn last_submission_height(&self) -> u64 {
+		self.last_submission_height.load(Ordering::SeqCst)
+	}
+
+	/// Records that the block proposer loop made progress.
+	pub async fn proposer_heartbeat(&self) {
+		self.proposer.write().await.last_heartbeat = Some(Instant::now());
+	}
+
+	/// Records that the block proposer loop failed and is being restarted.
+	pub async fn proposer_failed(&self, error: &anyhow::Error) {
+		let mut proposer = self.proposer.write().await;
+		proposer.last_error = Some(error.to_string());
+		proposer.restarts += 1;
+	}
+
+	/// Gets the status of the block proposer loop.
+	///
+	/// The proposer is healthy if it made progress within the timeout,
+	/// even if it has recovered from failures since.
+	pub async fn proposer_status(&self, timeout: Duration) -> ComponentStatus {
+		let proposer = self.proposer.read().await;
+		let recovered = match &proposer.last_error {
+			Some(error) => {
+				format!("Restarted {} times, last error: {}", proposer.restarts, error)
+			}
+			None => String::new(),
+		};
+
+		match proposer.last_heartbeat {
+			Some(heartbeat) if heartbeat.elapsed() <= timeout => {
+				ComponentStatus { healthy: true, detail: recovered }
+			}
+			Some(heartbeat) => {
+				unhealthy(format!("No progress for {:?}. {}", heartbeat.elapsed(), recovered))
+			}
+			None => unhealthy(format!("Not started. {}", recovered)),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_caught_up_status() {
+		assert!(caught_up_status(100, 100).healthy);
+		assert!(caught_up_status(100 - CAUGHT_UP_TOLERANCE, 100).healthy);
+		assert!(!caught_up_status(99 - CAUGHT_UP_TOLERANCE, 100).healthy);
+		// a local head ahead of a stale network head is caught up
+		assert!(caught_up_status(101, 100).healthy);
+	}
+
+	#[tokio::test]
+	async fn test_proposer_status() -> Result<(), anyhow::Error> {
+		let tracker = StatusTracker::new();
+		let timeout = Duration::from_secs(60);
+		assert!(!tracker.proposer_status(timeout).await.healthy);
+
+		tracker.proposer_heartbeat().await;
+		assert!(tracker.proposer_status(timeout).await.healthy);
+
+		// a restarted proposer is still healthy, but reports the error
+		tracker.proposer_failed(&anyhow::anyhow!("submission failed")).await;
+		let status = tracker.proposer_status(timeout).await;
+		assert!(status.healthy);
+		assert!(status.detail.contains("submission failed"));
+
+		// a proposer which stopped making progress is unhealthy
+		tokio::time::sleep(Duration::from_millis(1)).await;
+		assert!(!tracker.proposer_status(Duration::ZERO).await.healthy);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_status_health() {
+		let mut status = StatusResponse {
+			celestia_connection: Some(healthy()),
+			caught_up: Some(healthy()),
+			block_proposer: None,
+			..Default::default()
+		};
+		assert!(is_healthy(&status));
+
+		status.block_proposer = Some(unhealthy("Not started."));
+		assert!(!is_healthy(&status));
+	}
+
+	#[test]
+	fn test_last_submission_height() {
+		let tracker = StatusTracker::new();
+		assert_eq!(tracker.last_submission_height(), 0);
+		tracker.record_submission(10);
+		// submissions may complete out of order
+		tracker.record_submission(8);
+		assert_eq!(tracker.last_submission_height(), 10);
+	}
+}
@@ -1,5 +1,6 @@
 use celestia_types::nmt::Namespace;
 use godfig::env_default;
+use std::collections::BTreeMap;
 
 // The default hostname for the Celestia RPC
 env_default!(
@@ -77,6 +78,154 @@ env_default!(
 	30730
 );
 
+// The default M1 DA Light Node metrics listen hostname
+env_default!(
+	default_m1_da_light_node_metrics_listen_hostname,
+	"M1_DA_LIGHT_NODE_METRICS_LISTEN_HOSTNAME",
+	String,
+	"0.0.0.0".to_string()
+);
+
+// The default M1 DA Light Node metrics listen port
+env_default!(
+	default_m1_da_light_node_metrics_listen_port,
+	"M1_DA_LIGHT_NODE_METRICS_LISTEN_PORT",
+	u16,
+	30736
+);
+
+// The path to the PEM certificate the M1 DA Light Node serves TLS with
+env_default!(default_m1_da_light_node_tls_cert_path, "M1_DA_LIGHT_NODE_TLS_CERT_PATH", String);
+
+// The path to the PEM private key the M1 DA Light Node serves TLS with
+env_default!(default_m1_da_light_node_tls_key_path, "M1_DA_LIGHT_NODE_TLS_KEY_PATH", String);
+
+// The path to the PEM CA certificate the M1 DA Light Node verifies client certificates with
+env_default!(
+	default_m1_da_light_node_tls_client_ca_path,
+	"M1_DA_LIGHT_NODE_TLS_CLIENT_CA_PATH",
+	String
+);
+
+// The bearer token to connect to the M1 DA Light Node with
+env_default!(
+	default_m1_da_light_node_connection_token,
+	"M1_DA_LIGHT_NODE_CONNECTION_TOKEN",
+	String
+);
+
+// The path to the PEM CA certificate to verify the M1 DA Light Node's TLS certificate with
+env_default!(
+	default_m1_da_light_node_connection_ca_cert_path,
+	"M1_DA_LIGHT_NODE_CONNECTION_CA_CERT_PATH",
+	String
+);
+
+// The bearer tokens of M1 DA Light Node clients
+pub fn default_m1_da_light_node_client_tokens() -> Vec<String> {
+	match std::env::var("M1_DA_LIGHT_NODE_CLIENT_TOKENS") {
+		Ok(val) => val.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect(),
+		Err(_) => vec![],
+	}
+}
+
+// The bearer tokens of M1 DA Light Node admins
+pub fn default_m1_da_light_node_admin_tokens() -> Vec<String> {
+	match std::env::var("M1_DA_LIGHT_NODE_ADMIN_TOKENS") {
+		Ok(val) => val.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect(),
+		Err(_) => vec![],
+	}
+}
+
+// The codec the M1 DA Light Node encodes the blobs of its default namespace with: none, zstd or
+// zstd-dictionary
+env_default!(
+	default_m1_da_light_node_blob_codec,
+	"M1_DA_LIGHT_NODE_BLOB_CODEC",
+	String,
+	"zstd".to_string()
+);
+
+// The codecs the M1 DA Light Node encodes the blobs of its named namespaces with, by name
+pub fn default_m1_da_light_node_blob_codecs() -> BTreeMap<String, String> {
+	match std::env::var("M1_DA_LIGHT_NODE_BLOB_CODECS") {
+		Ok(val) => serde_json::from_str(&val).unwrap_or_default(),
+		Err(_) => BTreeMap::new(),
+	}
+}
+
+// The zstd compression level the M1 DA Light Node encodes blobs with
+env_default!(default_m1_da_light_node_blob_zstd_level, "M1_DA_LIGHT_NODE_BLOB_ZSTD_LEVEL", i32, 0);
+
+// The path to the trained zstd dictionary of the M1 DA Light Node
+env_default!(
+	default_m1_da_light_node_blob_zstd_dictionary_path,
+	"M1_DA_LIGHT_NODE_BLOB_ZSTD_DICTIONARY_PATH",
+	String
+);
+
+// Whether the M1 DA Light Node samples the data square of each block it verifies a blob in
+env_default!(default_m1_da_light_node_das_enabled, "M1_DA_LIGHT_NODE_DAS_ENABLED", bool, false);
+
+// The least number of shares the M1 DA Light Node samples per block
+env_default!(default_m1_da_light_node_das_samples, "M1_DA_LIGHT_NODE_DAS_SAMPLES", u32, 16);
+
+// The confidence in the availability of a block the M1 DA Light Node samples until, in parts per
+// million
+env_default!(
+	default_m1_da_light_node_das_confidence_ppm,
+	"M1_DA_LIGHT_NODE_DAS_CONFIDENCE_PPM",
+	u32,
+	990_000
+);
+
+// How long the M1 DA Light Node waits for a sampled share before considering it unavailable
+env_default!(
+	default_m1_da_light_node_das_sample_timeout_ms,
+	"M1_DA_LIGHT_NODE_DAS_SAMPLE_TIMEOUT_MS",
+	u64,
+	10_000
+);
+
+// The number of blob verification results the M1 DA Light Node caches
+env_default!(
+	default_m1_da_light_node_verification_cache_size,
+	"M1_DA_LIGHT_NODE_VERIFICATION_CACHE_SIZE",
+	u64,
+	65_536
+);
+
+// The path to persist the blob verification results of the M1 DA Light Node to
+env_default!(
+	default_m1_da_light_node_verification_cache_path,
+	"M1_DA_LIGHT_NODE_VERIFICATION_CACHE_PATH",
+	String
+);
+
+// The number of block groups the M1 DA Light Node submits to Celestia at once
+env_default!(
+	default_m1_da_light_node_submission_concurrency,
+	"M1_DA_LIGHT_NODE_SUBMISSION_CONCURRENCY",
+	u32,
+	4
+);
+
+// Whether the block groups the M1 DA Light Node submits must land in order
+env_default!(
+	default_m1_da_light_node_ordered_submission,
+	"M1_DA_LIGHT_NODE_ORDERED_SUBMISSION",
+	bool,
+	true
+);
+
+// The hex SHA-256 fingerprints of the client certificates of M1 DA Light Node admins
+pub fn default_m1_da_light_node_admin_cert_fingerprints() -> Vec<String> {
+	match std::env::var("M1_DA_LIGHT_NODE_ADMIN_CERT_FINGERPRINTS") {
+		Ok(val) => val.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect(),
+		Err(_) => vec![],
+	}
+}
+
 // The default Celestia Namespace
 pub fn default_celestia_namespace() -> Namespace {
 	match std::env::var("CELESTIA_NAMESPACE") {
@@ -90,6 +239,14 @@ pub fn default_celestia_namespace() -> Namespace {
 	}
 }
 
+// The named Celestia Namespaces served alongside the default, as a JSON object
+pub fn default_celestia_namespaces() -> BTreeMap<String, Namespace> {
+	match std::env::var("CELESTIA_NAMESPACES") {
+		Ok(val) => serde_json::from_str(&val).unwrap_or_default(),
+		Err(_) => BTreeMap::new(),
+	}
+}
+
 // The default Celestia chain id
 env_default!(default_celestia_chain_id, "CELESTIA_CHAIN_ID", String, "movement".to_string());
 
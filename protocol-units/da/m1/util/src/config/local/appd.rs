@@ -1,12 +1,13 @@
 use crate::config::common::{
 	default_celestia_appd_replace_args, default_celestia_appd_use_replace_args,
-	default_celestia_chain_id, default_celestia_namespace, default_celestia_rpc_listen_hostname,
-	default_celestia_rpc_listen_port, default_celestia_websocket_connection_hostname,
-	default_celestia_websocket_connection_port,
+	default_celestia_chain_id, default_celestia_namespace, default_celestia_namespaces,
+	default_celestia_rpc_listen_hostname, default_celestia_rpc_listen_port,
+	default_celestia_websocket_connection_hostname, default_celestia_websocket_connection_port,
 };
 
 use celestia_types::nmt::Namespace;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The inner configuration for the local Celestia Appd Runner
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -38,6 +39,10 @@ pub struct Config {
 	#[serde(default = "default_celestia_namespace")]
 	pub celestia_namespace: Namespace,
 
+	/// Additional namespaces for the Celestia node, selected by name in requests
+	#[serde(default = "default_celestia_namespaces")]
+	pub celestia_namespaces: BTreeMap<String, Namespace>,
+
 	/// The celestia app path for when that is being orchestrated locally
 	/// This does not have a default because if it is needed, a default is generally not appropriate.
 	pub celestia_path: Option<String>,
@@ -66,6 +71,7 @@ impl Default for Config {
 			celestia_chain_id: default_celestia_chain_id(),
 			celestia_auth_token: None,
 			celestia_namespace: default_celestia_namespace(),
+			celestia_namespaces: default_celestia_namespaces(),
 			celestia_path: None,
 			celestia_validator_address: None,
 			celestia_appd_use_replace_args: default_celestia_appd_use_replace_args(),
@@ -1,10 +1,23 @@
 use crate::config::common::{
 	default_celestia_rpc_connection_hostname, default_celestia_rpc_connection_port,
 	default_celestia_websocket_connection_hostname, default_celestia_websocket_connection_port,
+	default_m1_da_light_node_admin_cert_fingerprints, default_m1_da_light_node_admin_tokens,
+	default_m1_da_light_node_blob_codec, default_m1_da_light_node_blob_codecs,
+	default_m1_da_light_node_blob_zstd_dictionary_path, default_m1_da_light_node_blob_zstd_level,
+	default_m1_da_light_node_client_tokens, default_m1_da_light_node_connection_ca_cert_path,
 	default_m1_da_light_node_connection_hostname, default_m1_da_light_node_connection_port,
-	default_m1_da_light_node_listen_hostname, default_m1_da_light_node_listen_port,
+	default_m1_da_light_node_connection_token, default_m1_da_light_node_das_confidence_ppm,
+	default_m1_da_light_node_das_enabled, default_m1_da_light_node_das_sample_timeout_ms,
+	default_m1_da_light_node_das_samples, default_m1_da_light_node_listen_hostname,
+	default_m1_da_light_node_listen_port, default_m1_da_light_node_metrics_listen_hostname,
+	default_m1_da_light_node_metrics_listen_port, default_m1_da_light_node_ordered_submission,
+	default_m1_da_light_node_submission_concurrency, default_m1_da_light_node_tls_cert_path,
+	default_m1_da_light_node_tls_client_ca_path, default_m1_da_light_node_tls_key_path,
+	default_m1_da_light_node_verification_cache_path,
+	default_m1_da_light_node_verification_cache_size,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The inner configuration for the local Celestia Appd Runner
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -40,6 +53,97 @@ pub struct Config {
 	/// The port for m1-da-light-node connection
 	#[serde(default = "default_m1_da_light_node_connection_port")]
 	pub m1_da_light_node_connection_port: u16,
+
+	/// The bearer token to connect to the m1-da-light-node service with
+	#[serde(default = "default_m1_da_light_node_connection_token")]
+	pub m1_da_light_node_connection_token: Option<String>,
+
+	/// The path to the PEM CA certificate to verify the m1-da-light-node service's TLS certificate
+	/// with. The connection uses TLS if set.
+	#[serde(default = "default_m1_da_light_node_connection_ca_cert_path")]
+	pub m1_da_light_node_connection_ca_cert_path: Option<String>,
+
+	/// The hostname to serve the m1-da-light-node Prometheus metrics on
+	#[serde(default = "default_m1_da_light_node_metrics_listen_hostname")]
+	pub m1_da_light_node_metrics_listen_hostname: String,
+
+	/// The port to serve the m1-da-light-node Prometheus metrics on
+	#[serde(default = "default_m1_da_light_node_metrics_listen_port")]
+	pub m1_da_light_node_metrics_listen_port: u16,
+
+	/// The path to the PEM certificate to serve TLS with. TLS is disabled if unset.
+	#[serde(default = "default_m1_da_light_node_tls_cert_path")]
+	pub m1_da_light_node_tls_cert_path: Option<String>,
+
+	/// The path to the PEM private key to serve TLS with
+	#[serde(default = "default_m1_da_light_node_tls_key_path")]
+	pub m1_da_light_node_tls_key_path: Option<String>,
+
+	/// The path to the PEM CA certificate to verify client certificates with. Enables mTLS.
+	#[serde(default = "default_m1_da_light_node_tls_client_ca_path")]
+	pub m1_da_light_node_tls_client_ca_path: Option<String>,
+
+	/// The bearer tokens which may read and write
+	#[serde(default = "default_m1_da_light_node_client_tokens")]
+	pub m1_da_light_node_client_tokens: Vec<String>,
+
+	/// The bearer tokens which may additionally call admin RPCs
+	#[serde(default = "default_m1_da_light_node_admin_tokens")]
+	pub m1_da_light_node_admin_tokens: Vec<String>,
+
+	/// The hex SHA-256 fingerprints of the client certificates which may call admin RPCs
+	#[serde(default = "default_m1_da_light_node_admin_cert_fingerprints")]
+	pub m1_da_light_node_admin_cert_fingerprints: Vec<String>,
+
+	/// The codec the blobs of the default namespace are encoded with: none, zstd or zstd-dictionary
+	#[serde(default = "default_m1_da_light_node_blob_codec")]
+	pub m1_da_light_node_blob_codec: String,
+
+	/// The codecs the blobs of the named namespaces are encoded with, by namespace name.
+	/// Each named namespace must have one.
+	#[serde(default = "default_m1_da_light_node_blob_codecs")]
+	pub m1_da_light_node_blob_codecs: BTreeMap<String, String>,
+
+	/// The zstd compression level blobs are encoded with
+	#[serde(default = "default_m1_da_light_node_blob_zstd_level")]
+	pub m1_da_light_node_blob_zstd_level: i32,
+
+	/// The path to the trained zstd dictionary, required by the zstd-dictionary codec
+	/// and to decode blobs encoded with it
+	#[serde(default = "default_m1_da_light_node_blob_zstd_dictionary_path")]
+	pub m1_da_light_node_blob_zstd_dictionary_path: Option<String>,
+
+	/// Whether to sample the data square of each block a blob is verified in
+	#[serde(default = "default_m1_da_light_node_das_enabled")]
+	pub m1_da_light_node_das_enabled: bool,
+
+	/// The least number of shares to sample per block
+	#[serde(default = "default_m1_da_light_node_das_samples")]
+	pub m1_da_light_node_das_samples: u32,
+
+	/// The confidence in the availability of a block to sample until, in parts per million
+	#[serde(default = "default_m1_da_light_node_das_confidence_ppm")]
+	pub m1_da_light_node_das_confidence_ppm: u32,
+
+	/// How long to wait for a sampled share before considering it unavailable
+	#[serde(default = "default_m1_da_light_node_das_sample_timeout_ms")]
+	pub m1_da_light_node_das_sample_timeout_ms: u64,
+
+	/// The number of blob verification results to cache. Zero disables the cache.
+	#[serde(default = "default_m1_da_light_node_verification_cache_size")]
+	pub m1_da_light_node_verification_cache_size: u64,
+
+	/// The path to persist blob verification results to. They are only kept in memory if unset.
+	#[serde(default = "default_m1_da_light_node_verification_cache_path")]
+	pub m1_da_light_node_verification_cache_path: Option<String>,
+
+	/// The number of block groups to submit to Celestia at once
+	#[serde(default = "default_m1_da_light_node_submission_concurrency")]
+	pub m1_da_light_node_submission_concurrency: u32,
+
+	/// Whether block groups must land in order, which submits them one at a time
+	#[serde(default = "default_m1_da_light_node_ordered_submission")]
+	pub m1_da_light_node_ordered_submission: bool,
 }
 
 impl Default for Config {
@@ -54,6 +158,36 @@ impl Default for Config {
 			m1_da_light_node_listen_port: default_m1_da_light_node_listen_port(),
 			m1_da_light_node_connection_hostname: default_m1_da_light_node_connection_hostname(),
 			m1_da_light_node_connection_port: default_m1_da_light_node_connection_port(),
+			m1_da_light_node_connection_token: default_m1_da_light_node_connection_token(),
+			m1_da_light_node_connection_ca_cert_path:
+				default_m1_da_light_node_connection_ca_cert_path(),
+			m1_da_light_node_metrics_listen_hostname:
+				default_m1_da_light_node_metrics_listen_hostname(),
+			m1_da_light_node_metrics_listen_port: default_m1_da_light_node_metrics_listen_port(),
+			m1_da_light_node_tls_cert_path: default_m1_da_light_node_tls_cert_path(),
+			m1_da_light_node_tls_key_path: default_m1_da_light_node_tls_key_path(),
+			m1_da_light_node_tls_client_ca_path: default_m1_da_light_node_tls_client_ca_path(),
+			m1_da_light_node_client_tokens: default_m1_da_light_node_client_tokens(),
+			m1_da_light_node_admin_tokens: default_m1_da_light_node_admin_tokens(),
+			m1_da_light_node_admin_cert_fingerprints:
+				default_m1_da_light_node_admin_cert_fingerprints(),
+			m1_da_light_node_blob_codec: default_m1_da_light_node_blob_codec(),
+			m1_da_light_node_blob_codecs: default_m1_da_light_node_blob_codecs(),
+			m1_da_light_node_blob_zstd_level: default_m1_da_light_node_blob_zstd_level(),
+			m1_da_light_node_blob_zstd_dictionary_path:
+				default_m1_da_light_node_blob_zstd_dictionary_path(),
+			m1_da_light_node_das_enabled: default_m1_da_light_node_das_enabled(),
+			m1_da_light_node_das_samples: default_m1_da_light_node_das_samples(),
+			m1_da_light_node_das_confidence_ppm: default_m1_da_light_node_das_confidence_ppm(),
+			m1_da_light_node_das_sample_timeout_ms: default_m1_da_light_node_das_sample_timeout_ms(
+			),
+			m1_da_light_node_verification_cache_size:
+				default_m1_da_light_node_verification_cache_size(),
+			m1_da_light_node_verification_cache_path:
+				default_m1_da_light_node_verification_cache_path(),
+			m1_da_light_node_submission_concurrency:
+				default_m1_da_light_node_submission_concurrency(),
+			m1_da_light_node_ordered_submission: default_m1_da_light_node_ordered_submission(),
 		}
 	}
 }
@@ -1,7 +1,9 @@
 use anyhow::Context;
 use celestia_rpc::Client;
 use celestia_types::nmt::Namespace;
+use memseq_util::Config as MemseqConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub mod common;
 pub mod local;
@@ -110,6 +112,15 @@ impl Config {
 		}
 	}
 
+	/// Gets the named Celestia namespaces served alongside the default namespace
+	pub fn celestia_namespaces(&self) -> BTreeMap<String, Namespace> {
+		match self {
+			Config::Local(local) => local.appd.celestia_namespaces.clone(),
+			Config::Arabica(local) => local.appd.celestia_namespaces.clone(),
+			Config::Mocha(local) => local.appd.celestia_namespaces.clone(),
+		}
+	}
+
 	/// Gets M1 DA Light Node listen hostname
 	pub fn m1_da_light_node_listen_hostname(&self) -> String {
 		match self {
@@ -161,6 +172,130 @@ impl Config {
 		}
 	}
 
+	/// Gets the bearer token to connect to the M1 DA Light Node with
+	pub fn m1_da_light_node_connection_token(&self) -> Option<String> {
+		self.m1_da_light_node_config().m1_da_light_node_connection_token.clone()
+	}
+
+	/// Gets the path to the CA certificate to verify the M1 DA Light Node's TLS certificate with
+	pub fn m1_da_light_node_connection_ca_cert_path(&self) -> Option<String> {
+		self.m1_da_light_node_config().m1_da_light_node_connection_ca_cert_path.clone()
+	}
+
+	/// Gets the URL to connect to the M1 DA Light Node at, which uses TLS if a CA certificate is set
+	pub fn m1_da_light_node_connection_url(&self) -> String {
+		let scheme = if self.m1_da_light_node_connection_ca_cert_path().is_some() {
+			"https"
+		} else {
+			"http"
+		};
+		format!(
+			"{}://{}:{}",
+			scheme,
+			self.m1_da_light_node_connection_hostname(),
+			self.m1_da_light_node_connection_port()
+		)
+	}
+
+	/// Gets M1 DA Light Node metrics listen hostname
+	pub fn m1_da_light_node_metrics_listen_hostname(&self) -> String {
+		match self {
+			Config::Local(local) => {
+				local.m1_da_light_node.m1_da_light_node_metrics_listen_hostname.clone()
+			}
+			Config::Arabica(local) => {
+				local.m1_da_light_node.m1_da_light_node_metrics_listen_hostname.clone()
+			}
+			Config::Mocha(local) => {
+				local.m1_da_light_node.m1_da_light_node_metrics_listen_hostname.clone()
+			}
+		}
+	}
+
+	/// Gets M1 DA Light Node metrics listen port
+	pub fn m1_da_light_node_metrics_listen_port(&self) -> u16 {
+		match self {
+			Config::Local(local) => local.m1_da_light_node.m1_da_light_node_metrics_listen_port,
+			Config::Arabica(local) => local.m1_da_light_node.m1_da_light_node_metrics_listen_port,
+			Config::Mocha(local) => local.m1_da_light_node.m1_da_light_node_metrics_listen_port,
+		}
+	}
+
+	/// Gets M1 DA Light Node metrics service
+	pub fn m1_da_light_node_metrics_service(&self) -> String {
+		let hostname = self.m1_da_light_node_metrics_listen_hostname();
+		let port = self.m1_da_light_node_metrics_listen_port();
+		format!("{}:{}", hostname, port)
+	}
+
+	/// Gets the codec the M1 DA Light Node encodes the blobs of its default namespace with
+	pub fn m1_da_light_node_blob_codec(&self) -> String {
+		match self {
+			Config::Local(local) => local.m1_da_light_node.m1_da_light_node_blob_codec.clone(),
+			Config::Arabica(local) => local.m1_da_light_node.m1_da_light_node_blob_codec.clone(),
+			Config::Mocha(local) => local.m1_da_light_node.m1_da_light_node_blob_codec.clone(),
+		}
+	}
+
+	/// Gets the codecs the M1 DA Light Node encodes the blobs of its named namespaces with
+	pub fn m1_da_light_node_blob_codecs(&self) -> BTreeMap<String, String> {
+		self.m1_da_light_node_config().m1_da_light_node_blob_codecs.clone()
+	}
+
+	/// Gets the zstd compression level the M1 DA Light Node encodes blobs with
+	pub fn m1_da_light_node_blob_zstd_level(&self) -> i32 {
+		match self {
+			Config::Local(local) => local.m1_da_light_node.m1_da_light_node_blob_zstd_level,
+			Config::Arabica(local) => local.m1_da_light_node.m1_da_light_node_blob_zstd_level,
+			Config::Mocha(local) => local.m1_da_light_node.m1_da_light_node_blob_zstd_level,
+		}
+	}
+
+	/// Gets the path to the trained zstd dictionary of the M1 DA Light Node
+	pub fn m1_da_light_node_blob_zstd_dictionary_path(&self) -> Option<String> {
+		self.m1_da_light_node_config().m1_da_light_node_blob_zstd_dictionary_path.clone()
+	}
+
+	/// Gets whether the M1 DA Light Node samples the data square of each block it verifies a blob in
+	pub fn m1_da_light_node_das_enabled(&self) -> bool {
+		self.m1_da_light_node_config().m1_da_light_node_das_enabled
+	}
+
+	/// Gets the least number of shares the M1 DA Light Node samples per block
+	pub fn m1_da_light_node_das_samples(&self) -> u32 {
+		self.m1_da_light_node_config().m1_da_light_node_das_samples
+	}
+
+	/// Gets the availability confidence the M1 DA Light Node samples until, in parts per million
+	pub fn m1_da_light_node_das_confidence_ppm(&self) -> u32 {
+		self.m1_da_light_node_config().m1_da_light_node_das_confidence_ppm
+	}
+
+	/// Gets how long the M1 DA Light Node waits for a sampled share, in milliseconds
+	pub fn m1_da_light_node_das_sample_timeout_ms(&self) -> u64 {
+		self.m1_da_light_node_config().m1_da_light_node_das_sample_timeout_ms
+	}
+
+	/// Gets the number of blob verification results the M1 DA Light Node caches
+	pub fn m1_da_light_node_verification_cache_size(&self) -> u64 {
+		self.m1_da_light_node_config().m1_da_light_node_verification_cache_size
+	}
+
+	/// Gets the path to persist the blob verification results of the M1 DA Light Node to
+	pub fn m1_da_light_node_verification_cache_path(&self) -> Option<String> {
+		self.m1_da_light_node_config().m1_da_light_node_verification_cache_path.clone()
+	}
+
+	/// Gets the number of block groups the M1 DA Light Node submits to Celestia at once
+	pub fn m1_da_light_node_submission_concurrency(&self) -> u32 {
+		self.m1_da_light_node_config().m1_da_light_node_submission_concurrency
+	}
+
+	/// Gets whether the block groups the M1 DA Light Node submits must land in order
+	pub fn m1_da_light_node_ordered_submission(&self) -> bool {
+		self.m1_da_light_node_config().m1_da_light_node_ordered_submission
+	}
+
 	/// Gets the memseq path
 	pub fn try_memseq_path(&self) -> Result<String, anyhow::Error> {
 		match self {
@@ -176,6 +311,24 @@ impl Config {
 		}
 	}
 
+	/// Gets the M1 DA Light Node config
+	pub fn m1_da_light_node_config(&self) -> &local::m1_da_light_node::Config {
+		match self {
+			Config::Local(local) => &local.m1_da_light_node,
+			Config::Arabica(local) => &local.m1_da_light_node,
+			Config::Mocha(local) => &local.m1_da_light_node,
+		}
+	}
+
+	/// Gets the memseq config
+	pub fn memseq_config(&self) -> &MemseqConfig {
+		match self {
+			Config::Local(local) => &local.memseq,
+			Config::Arabica(local) => &local.memseq,
+			Config::Mocha(local) => &local.memseq,
+		}
+	}
+
 	pub fn try_block_building_parameters(&self) -> Result<(u32, u64), anyhow::Error> {
 		match self {
 			Config::Local(local) => Ok((local.memseq.memseq_max_block_size, local.memseq.memseq_build_time)),
@@ -209,4 +362,9 @@ impl M1DaLightNodeConfig {
 	pub fn celestia_namespace(&self) -> Namespace {
 		self.m1_da_light_node_config.celestia_namespace()
 	}
+
+	/// Gets the named Celestia namespaces served alongside the default namespace
+	pub fn celestia_namespaces(&self) -> BTreeMap<String, Namespace> {
+		self.m1_da_light_node_config.celestia_namespaces()
+	}
 }
@@ -3,17 +3,20 @@ pub mod v1;
 use aptos_api::runtime::Apis;
 pub use aptos_crypto::hash::HashValue;
 pub use aptos_types::{
+	account_address::AccountAddress,
 	block_executor::partitioner::ExecutableBlock,
 	block_executor::partitioner::ExecutableTransactions,
 	block_metadata::BlockMetadata,
 	transaction::signature_verified_transaction::SignatureVerifiedTransaction,
 	transaction::{SignedTransaction, Transaction},
 };
+pub use maptos_opt_executor::gas::BlockGasReport;
 
 use movement_types::BlockCommitment;
 
 use async_channel::Sender;
 use async_trait::async_trait;
+use std::path::Path;
 
 #[async_trait]
 pub trait DynOptFinExecutor {
@@ -29,6 +32,13 @@ pub trait DynOptFinExecutor {
 		block: ExecutableBlock,
 	) -> Result<BlockCommitment, anyhow::Error>;
 
+	/// Executes a block optimistically, and reports the gas it used and the transactions it left
+	/// out once it reached its gas limit.
+	async fn execute_block_opt_with_report(
+		&self,
+		block: ExecutableBlock,
+	) -> Result<(BlockCommitment, BlockGasReport), anyhow::Error>;
+
 	/// Update the height of the latest finalized block
 	fn set_finalized_block_height(&self, block_height: u64) -> Result<(), anyhow::Error>;
 
@@ -51,8 +61,29 @@ pub trait DynOptFinExecutor {
 		timestamp: u64,
 	) -> Result<BlockMetadata, anyhow::Error>;
 
-	/// Rollover the genesis block
-	async fn rollover_genesis_block(&self) -> Result<(), anyhow::Error>;
+	/// Rolls over the genesis block at a timestamp, which must be the same on every node.
+	async fn rollover_genesis_block(&self, timestamp: u64) -> Result<(), anyhow::Error>;
+
+	/// Gets the id of the genesis block, which commits to the genesis the node booted from.
+	fn genesis_block_id(&self) -> Result<HashValue, anyhow::Error>;
+
+	/// Gets the signed transactions of the genesis block, if this node holds the root key.
+	fn genesis_transactions(&self) -> Result<Vec<SignedTransaction>, anyhow::Error>;
+
+	/// Reverts the optimistic state to the block at the height, discarding the blocks above it.
+	async fn revert_block_head_to(&self, block_height: u64) -> Result<(), anyhow::Error>;
+
+	/// Exports a snapshot of the optimistic state at the block head into a directory, and gets
+	/// the commitment of the block head.
+	async fn export_state_snapshot(&self, path: &Path) -> Result<BlockCommitment, anyhow::Error>;
+
+	/// Imports a snapshot of the state from a directory if it is of the trusted commitment, and
+	/// gets the height of the block it was taken at, which becomes the block head.
+	async fn import_state_snapshot(
+		&self,
+		path: &Path,
+		trusted_commitment: &BlockCommitment,
+	) -> Result<u64, anyhow::Error>;
 
 	/// Decrements transactions in flight on the transaction channel.
 	fn decrement_transactions_in_flight(&self, count: u64);
@@ -1,13 +1,17 @@
-use crate::{BlockMetadata, DynOptFinExecutor, ExecutableBlock, HashValue, SignedTransaction};
+use crate::{
+	BlockGasReport, BlockMetadata, DynOptFinExecutor, ExecutableBlock, HashValue, SignedTransaction,
+};
 use aptos_api::runtime::Apis;
 use aptos_config::config::NodeConfig;
 use aptos_mempool::core_mempool::CoreMempool;
 use async_channel::Sender;
 use async_trait::async_trait;
 use maptos_fin_view::FinalityView;
+use maptos_opt_executor::snapshot::STATE_SNAPSHOT_CHUNK_SIZE;
 use maptos_opt_executor::transaction_pipe::TransactionPipeError;
 use maptos_opt_executor::Executor as OptExecutor;
 use movement_types::BlockCommitment;
+use std::path::Path;
 use std::sync::atomic::Ordering;
 use tokio::time::interval;
 use tokio::time::Duration;
@@ -92,6 +96,14 @@ impl DynOptFinExecutor for Executor {
 		self.executor.execute_block(block).await
 	}
 
+	async fn execute_block_opt_with_report(
+		&self,
+		block: ExecutableBlock,
+	) -> Result<(BlockCommitment, BlockGasReport), anyhow::Error> {
+		debug!("Executing block: {:?}", block.block_id);
+		self.executor.execute_block_with_report(block).await
+	}
+
 	fn set_finalized_block_height(&self, height: u64) -> Result<(), anyhow::Error> {
 		self.finality_view.set_finalized_block_height(height)
 	}
@@ -121,15 +133,41 @@ impl DynOptFinExecutor for Executor {
 		timestamp: u64,
 	) -> Result<BlockMetadata, anyhow::Error> {
 		let (epoch, round) = self.executor.get_next_epoch_and_round().await?;
-		let signer = &self.executor.signer;
+		let proposer = &self.executor.proposer;
 
 		// Create a block metadata transaction.
-		Ok(BlockMetadata::new(block_id, epoch, round, signer.author(), vec![], vec![], timestamp))
+		Ok(BlockMetadata::new(block_id, epoch, round, proposer.author(), vec![], vec![], timestamp))
 	}
 
 	/// Rollover the genesis block
-	async fn rollover_genesis_block(&self) -> Result<(), anyhow::Error> {
-		self.executor.rollover_genesis_now().await
+	async fn rollover_genesis_block(&self, timestamp: u64) -> Result<(), anyhow::Error> {
+		self.executor.rollover_genesis(timestamp).await
+	}
+
+	fn genesis_block_id(&self) -> Result<HashValue, anyhow::Error> {
+		self.executor.genesis_block_id()
+	}
+
+	fn genesis_transactions(&self) -> Result<Vec<SignedTransaction>, anyhow::Error> {
+		self.executor.genesis_transactions()
+	}
+
+	async fn revert_block_head_to(&self, block_height: u64) -> Result<(), anyhow::Error> {
+		self.executor.revert_block_head_to(block_height).await
+	}
+
+	async fn export_state_snapshot(&self, path: &Path) -> Result<BlockCommitment, anyhow::Error> {
+		let manifest = self.executor.export_state_snapshot(path, STATE_SNAPSHOT_CHUNK_SIZE).await?;
+		Ok(manifest.commitment())
+	}
+
+	async fn import_state_snapshot(
+		&self,
+		path: &Path,
+		trusted_commitment: &BlockCommitment,
+	) -> Result<u64, anyhow::Error> {
+		let manifest = self.executor.import_state_snapshot(path, trusted_commitment).await?;
+		Ok(manifest.block_height)
 	}
 
 	fn decrement_transactions_in_flight(&self, count: u64) {
@@ -126,8 +126,8 @@ mod tests {
 
 			let block_id = HashValue::random(); // Generate a random block ID for each block.
 
-			// Clone the signer from the executor for signing the metadata.
-			let signer = executor.signer.clone();
+			// Clone the proposer of the executor for the metadata.
+			let proposer = executor.proposer.clone();
 			// Get the current time in microseconds for the block timestamp.
 			let current_time_micros = chrono::Utc::now().timestamp_micros() as u64;
 
@@ -136,7 +136,7 @@ mod tests {
 				block_id,
 				epoch,
 				round,
-				signer.author(),
+				proposer.author(),
 				vec![],
 				vec![],
 				current_time_micros,
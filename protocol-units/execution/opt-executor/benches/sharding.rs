@@ -0,0 +1,170 @@
+//! Compares sharded and unsharded execution of blocks in which many accounts transact at once, like
+//! the wallets of the howzit benchmark.
+//!
+//! Each account sends its transfers in a run to a recipient of its own, so no two accounts conflict
+//! and the partitioner shards every block after the one which funds the accounts.
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, Uniform};
+use aptos_sdk::{
+	transaction_builder::TransactionFactory,
+	types::{AccountKey, LocalAccount},
+};
+use aptos_types::{
+	account_config::aptos_test_root_address,
+	block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
+	block_metadata::BlockMetadata,
+	transaction::{
+		signature_verified_transaction::into_signature_verified_block, SignedTransaction,
+		Transaction,
+	},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use maptos_execution_util::config::Config;
+use maptos_opt_executor::{partitioner::AccessPartitioner, Executor};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+/// The accounts transacting in each block.
+const ACCOUNTS: usize = 64;
+
+/// The transactions each account sends per block.
+const TRANSACTIONS_PER_ACCOUNT: usize = 4;
+
+/// The number of shards, which the `AptosVM` sets once per process.
+const SHARDS: usize = 4;
+
+type Partitioner = fn() -> Option<AccessPartitioner>;
+
+/// Executes blocks unsharded and sharded.
+fn partitioners() -> Vec<(&'static str, Partitioner)> {
+	vec![("unsharded", || None), ("sharded", || Some(AccessPartitioner::new(SHARDS)))]
+}
+
+/// An executor with funded accounts.
+struct Workload {
+	executor: Executor,
+	tx_factory: TransactionFactory,
+	accounts: Vec<LocalAccount>,
+	recipients: Vec<LocalAccount>,
+	blocks: u64,
+	_tempdir: TempDir,
+}
+
+impl Workload {
+	async fn try_new(partitioner: Option<AccessPartitioner>) -> Result<Self, anyhow::Error> {
+		let tempdir = tempfile::tempdir()?;
+		let mut maptos_config = Config::default();
+		maptos_config.chain.maptos_private_key = Ed25519PrivateKey::generate_for_testing();
+		maptos_config.chain.maptos_db_path.replace(tempdir.path().to_path_buf());
+		let executor = Executor::try_from_config(&maptos_config)?;
+		let executor = match partitioner {
+			Some(partitioner) => executor.with_partitioner(partitioner),
+			None => executor,
+		};
+		executor.rollover_genesis_now().await?;
+
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+		let tx_factory = TransactionFactory::new(maptos_config.chain.maptos_chain_id.clone());
+		let mut rng = StdRng::from_seed([7u8; 32]);
+		let accounts: Vec<LocalAccount> =
+			(0..ACCOUNTS).map(|_| LocalAccount::generate(&mut rng)).collect();
+		let recipients: Vec<LocalAccount> =
+			(0..ACCOUNTS).map(|_| LocalAccount::generate(&mut rng)).collect();
+
+		let mut workload =
+			Self { executor, tx_factory, accounts, recipients, blocks: 0, _tempdir: tempdir };
+		let mut funding = Vec::new();
+		for account in &workload.accounts {
+			funding.push(root_account.sign_with_transaction_builder(
+				workload.tx_factory.create_user_account(account.public_key()),
+			));
+			funding.push(root_account.sign_with_transaction_builder(
+				workload.tx_factory.mint(account.address(), 1_000_000_000),
+			));
+		}
+		workload.execute(funding).await?;
+
+		Ok(workload)
+	}
+
+	/// Signs the transfers of the next block, those of each account in a run.
+	fn transfers(&self) -> Vec<SignedTransaction> {
+		let mut transfers = Vec::new();
+		for (account, recipient) in self.accounts.iter().zip(&self.recipients) {
+			for _ in 0..TRANSACTIONS_PER_ACCOUNT {
+				transfers.push(
+					account.sign_with_transaction_builder(
+						self.tx_factory
+							.payload(aptos_stdlib::aptos_account_transfer(recipient.address(), 1)),
+					),
+				);
+			}
+		}
+		transfers
+	}
+
+	/// Executes a block of the transactions, and gets how long the execution took.
+	async fn execute(
+		&mut self,
+		transactions: Vec<SignedTransaction>,
+	) -> Result<Duration, anyhow::Error> {
+		let (epoch, round) = self.executor.get_next_epoch_and_round().await?;
+		let block_id = HashValue::sha3_256_of(&self.blocks.to_le_bytes());
+		self.blocks += 1;
+		let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			self.executor.proposer.author(),
+			vec![],
+			vec![],
+			chrono::Utc::now().timestamp_micros() as u64,
+		));
+		let transactions = into_signature_verified_block(
+			std::iter::once(block_metadata)
+				.chain(transactions.into_iter().map(Transaction::UserTransaction))
+				.collect(),
+		);
+		let block = ExecutableBlock::new(block_id, ExecutableTransactions::Unsharded(transactions));
+
+		let start = Instant::now();
+		self.executor.execute_block(block).await?;
+		Ok(start.elapsed())
+	}
+}
+
+fn bench_sharding(c: &mut Criterion) {
+	let runtime = Runtime::new().expect("failed to start the runtime");
+	let mut group = c.benchmark_group("execute-block");
+	group.sample_size(10);
+
+	for (name, partitioner) in partitioners() {
+		let mut workload = runtime
+			.block_on(Workload::try_new(partitioner()))
+			.expect("failed to set up the workload");
+		group.bench_function(BenchmarkId::new(name, ACCOUNTS * TRANSACTIONS_PER_ACCOUNT), |b| {
+			b.iter_custom(|iters| {
+				let mut elapsed = Duration::ZERO;
+				for _ in 0..iters {
+					let transfers = workload.transfers();
+					elapsed += runtime
+						.block_on(workload.execute(transfers))
+						.expect("failed to execute the block");
+				}
+				elapsed
+			});
+		});
+	}
+
+	group.finish();
+}
+
+criterion_group!(benches, bench_sharding);
+criterion_main!(benches);
@@ -1,15 +1,11 @@
-use super::Executor;
+use super::{gas::BlockGasReport, Executor};
 use aptos_api::Context;
 use aptos_crypto::HashValue;
 use aptos_executor_types::BlockExecutorTrait;
 use aptos_types::transaction::signature_verified_transaction::into_signature_verified_block;
 use aptos_types::{
-	account_address::AccountAddress,
 	aggregate_signature::AggregateSignature,
-	block_executor::{
-		config::BlockExecutorConfigFromOnchain,
-		partitioner::{ExecutableBlock, ExecutableTransactions},
-	},
+	block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
 	block_info::BlockInfo,
 	block_metadata::BlockMetadata,
 	epoch_state::EpochState,
@@ -21,12 +17,24 @@ use movement_types::{BlockCommitment, Commitment, Id};
 use std::sync::Arc;
 use tracing::{debug, debug_span, info};
 
+/// The most transaction outputs of a block read back at once.
+const OUTPUTS_PER_READ: u64 = 1_000;
+
 impl Executor {
 	pub async fn execute_block(
 		&self,
 		block: ExecutableBlock,
 	) -> Result<BlockCommitment, anyhow::Error> {
-		let (block_metadata, block, senders_and_sequence_numbers) = {
+		let (commitment, _) = self.execute_block_with_report(block).await?;
+		Ok(commitment)
+	}
+
+	/// Executes and commits a block within the block gas limit, and reports the gas it used.
+	pub async fn execute_block_with_report(
+		&self,
+		block: ExecutableBlock,
+	) -> Result<(BlockCommitment, BlockGasReport), anyhow::Error> {
+		let (block_metadata, block, input_transactions) = {
 			// get the block metadata transaction
 			let metadata_access_block = block.transactions.clone();
 			let metadata_access_transactions = metadata_access_block.into_txns();
@@ -41,38 +49,33 @@ impl Executor {
 				}
 			};
 
-			// senders and sequence numbers
-			let senders_and_sequence_numbers = metadata_access_transactions
+			// the transactions, which the statuses of the block are matched with
+			let input_transactions = metadata_access_transactions
 				.iter()
-				.map(|transaction| match transaction.clone().into_inner() {
-					Transaction::UserTransaction(transaction) => {
-						(transaction.sender(), transaction.sequence_number())
-					}
-					_ => (AccountAddress::ZERO, 0),
-				})
-				.collect::<Vec<(AccountAddress, u64)>>();
+				.map(|transaction| transaction.clone().into_inner())
+				.collect::<Vec<Transaction>>();
 
-			// reconstruct the block
-			let block = ExecutableBlock::new(
-				block.block_id.clone(),
-				ExecutableTransactions::Unsharded(metadata_access_transactions),
-			);
+			// reconstruct the block, sharded if there is a partitioner
+			let transactions = match &self.partitioner {
+				Some(partitioner) => partitioner.partition(metadata_access_transactions)?,
+				None => ExecutableTransactions::Unsharded(metadata_access_transactions),
+			};
+			let block = ExecutableBlock::new(block.block_id.clone(), transactions);
 
-			(block_metadata, block, senders_and_sequence_numbers)
+			(block_metadata, block, input_transactions)
 		};
 
 		let block_executor = self.block_executor.clone();
 
 		let block_id = block.block_id.clone();
 		let parent_block_id = block_executor.committed_block_id();
+		let first_version = self.db.reader.get_synced_version()? + 1;
 
+		let block_executor_config = self.block_executor_config()?;
+		let block_gas_limit = block_executor_config.block_gas_limit_type.block_gas_limit();
 		let block_executor_clone = block_executor.clone();
 		let state_compute = tokio::task::spawn_blocking(move || {
-			block_executor_clone.execute_block(
-				block,
-				parent_block_id,
-				BlockExecutorConfigFromOnchain::new_no_block_limit(),
-			)
+			block_executor_clone.execute_block(block, parent_block_id, block_executor_config)
 		})
 		.await??;
 
@@ -80,6 +83,8 @@ impl Executor {
 
 		let version = state_compute.version();
 		debug!("Block execution computed the following version: {:?}", version);
+		let mut gas_report = BlockGasReport::new(block_gas_limit);
+		gas_report.add_statuses(&input_transactions, state_compute.compute_status_for_input_txns());
 		let (epoch, round) = (block_metadata.epoch(), block_metadata.round());
 
 		let ledger_info_with_sigs = self.ledger_info_with_sigs(
@@ -96,6 +101,15 @@ impl Executor {
 		})
 		.await??;
 
+		// the outputs are read back for the gas they used
+		let mut start_version = first_version;
+		while start_version <= version {
+			let limit = (version + 1 - start_version).min(OUTPUTS_PER_READ);
+			let outputs = self.db.reader.get_transaction_outputs(start_version, limit, version)?;
+			gas_report.add_outputs(&outputs.transactions_and_outputs);
+			start_version += limit;
+		}
+
 		let proof = {
 			let reader = self.db.reader.clone();
 			reader.get_state_proof(version)?
@@ -106,12 +120,26 @@ impl Executor {
 		// Race conditions, anyone?
 		let block_height = self.get_block_head_height()?;
 
+		gas_report.block_height = block_height;
+		info!(
+			target: "movement_timing",
+			block_height,
+			gas_units_used = gas_report.gas_units_used,
+			effective_block_gas_limit = ?gas_report.effective_block_gas_limit,
+			committed = gas_report.committed,
+			retried = gas_report.retried.len(),
+			"block_gas"
+		);
+
 		let commitment = Commitment::digest_state_proof(&proof);
-		Ok(BlockCommitment {
-			block_id: Id(*block_id.clone()),
-			commitment,
-			height: block_height.into(),
-		})
+		Ok((
+			BlockCommitment {
+				block_id: Id(*block_id.clone()),
+				commitment,
+				height: block_height.into(),
+			},
+			gas_report,
+		))
 	}
 
 	pub fn get_block_head_height(&self) -> Result<u64, anyhow::Error> {
@@ -119,6 +147,41 @@ impl Executor {
 		Ok(ledger_info.block_height.into())
 	}
 
+	/// Reverts the committed state to the end of the block at the height, discarding the blocks
+	/// above it.
+	pub async fn revert_block_head_to(&self, block_height: u64) -> Result<(), anyhow::Error> {
+		let head_height = self.get_block_head_height()?;
+		if block_height >= head_height {
+			return Ok(());
+		}
+		info!("Reverting block head from height {} to {}", head_height, block_height);
+
+		// rebuild the ledger info of the block, which is the one its commit wrote
+		let (_, end_version, block_event) =
+			self.db.reader.get_block_info_by_height(block_height)?;
+		let root_hash = self.db.reader.get_accumulator_root_hash(end_version)?;
+		let ledger_info_with_sigs = self.ledger_info_with_sigs(
+			block_event.epoch(),
+			block_event.round(),
+			block_event.hash()?,
+			block_event.proposed_time(),
+			root_hash,
+			end_version,
+		);
+
+		// the block executor caches the committed block, so it is reset onto the reverted state
+		let db_writer = self.db.writer.clone();
+		let block_executor = self.block_executor.clone();
+		tokio::task::spawn_blocking(move || {
+			db_writer.revert_commit(&ledger_info_with_sigs)?;
+			block_executor.reset()?;
+			Ok::<(), anyhow::Error>(())
+		})
+		.await??;
+
+		Ok(())
+	}
+
 	pub fn context(&self) -> Arc<Context> {
 		self.context.clone()
 	}
@@ -136,9 +199,12 @@ impl Executor {
 		Ok(ledger_info.ledger_info().timestamp_usecs())
 	}
 
+	/// Executes the genesis block, which rolls over the epoch of the genesis transaction.
+	///
+	/// Its id commits to the genesis, so nodes which roll over at the same timestamp agree on it.
 	pub async fn rollover_genesis(&self, timestamp: u64) -> Result<(), anyhow::Error> {
 		let (epoch, round) = self.get_next_epoch_and_round().await?;
-		let block_id = HashValue::random();
+		let block_id = self.genesis_block_id()?;
 
 		// genesis timestamp should always be 0
 		let genesis_timestamp = self.get_last_state_timestamp_micros().await?;
@@ -151,7 +217,7 @@ impl Executor {
 			block_id,
 			epoch,
 			round,
-			self.signer.author(),
+			self.proposer.author(),
 			vec![],
 			vec![],
 			timestamp,
@@ -195,8 +261,8 @@ impl Executor {
 			Some(EpochState {
 				epoch,
 				verifier: ValidatorVerifier::new(vec![ValidatorConsensusInfo::new(
-					self.signer.author(),
-					self.signer.public_key(),
+					self.proposer.author(),
+					self.proposer.public_key(),
 					100_000_000,
 				)]),
 			}),
@@ -216,7 +282,9 @@ impl Executor {
 mod tests {
 
 	use super::*;
+	use crate::executor::partitioner::AccessPartitioner;
 	use aptos_api::accept_type::AcceptType;
+	use aptos_cached_packages::aptos_stdlib;
 	use aptos_crypto::{
 		ed25519::{Ed25519PrivateKey, Ed25519Signature},
 		HashValue, PrivateKey, Uniform,
@@ -265,7 +333,7 @@ mod tests {
 			block_id,
 			0,
 			0,
-			executor.signer.author(),
+			executor.proposer.author(),
 			vec![],
 			vec![],
 			chrono::Utc::now().timestamp_micros() as u64,
@@ -311,8 +379,8 @@ mod tests {
 
 			// Generate a random block ID.
 			let block_id = HashValue::random();
-			// Clone the signer from the executor for signing the metadata.
-			let signer = executor.signer.clone();
+			// Clone the proposer of the executor for the metadata.
+			let proposer = executor.proposer.clone();
 			// Get the current time in microseconds for the block timestamp.
 			let current_time_microseconds = chrono::Utc::now().timestamp_micros() as u64;
 
@@ -328,7 +396,7 @@ mod tests {
 				block_id,
 				epoch,
 				round,
-				signer.author(),
+				proposer.author(),
 				vec![],
 				vec![],
 				current_time_microseconds,
@@ -416,8 +484,8 @@ mod tests {
 
 			let block_id = HashValue::random(); // Generate a random block ID for each block.
 
-			// Clone the signer from the executor for signing the metadata.
-			let signer = executor.signer.clone();
+			// Clone the proposer of the executor for the metadata.
+			let proposer = executor.proposer.clone();
 			// Get the current time in microseconds for the block timestamp.
 			let current_time_microseconds = chrono::Utc::now().timestamp_micros() as u64;
 
@@ -426,7 +494,7 @@ mod tests {
 				block_id,
 				epoch,
 				round,
-				signer.author(),
+				proposer.author(),
 				vec![],
 				vec![],
 				current_time_microseconds,
@@ -466,4 +534,233 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_execute_block_sharded_matches_unsharded() -> Result<(), anyhow::Error> {
+		// the same blocks must commit the same state with or without a partitioner
+		let private_key = Ed25519PrivateKey::generate_for_testing();
+		let (sharded, _sharded_tempdir) = Executor::try_test_default(private_key.clone())?;
+		let sharded = sharded.with_partitioner(AccessPartitioner::new(2));
+		let (unsharded, _unsharded_tempdir) = Executor::try_test_default(private_key.clone())?;
+		assert!(unsharded.partitioner.is_none());
+
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(sharded.maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+		let tx_factory =
+			TransactionFactory::new(sharded.maptos_config.chain.maptos_chain_id.clone());
+		let mut rng = ::rand::rngs::StdRng::from_seed([5u8; 32]);
+		let accounts: Vec<LocalAccount> =
+			(0..8).map(|_| LocalAccount::generate(&mut rng)).collect();
+		let recipients: Vec<LocalAccount> =
+			(0..8).map(|_| LocalAccount::generate(&mut rng)).collect();
+
+		// the first block funds the accounts, and the following blocks transfer between them
+		let mut blocks = vec![vec![]];
+		for account in &accounts {
+			blocks[0].push(root_account.sign_with_transaction_builder(
+				tx_factory.create_user_account(account.public_key()),
+			));
+			blocks[0].push(
+				root_account
+					.sign_with_transaction_builder(tx_factory.mint(account.address(), 10_000)),
+			);
+		}
+		for _ in 0..4 {
+			let block = accounts
+				.iter()
+				.zip(&recipients)
+				.map(|(account, recipient)| {
+					account.sign_with_transaction_builder(
+						tx_factory
+							.payload(aptos_stdlib::aptos_account_transfer(recipient.address(), 10)),
+					)
+				})
+				.collect();
+			blocks.push(block);
+		}
+
+		for (i, transactions) in blocks.into_iter().enumerate() {
+			let (epoch, round) = sharded.get_next_epoch_and_round().await?;
+			let block_id = HashValue::sha3_256_of(&i.to_le_bytes());
+			let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+				block_id,
+				epoch,
+				round,
+				sharded.proposer.author(),
+				vec![],
+				vec![],
+				chrono::Utc::now().timestamp_micros() as u64,
+			));
+			let transactions = into_signature_verified_block(
+				std::iter::once(block_metadata)
+					.chain(transactions.into_iter().map(Transaction::UserTransaction))
+					.collect(),
+			);
+
+			let sharded_commitment = sharded
+				.execute_block(ExecutableBlock::new(
+					block_id,
+					ExecutableTransactions::Unsharded(transactions.clone()),
+				))
+				.await?;
+			let unsharded_commitment = unsharded
+				.execute_block(ExecutableBlock::new(
+					block_id,
+					ExecutableTransactions::Unsharded(transactions),
+				))
+				.await?;
+			// the commitments digest the state proofs, which commit to the state roots
+			assert_eq!(sharded_commitment, unsharded_commitment);
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_revert_block_head_to() -> Result<(), anyhow::Error> {
+		let private_key = Ed25519PrivateKey::generate_for_testing();
+		let (executor, _tempdir) = Executor::try_test_default(private_key.clone())?;
+		executor.rollover_genesis_now().await?;
+
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(executor.maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+		let tx_factory =
+			TransactionFactory::new(executor.maptos_config.chain.maptos_chain_id.clone());
+		let mut rng = ::rand::rngs::StdRng::from_seed([6u8; 32]);
+
+		let mut hashes = Vec::new();
+		for _ in 0..4 {
+			let (epoch, round) = executor.get_next_epoch_and_round().await?;
+			let block_id = HashValue::random();
+			let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+				block_id,
+				epoch,
+				round,
+				executor.proposer.author(),
+				vec![],
+				vec![],
+				chrono::Utc::now().timestamp_micros() as u64,
+			));
+			let new_account = LocalAccount::generate(&mut rng);
+			let user_account_creation_tx = root_account.sign_with_transaction_builder(
+				tx_factory.create_user_account(new_account.public_key()),
+			);
+			hashes.push(user_account_creation_tx.committed_hash());
+			let transactions =
+				ExecutableTransactions::Unsharded(into_signature_verified_block(vec![
+					block_metadata,
+					Transaction::UserTransaction(user_account_creation_tx),
+				]));
+			executor.execute_block(ExecutableBlock::new(block_id, transactions)).await?;
+		}
+		assert_eq!(executor.get_block_head_height()?, 5);
+
+		// reverting to the block head or above does nothing
+		executor.revert_block_head_to(5).await?;
+		assert_eq!(executor.get_block_head_height()?, 5);
+
+		executor.revert_block_head_to(3).await?;
+		assert_eq!(executor.get_block_head_height()?, 3);
+		let db_reader = executor.db.reader.clone();
+		let latest_version = db_reader.get_synced_version()?;
+		assert!(db_reader.get_transaction_by_hash(hashes[1], latest_version, false)?.is_some());
+		assert!(db_reader.get_transaction_by_hash(hashes[2], latest_version, false)?.is_none());
+
+		// blocks execute on top of the reverted head
+		let (epoch, round) = executor.get_next_epoch_and_round().await?;
+		let block_id = HashValue::random();
+		let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			executor.proposer.author(),
+			vec![],
+			vec![],
+			chrono::Utc::now().timestamp_micros() as u64,
+		));
+		let transactions =
+			ExecutableTransactions::Unsharded(into_signature_verified_block(vec![block_metadata]));
+		let commitment =
+			executor.execute_block(ExecutableBlock::new(block_id, transactions)).await?;
+		assert_eq!(commitment.height, 4);
+
+		Ok(())
+	}
+
+	/// Executes a block with a transaction, as a block read from the DA at a timestamp.
+	async fn execute_read_block(
+		executor: &Executor,
+		block_id: HashValue,
+		timestamp: u64,
+		transaction: SignedTransaction,
+	) -> Result<BlockCommitment, anyhow::Error> {
+		let (epoch, round) = executor.get_next_epoch_and_round().await?;
+		let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			executor.proposer.author(),
+			vec![],
+			vec![],
+			timestamp,
+		));
+		let transactions = ExecutableTransactions::Unsharded(into_signature_verified_block(vec![
+			block_metadata,
+			Transaction::UserTransaction(transaction),
+		]));
+		executor.execute_block(ExecutableBlock::new(block_id, transactions)).await
+	}
+
+	#[tokio::test]
+	async fn test_reexecute_blocks_after_revert() -> Result<(), anyhow::Error> {
+		let private_key = Ed25519PrivateKey::generate_for_testing();
+		let (executor, _tempdir) = Executor::try_test_default(private_key.clone())?;
+		executor.rollover_genesis_now().await?;
+
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(executor.maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+		let tx_factory =
+			TransactionFactory::new(executor.maptos_config.chain.maptos_chain_id.clone());
+		let mut rng = ::rand::rngs::StdRng::from_seed([8u8; 32]);
+
+		// blocks as they are read from the DA, which builds the same metadata when read again
+		let mut blocks = Vec::new();
+		for i in 0..4u64 {
+			let new_account = LocalAccount::generate(&mut rng);
+			let transaction = root_account.sign_with_transaction_builder(
+				tx_factory.create_user_account(new_account.public_key()),
+			);
+			let timestamp = chrono::Utc::now().timestamp_micros() as u64 + i;
+			blocks.push((HashValue::sha3_256_of(&i.to_le_bytes()), timestamp, transaction));
+		}
+
+		let mut commitments = Vec::new();
+		for (block_id, timestamp, transaction) in &blocks {
+			commitments.push(
+				execute_read_block(&executor, *block_id, *timestamp, transaction.clone()).await?,
+			);
+		}
+
+		// the blocks above the reverted head execute again to the same commitments
+		executor.revert_block_head_to(3).await?;
+		for ((block_id, timestamp, transaction), commitment) in
+			blocks.into_iter().zip(commitments).skip(2)
+		{
+			let reexecuted =
+				execute_read_block(&executor, block_id, timestamp, transaction).await?;
+			assert_eq!(reexecuted, commitment);
+		}
+		assert_eq!(executor.get_block_head_height()?, 5);
+
+		Ok(())
+	}
 }
@@ -0,0 +1,178 @@
+use super::Executor;
+use aptos_storage_interface::state_view::DbStateViewAtVersion;
+use aptos_types::{
+	block_executor::config::BlockExecutorConfigFromOnchain,
+	on_chain_config::{OnChainConfig, OnChainExecutionConfig},
+	transaction::{SignedTransaction, Transaction, TransactionOutput, TransactionStatus},
+};
+
+/// The gas used by a block, reported once it is committed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockGasReport {
+	/// The height of the block.
+	pub block_height: u64,
+	/// The gas limit of the block in effective block gas, if blocks are limited.
+	pub effective_block_gas_limit: Option<u64>,
+	/// The gas units used by the committed user transactions, of which the effective block gas
+	/// weighs the execution and io gas.
+	pub gas_units_used: u64,
+	/// The number of committed user transactions.
+	pub committed: u64,
+	/// The user transactions left out of the block once it reached its gas limit, in order.
+	pub retried: Vec<SignedTransaction>,
+}
+
+impl BlockGasReport {
+	pub fn new(effective_block_gas_limit: Option<u64>) -> Self {
+		Self { effective_block_gas_limit, ..Default::default() }
+	}
+
+	/// Gathers the user transactions which were left out of the block, from the transactions of
+	/// the block and their statuses.
+	pub fn add_statuses(&mut self, transactions: &[Transaction], statuses: &[TransactionStatus]) {
+		for (transaction, status) in transactions.iter().zip(statuses) {
+			if let (Transaction::UserTransaction(transaction), TransactionStatus::Retry) =
+				(transaction, status)
+			{
+				self.retried.push(transaction.clone());
+			}
+		}
+	}
+
+	/// Adds the gas used by the committed user transactions.
+	pub fn add_outputs(&mut self, outputs: &[(Transaction, TransactionOutput)]) {
+		for (transaction, output) in outputs {
+			if let Transaction::UserTransaction(_) = transaction {
+				self.gas_units_used += output.gas_used();
+				self.committed += 1;
+			}
+		}
+	}
+}
+
+impl Executor {
+	/// Gets the config of block execution from the on-chain execution config at the latest
+	/// state, so that every node limits blocks alike.
+	pub fn block_executor_config(&self) -> Result<BlockExecutorConfigFromOnchain, anyhow::Error> {
+		let db_reader = self.db.reader.clone();
+		let state_view = db_reader.state_view_at_version(Some(db_reader.get_synced_version()?))?;
+		let execution_config = OnChainExecutionConfig::fetch_config(&state_view)
+			.unwrap_or_else(OnChainExecutionConfig::default_if_missing);
+		Ok(BlockExecutorConfigFromOnchain {
+			block_gas_limit_type: execution_config.block_gas_limit_type(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use aptos_cached_packages::aptos_stdlib;
+	use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+	use aptos_sdk::{
+		transaction_builder::TransactionFactory,
+		types::{AccountKey, LocalAccount},
+	};
+	use aptos_types::{
+		account_config::aptos_test_root_address,
+		block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
+		block_metadata::BlockMetadata,
+		on_chain_config::{
+			BlockGasLimitType, ExecutionConfigV4, TransactionDeduperType, TransactionShufflerType,
+		},
+		transaction::signature_verified_transaction::into_signature_verified_block,
+	};
+	use aptos_vm_genesis::default_gas_schedule;
+	use maptos_execution_util::config::{genesis::GenesisConfig, Config};
+	use rand::SeedableRng;
+
+	#[tokio::test]
+	async fn test_block_gas_limit() -> Result<(), anyhow::Error> {
+		let tempdir = tempfile::tempdir()?;
+		let mut maptos_config = Config::default();
+		maptos_config.chain.maptos_private_key = Ed25519PrivateKey::generate_for_testing();
+		maptos_config.chain.maptos_db_path.replace(tempdir.path().join("db"));
+
+		// the smallest limit, which any transaction reaches
+		let mut genesis = GenesisConfig::new(
+			maptos_config.chain.maptos_chain_id,
+			maptos_config.chain.maptos_private_key.public_key(),
+		);
+		genesis.execution_config = OnChainExecutionConfig::V4(ExecutionConfigV4 {
+			transaction_shuffler_type: TransactionShufflerType::NoShuffling,
+			block_gas_limit_type: BlockGasLimitType::Limit(1),
+			transaction_deduper_type: TransactionDeduperType::TxnHashAndAuthenticatorV1,
+		});
+		let genesis_path = tempdir.path().join("genesis.json");
+		std::fs::write(&genesis_path, serde_json::to_vec(&genesis)?)?;
+		maptos_config.chain.maptos_genesis_path = Some(genesis_path);
+		let executor = Executor::try_from_config(&maptos_config)?;
+		executor.rollover_genesis_now().await?;
+
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+		let tx_factory = TransactionFactory::new(maptos_config.chain.maptos_chain_id.clone());
+		let mut rng = ::rand::rngs::StdRng::from_seed([9u8; 32]);
+		let (epoch, round) = executor.get_next_epoch_and_round().await?;
+		let block_id = HashValue::random();
+		let mut transactions = vec![Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			executor.proposer.author(),
+			vec![],
+			vec![],
+			chrono::Utc::now().timestamp_micros() as u64,
+		))];
+		let mut user_transactions = Vec::new();
+		for _ in 0..3 {
+			let new_account = LocalAccount::generate(&mut rng);
+			user_transactions.push(
+				root_account
+					.sign_with_transaction_builder(tx_factory.payload(
+						aptos_stdlib::aptos_account_create_account(new_account.address()),
+					)),
+			);
+		}
+		transactions.extend(user_transactions.iter().cloned().map(Transaction::UserTransaction));
+		let block = ExecutableBlock::new(
+			block_id,
+			ExecutableTransactions::Unsharded(into_signature_verified_block(transactions)),
+		);
+
+		let (commitment, report) = executor.execute_block_with_report(block).await?;
+		assert_eq!(report.block_height, commitment.height);
+		assert_eq!(report.effective_block_gas_limit, Some(1));
+		assert_eq!(report.committed, 1);
+		// the transactions after the limit are left out in order
+		assert_eq!(report.retried, user_transactions[1..]);
+		assert!(report.gas_units_used > 0);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_genesis_gas_schedule() -> Result<(), anyhow::Error> {
+		let default = default_gas_schedule();
+		let (name, value) = default.entries[0].clone();
+		let mut genesis = GenesisConfig::new(
+			Config::default().chain.maptos_chain_id,
+			Ed25519PrivateKey::generate_for_testing().public_key(),
+		);
+		genesis.gas_schedule.insert(name.clone(), value + 1);
+
+		let gas_schedule = Executor::genesis_gas_schedule(&genesis)?;
+		assert_eq!(gas_schedule.entries[0], (name, value + 1));
+		assert_eq!(gas_schedule.entries[1..], default.entries[1..]);
+
+		// only entries of the default schedule can be overridden
+		genesis.gas_schedule.insert("no.such.entry".to_string(), 1);
+		assert!(Executor::genesis_gas_schedule(&genesis).is_err());
+
+		Ok(())
+	}
+}
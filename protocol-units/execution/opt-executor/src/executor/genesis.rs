@@ -0,0 +1,231 @@
+use super::Executor;
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{HashValue, PrivateKey};
+use aptos_sdk::{
+	transaction_builder::TransactionFactory,
+	types::{AccountKey, LocalAccount},
+};
+use aptos_storage_interface::state_view::DbStateViewAtVersion;
+use aptos_types::{
+	account_config::{aptos_test_root_address, AccountResource},
+	state_store::MoveResourceExt,
+	transaction::SignedTransaction,
+};
+
+impl Executor {
+	/// Gets the id of the genesis block, which commits to the genesis transaction so that it is the
+	/// same on every node booted from the same genesis.
+	pub fn genesis_block_id(&self) -> Result<HashValue, anyhow::Error> {
+		Ok(self.db.reader.get_accumulator_root_hash(0)?)
+	}
+
+	/// Signs the transactions of the genesis block, which create and fund the accounts of the
+	/// genesis from the root account.
+	///
+	/// Only the node holding the root key can sign them, so every other node gets none.
+	pub fn genesis_transactions(&self) -> Result<Vec<SignedTransaction>, anyhow::Error> {
+		let genesis = self.maptos_config.chain.try_genesis()?;
+		let private_key = &self.maptos_config.chain.maptos_private_key;
+		if private_key.public_key() != genesis.root_public_key {
+			return Ok(Vec::new());
+		}
+
+		let db_reader = self.db.reader.clone();
+		let state_view = db_reader.state_view_at_version(Some(db_reader.get_synced_version()?))?;
+		let sequence_number =
+			AccountResource::fetch_move_resource(&state_view, &aptos_test_root_address())?
+				.map(|account| account.sequence_number())
+				.unwrap_or(0);
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(private_key.clone()),
+			sequence_number,
+		);
+
+		let tx_factory = TransactionFactory::new(genesis.chain_id);
+		let mut transactions = Vec::with_capacity(genesis.accounts.len() * 2);
+		for account in &genesis.accounts {
+			transactions.push(root_account.sign_with_transaction_builder(
+				tx_factory.payload(aptos_stdlib::aptos_account_create_account(account.address)),
+			));
+			transactions.push(
+				root_account.sign_with_transaction_builder(
+					tx_factory.mint(account.address, account.balance),
+				),
+			);
+		}
+		Ok(transactions)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use aptos_crypto::{bls12381, ed25519::Ed25519PrivateKey, Uniform};
+	use aptos_types::{
+		account_address::AccountAddress,
+		block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
+		block_metadata::BlockMetadata,
+		transaction::{signature_verified_transaction::into_signature_verified_block, Transaction},
+	};
+	use maptos_execution_util::config::{
+		genesis::{GenesisAccount, GenesisConfig, GenesisValidator},
+		Config,
+	};
+	use tempfile::TempDir;
+
+	fn try_genesis_executor(
+		private_key: Ed25519PrivateKey,
+		genesis: &GenesisConfig,
+	) -> Result<(Executor, TempDir), anyhow::Error> {
+		let tempdir = tempfile::tempdir()?;
+		let genesis_path = tempdir.path().join("genesis.json");
+		std::fs::write(&genesis_path, serde_json::to_vec(genesis)?)?;
+
+		let mut maptos_config = Config::default();
+		maptos_config.chain.maptos_private_key = private_key;
+		maptos_config.chain.maptos_chain_id = genesis.chain_id;
+		maptos_config.chain.maptos_genesis_path = Some(genesis_path);
+		maptos_config.chain.maptos_db_path.replace(tempdir.path().join("db"));
+		let executor = Executor::try_from_config(&maptos_config)?;
+		Ok((executor, tempdir))
+	}
+
+	#[tokio::test]
+	async fn test_genesis_is_deterministic() -> Result<(), anyhow::Error> {
+		let root_key = Ed25519PrivateKey::generate_for_testing();
+		let genesis =
+			GenesisConfig::new(Config::default().chain.maptos_chain_id, root_key.public_key());
+
+		// nodes with other keys boot into the same genesis
+		let (executor, _tempdir) = try_genesis_executor(root_key, &genesis)?;
+		let (other, _other_tempdir) =
+			try_genesis_executor(Ed25519PrivateKey::generate_for_testing(), &genesis)?;
+		assert_eq!(executor.genesis_block_id()?, other.genesis_block_id()?);
+
+		let timestamp = chrono::Utc::now().timestamp_micros() as u64;
+		executor.rollover_genesis(timestamp).await?;
+		other.rollover_genesis(timestamp).await?;
+		let root_hash = executor
+			.db
+			.reader
+			.get_latest_ledger_info()?
+			.ledger_info()
+			.transaction_accumulator_hash();
+		let other_root_hash = other
+			.db
+			.reader
+			.get_latest_ledger_info()?
+			.ledger_info()
+			.transaction_accumulator_hash();
+		assert_eq!(root_hash, other_root_hash);
+
+		// a different genesis has a different genesis block
+		let mut different = genesis.clone();
+		different.validator_stake += 1;
+		let (different, _different_tempdir) =
+			try_genesis_executor(Ed25519PrivateKey::generate_for_testing(), &different)?;
+		assert_ne!(different.genesis_block_id()?, executor.genesis_block_id()?);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_genesis_transactions() -> Result<(), anyhow::Error> {
+		let root_key = Ed25519PrivateKey::generate_for_testing();
+		let mut genesis =
+			GenesisConfig::new(Config::default().chain.maptos_chain_id, root_key.public_key());
+		let address = AccountAddress::random();
+		genesis.accounts.push(GenesisAccount { address, balance: 1_000 });
+
+		// only the holder of the root key signs the genesis transactions
+		let (other, _other_tempdir) =
+			try_genesis_executor(Ed25519PrivateKey::generate_for_testing(), &genesis)?;
+		assert!(other.genesis_transactions()?.is_empty());
+
+		let (executor, _tempdir) = try_genesis_executor(root_key, &genesis)?;
+		let transactions = executor.genesis_transactions()?;
+		assert_eq!(transactions.len(), 2);
+
+		let timestamp = chrono::Utc::now().timestamp_micros() as u64;
+		executor.rollover_genesis(timestamp).await?;
+		let (epoch, round) = executor.get_next_epoch_and_round().await?;
+		let block_id = HashValue::random();
+		let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			executor.proposer.author(),
+			vec![],
+			vec![],
+			timestamp + 1,
+		));
+		let transactions = ExecutableTransactions::Unsharded(into_signature_verified_block(
+			std::iter::once(block_metadata)
+				.chain(transactions.into_iter().map(Transaction::UserTransaction))
+				.collect(),
+		));
+		executor.execute_block(ExecutableBlock::new(block_id, transactions)).await?;
+
+		let db_reader = executor.db.reader.clone();
+		let state_view = db_reader.state_view_at_version(Some(db_reader.get_synced_version()?))?;
+		assert!(AccountResource::fetch_move_resource(&state_view, &address)?.is_some());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_genesis_validators() -> Result<(), anyhow::Error> {
+		let root_key = Ed25519PrivateKey::generate_for_testing();
+		let mut genesis =
+			GenesisConfig::new(Config::default().chain.maptos_chain_id, root_key.public_key());
+		genesis.is_test = false;
+
+		// only test chains fall back to the test validator set
+		assert!(try_genesis_executor(root_key.clone(), &genesis).is_err());
+
+		let consensus_key = bls12381::PrivateKey::generate_for_testing();
+		let owner_address = AccountAddress::random();
+		genesis.validators.push(GenesisValidator {
+			owner_address,
+			operator_address: owner_address,
+			voter_address: owner_address,
+			stake: genesis.validator_stake,
+			consensus_public_key: consensus_key.public_key(),
+			proof_of_possession: bls12381::ProofOfPossession::create(&consensus_key),
+		});
+		let (executor, _tempdir) = try_genesis_executor(root_key, &genesis)?;
+		assert_eq!(executor.proposer.author(), owner_address);
+		assert_eq!(executor.proposer.public_key(), consensus_key.public_key());
+		executor.rollover_genesis_now().await?;
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_genesis_framework_is_pinned() -> Result<(), anyhow::Error> {
+		let root_key = Ed25519PrivateKey::generate_for_testing();
+		let genesis =
+			GenesisConfig::new(Config::default().chain.maptos_chain_id, root_key.public_key());
+		let (executor, _tempdir) = try_genesis_executor(root_key.clone(), &genesis)?;
+
+		let framework_dir = tempfile::tempdir()?;
+		let framework_path = framework_dir.path().join("head.mrb");
+		let framework = bcs::to_bytes(aptos_cached_packages::head_release_bundle())?;
+		std::fs::write(&framework_path, &framework)?;
+
+		// a framework is only read with its hash
+		let mut pinned = genesis.clone();
+		pinned.framework_path = Some(framework_path);
+		assert!(try_genesis_executor(root_key.clone(), &pinned).is_err());
+		pinned.framework_hash = Some(HashValue::random());
+		assert!(try_genesis_executor(root_key.clone(), &pinned).is_err());
+
+		pinned.framework_hash = Some(HashValue::sha3_256_of(&framework));
+		let (pinned, _pinned_tempdir) = try_genesis_executor(root_key, &pinned)?;
+		assert_eq!(pinned.genesis_block_id()?, executor.genesis_block_id()?);
+
+		Ok(())
+	}
+}
@@ -1,37 +1,41 @@
-use super::Executor;
+use super::{
+	partitioner::AccessPartitioner,
+	validation::{
+		MaxTransactionSize, SenderFilter, SenderRateLimiter, ValidatorChain, VmValidator,
+	},
+	BlockProposer, Executor,
+};
 use aptos_api::Context;
 use aptos_config::config::NodeConfig;
 #[cfg(test)]
 use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_crypto::{ed25519::Ed25519PublicKey, PrivateKey};
+use aptos_crypto::{HashValue, PrivateKey};
 use aptos_db::AptosDB;
 use aptos_executor::{
 	block_executor::BlockExecutor,
 	db_bootstrapper::{generate_waypoint, maybe_bootstrap},
 };
+use aptos_framework::ReleaseBundle;
 use aptos_mempool::{MempoolClientRequest, MempoolClientSender};
-use aptos_sdk::types::on_chain_config::{OnChainConsensusConfig, OnChainExecutionConfig};
-use aptos_storage_interface::DbReaderWriter;
-use aptos_types::{
-	chain_id::ChainId,
-	transaction::{ChangeSet, Transaction, WriteSetPayload},
-	validator_signer::ValidatorSigner,
-};
+use aptos_sdk::types::on_chain_config::GasScheduleV2;
+use aptos_storage_interface::{DbReader, DbReaderWriter};
+use aptos_types::transaction::{ChangeSet, Transaction, WriteSetPayload};
 use aptos_vm::AptosVM;
 use aptos_vm_genesis::{
 	default_gas_schedule, encode_genesis_change_set, GenesisConfiguration, TestValidator, Validator,
 };
-use maptos_execution_util::config::Config;
+use maptos_execution_util::config::{genesis::GenesisConfig, Config};
 
 use anyhow::Context as _;
 use futures::channel::mpsc as futures_mpsc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 #[cfg(test)]
 use tempfile::TempDir;
 
 use std::{
-	path::PathBuf,
+	path::{Path, PathBuf},
 	sync::{atomic::AtomicU64, Arc},
 };
 
@@ -39,67 +43,143 @@ use std::{
 const EXECUTOR_CHANNEL_SIZE: usize = 2_usize.pow(16);
 
 impl Executor {
-	pub fn genesis_change_set_and_validators(
-		chain_id: ChainId,
-		count: Option<usize>,
-		public_key: &Ed25519PublicKey,
-	) -> (ChangeSet, Vec<TestValidator>) {
-		let framework = aptos_cached_packages::head_release_bundle();
-		let test_validators = TestValidator::new_test_set(count, Some(100_000_000));
-		let validators_: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
-		let validators = &validators_;
-
-		// This number should not exceed u64::MAX / 1_000_000_000
-		// to avoid overflowing calculations in aptos-vm-genesis.
-		// This will last several centuries.
-		const EPOCH_DURATION_SECS: u64 = 60 * 60 * 24 * 1024 * 128;
-
-		let genesis = encode_genesis_change_set(
-			&public_key,
-			validators,
+	/// Gets the change set of the genesis and the proposer of its blocks, which is its first
+	/// validator.
+	pub fn genesis_change_set_and_proposer(
+		genesis: &GenesisConfig,
+	) -> Result<(ChangeSet, BlockProposer), anyhow::Error> {
+		let release_bundle;
+		let framework = match &genesis.framework_path {
+			Some(path) => {
+				release_bundle = Self::read_framework(path, genesis.framework_hash)?;
+				&release_bundle
+			}
+			None => aptos_cached_packages::head_release_bundle(),
+		};
+		let (validators, proposer) = Self::genesis_validators(genesis)?;
+
+		let change_set = encode_genesis_change_set(
+			&genesis.root_public_key,
+			&validators,
 			framework,
-			chain_id,
-			// todo: get this config from somewhere
+			genesis.chain_id,
 			&GenesisConfiguration {
 				allow_new_validators: true,
-				epoch_duration_secs: EPOCH_DURATION_SECS,
-				is_test: true,
-				min_stake: 0,
+				epoch_duration_secs: genesis.epoch_duration_secs,
+				is_test: genesis.is_test,
+				min_stake: genesis.min_stake,
 				min_voting_threshold: 0,
-				// 1M APTOS coins (with 8 decimals).
-				max_stake: 100_000_000_000_000,
-				recurring_lockup_duration_secs: EPOCH_DURATION_SECS * 2,
+				max_stake: genesis.max_stake,
+				recurring_lockup_duration_secs: genesis.epoch_duration_secs * 2,
 				required_proposer_stake: 0,
-				rewards_apy_percentage: 0,
-				voting_duration_secs: EPOCH_DURATION_SECS,
-				voting_power_increase_limit: 50,
+				rewards_apy_percentage: genesis.rewards_apy_percentage,
+				voting_duration_secs: genesis.epoch_duration_secs,
+				voting_power_increase_limit: genesis.voting_power_increase_limit,
 				employee_vesting_start: 1663456089,
 				employee_vesting_period_duration: 5 * 60, // 5 minutes
 				initial_features_override: None,
 				randomness_config_override: None,
 				jwk_consensus_config_override: None,
 			},
-			&OnChainConsensusConfig::default_for_genesis(),
-			&OnChainExecutionConfig::default_for_genesis(),
-			&default_gas_schedule(),
+			&genesis.consensus_config,
+			&genesis.execution_config,
+			&Self::genesis_gas_schedule(genesis)?,
 		);
-		(genesis, test_validators)
+		Ok((change_set, proposer))
+	}
+
+	/// Reads the framework release bundle, which must have the hash pinned by the genesis.
+	fn read_framework(
+		path: &Path,
+		framework_hash: Option<HashValue>,
+	) -> Result<ReleaseBundle, anyhow::Error> {
+		let framework_hash = framework_hash
+			.ok_or(anyhow::anyhow!("The framework at {:?} is not pinned by a hash", path))?;
+		let bytes = std::fs::read(path)
+			.map_err(|e| anyhow::anyhow!("Failed to read the framework {:?}: {}", path, e))?;
+		let hash = HashValue::sha3_256_of(&bytes);
+		if hash != framework_hash {
+			anyhow::bail!(
+				"Framework {:?} has hash {}, but the genesis pins {}",
+				path,
+				hash,
+				framework_hash
+			);
+		}
+		Ok(bcs::from_bytes(&bytes)?)
+	}
+
+	/// Gets the validators of the genesis and the proposer of its blocks.
+	///
+	/// A test chain which lists no validators gets the test validator set, which is generated
+	/// from a fixed seed so that it is the same on every node.
+	fn genesis_validators(
+		genesis: &GenesisConfig,
+	) -> Result<(Vec<Validator>, BlockProposer), anyhow::Error> {
+		if genesis.validators.is_empty() {
+			if !genesis.is_test {
+				anyhow::bail!("Only the genesis of a test chain may list no validators");
+			}
+			let test_validators = TestValidator::new_test_set(
+				Some(genesis.validator_count),
+				Some(genesis.validator_stake),
+			);
+			let proposer = test_validators
+				.first()
+				.map(|validator| {
+					BlockProposer::new(
+						validator.data.owner_address,
+						validator.consensus_key.public_key(),
+					)
+				})
+				.ok_or(anyhow::anyhow!("The genesis has no test validators"))?;
+			let validators = test_validators.into_iter().map(|validator| validator.data).collect();
+			return Ok((validators, proposer));
+		}
+
+		let validators = genesis
+			.validators
+			.iter()
+			.map(|validator| Validator {
+				owner_address: validator.owner_address,
+				operator_address: validator.operator_address,
+				voter_address: validator.voter_address,
+				stake_amount: validator.stake,
+				consensus_pubkey: validator.consensus_public_key.to_bytes().to_vec(),
+				proof_of_possession: validator.proof_of_possession.to_bytes().to_vec(),
+				network_addresses: Vec::new(),
+				full_node_network_addresses: Vec::new(),
+			})
+			.collect();
+		let proposer = &genesis.validators[0];
+		Ok((
+			validators,
+			BlockProposer::new(proposer.owner_address, proposer.consensus_public_key.clone()),
+		))
+	}
+
+	/// Gets the default gas schedule with the entries of the genesis overridden.
+	pub fn genesis_gas_schedule(genesis: &GenesisConfig) -> Result<GasScheduleV2, anyhow::Error> {
+		let mut gas_schedule = default_gas_schedule();
+		for (name, value) in &genesis.gas_schedule {
+			let entry = gas_schedule
+				.entries
+				.iter_mut()
+				.find(|(entry_name, _)| entry_name == name)
+				.ok_or(anyhow::anyhow!("No gas schedule entry named {}", name))?;
+			entry.1 = *value;
+		}
+		Ok(gas_schedule)
 	}
 
 	/// Bootstrap a database with a genesis transaction if it is empty.
 	pub fn maybe_bootstrap_empty_db(
 		db_dir: &PathBuf,
-		chain_id: ChainId,
-		public_key: &Ed25519PublicKey,
-	) -> Result<(DbReaderWriter, ValidatorSigner), anyhow::Error> {
+		genesis: &GenesisConfig,
+	) -> Result<(DbReaderWriter, BlockProposer), anyhow::Error> {
 		let db_rw = DbReaderWriter::new(AptosDB::new_for_test(db_dir));
-		let (genesis, validators) =
-			Self::genesis_change_set_and_validators(chain_id, Some(1), public_key);
+		let (genesis, proposer) = Self::genesis_change_set_and_proposer(genesis)?;
 		let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
-		let validator_signer = ValidatorSigner::new(
-			validators[0].data.owner_address,
-			validators[0].consensus_key.clone(),
-		);
 
 		// check for context
 
@@ -119,7 +199,7 @@ impl Executor {
 			}
 		}
 
-		Ok((db_rw, validator_signer))
+		Ok((db_rw, proposer))
 	}
 
 	pub fn bootstrap(
@@ -128,17 +208,17 @@ impl Executor {
 		node_config: NodeConfig,
 		maptos_config: Config,
 	) -> Result<Self, anyhow::Error> {
-		let (db, signer) = Self::maybe_bootstrap_empty_db(
+		let (db, proposer) = Self::maybe_bootstrap_empty_db(
 			maptos_config.chain.maptos_db_path.as_ref().context("No db path provided.")?,
-			maptos_config.chain.maptos_chain_id.clone(),
-			&maptos_config.chain.maptos_private_key.public_key(),
+			&maptos_config.chain.try_genesis()?,
 		)?;
 		let reader = db.reader.clone();
+		let validators = Self::validator_chain(&maptos_config, db.reader.clone());
 
 		Ok(Self {
 			block_executor: Arc::new(BlockExecutor::new(db.clone())),
 			db,
-			signer,
+			proposer,
 			mempool_client_sender: mempool_client_sender.clone(),
 			mempool_client_receiver: Arc::new(RwLock::new(mempool_client_receiver)),
 			node_config: node_config.clone(),
@@ -156,9 +236,35 @@ impl Executor {
 			),
 			maptos_config,
 			transactions_in_flight: Arc::new(AtomicU64::new(0)),
+			partitioner: None,
+			validators,
 		})
 	}
 
+	/// Builds the chain validating submitted transactions from the policies of the node, which
+	/// are cheap and so come ahead of the VM.
+	pub fn validator_chain(maptos_config: &Config, db_reader: Arc<dyn DbReader>) -> ValidatorChain {
+		let chain_config = &maptos_config.chain;
+		let mut validators = ValidatorChain::new();
+		if let Some(max_bytes) = chain_config.maptos_max_transaction_size {
+			validators = validators.with_validator(MaxTransactionSize::new(max_bytes));
+		}
+		if !chain_config.maptos_sender_blocklist.is_empty() {
+			validators = validators.with_validator(SenderFilter::blocklist(
+				chain_config.maptos_sender_blocklist.iter().copied(),
+			));
+		}
+		if let Some(allowlist) = &chain_config.maptos_sender_allowlist {
+			validators =
+				validators.with_validator(SenderFilter::allowlist(allowlist.iter().copied()));
+		}
+		if let Some(max_transactions) = chain_config.maptos_sender_rate_limit {
+			validators = validators
+				.with_validator(SenderRateLimiter::new(max_transactions, Duration::from_secs(1)));
+		}
+		validators.with_validator(VmValidator::new(db_reader))
+	}
+
 	pub fn try_from_config(maptos_config: &Config) -> Result<Self, anyhow::Error> {
 		// use the default signer, block executor, and mempool
 		let (mempool_client_sender, mempool_client_receiver) =
@@ -197,12 +303,16 @@ impl Executor {
 		node_config.storage.dir = "./.movement/maptos-storage".to_string().into();
 		node_config.storage.set_data_dir(node_config.storage.dir.clone());
 
-		Self::bootstrap(
+		let executor = Self::bootstrap(
 			mempool_client_sender,
 			mempool_client_receiver,
 			node_config,
 			maptos_config.clone(),
-		)
+		)?;
+		match maptos_config.chain.maptos_executor_shards {
+			0 | 1 => Ok(executor),
+			shards => Ok(executor.with_partitioner(AccessPartitioner::new(shards))),
+		}
 	}
 
 	#[cfg(test)]
@@ -1,22 +1,52 @@
 //! Implementation is split over multiple files to make the code more manageable.
 pub mod execution;
+pub mod gas;
+pub mod genesis;
 pub mod initialization;
+pub mod partitioner;
 pub mod services;
+pub mod snapshot;
 pub mod transaction_pipe;
+pub mod validation;
 use anyhow::Context as _;
 use aptos_api::context::Context;
 use aptos_config::config::NodeConfig;
+use aptos_crypto::bls12381;
 use aptos_db::AptosDB;
 use aptos_executor::block_executor::BlockExecutor;
 use aptos_mempool::{MempoolClientRequest, MempoolClientSender};
 use aptos_storage_interface::DbReaderWriter;
-use aptos_types::validator_signer::ValidatorSigner;
+use aptos_types::account_address::AccountAddress;
 use aptos_vm::AptosVM;
 use futures::channel::mpsc as futures_mpsc;
+use partitioner::AccessPartitioner;
 use std::sync::{atomic::AtomicU64, Arc};
 use tokio::sync::RwLock;
+use validation::{TransactionValidator, ValidatorChain};
 pub mod indexer;
 
+/// The validator which proposes every block, which is set by the genesis so that it is the same on
+/// every node.
+#[derive(Debug, Clone)]
+pub struct BlockProposer {
+	author: AccountAddress,
+	public_key: bls12381::PublicKey,
+}
+
+impl BlockProposer {
+	pub fn new(author: AccountAddress, public_key: bls12381::PublicKey) -> Self {
+		Self { author, public_key }
+	}
+
+	pub fn author(&self) -> AccountAddress {
+		self.author
+	}
+
+	pub fn public_key(&self) -> bls12381::PublicKey {
+		self.public_key.clone()
+	}
+}
+
 /// The `Executor` is responsible for executing blocks and managing the state of the execution
 /// against the `AptosVM`.
 #[derive(Clone)]
@@ -25,8 +55,8 @@ pub struct Executor {
 	pub block_executor: Arc<BlockExecutor<AptosVM>>,
 	/// The access to db.
 	pub db: DbReaderWriter,
-	/// The signer of the executor's transactions.
-	pub signer: ValidatorSigner,
+	/// The proposer of the executed blocks.
+	pub proposer: BlockProposer,
 	/// The sender for the mempool client.
 	pub mempool_client_sender: MempoolClientSender,
 	/// The receiver for the mempool client.
@@ -41,13 +71,17 @@ pub struct Executor {
 	pub maptos_config: maptos_execution_util::config::Config,
 	/// Transactions in flight counter.
 	pub transactions_in_flight: Arc<AtomicU64>,
+	/// The partitioner of blocks into shards, if blocks are sharded.
+	pub partitioner: Option<Arc<AccessPartitioner>>,
+	/// The validators of submitted transactions, applied in order.
+	pub validators: ValidatorChain,
 }
 
 impl Executor {
 	/// Create a new `Executor` instance.
 	pub fn try_new(
 		block_executor: BlockExecutor<AptosVM>,
-		signer: ValidatorSigner,
+		proposer: BlockProposer,
 		mempool_client_sender: MempoolClientSender,
 		mempool_client_receiver: futures_mpsc::Receiver<MempoolClientRequest>,
 		node_config: NodeConfig,
@@ -58,10 +92,11 @@ impl Executor {
 		));
 
 		let reader = reader_writer.reader.clone();
+		let validators = Self::validator_chain(&maptos_config, reader.clone());
 		Ok(Self {
 			block_executor: Arc::new(block_executor),
 			db: reader_writer,
-			signer,
+			proposer,
 			mempool_client_sender: mempool_client_sender.clone(),
 			node_config: node_config.clone(),
 			mempool_client_receiver: Arc::new(RwLock::new(mempool_client_receiver)),
@@ -79,6 +114,24 @@ impl Executor {
 			),
 			maptos_config,
 			transactions_in_flight: Arc::new(AtomicU64::new(0)),
+			partitioner: None,
+			validators,
 		})
 	}
+
+	/// Shards blocks with the partitioner.
+	///
+	/// The `AptosVM` takes its number of shards from the first partitioner of the process.
+	pub fn with_partitioner(mut self, partitioner: AccessPartitioner) -> Self {
+		AptosVM::set_num_shards_once(partitioner.num_shards());
+		self.partitioner = Some(Arc::new(partitioner));
+		self
+	}
+
+	/// Adds a validator to the end of the chain validating submitted transactions, after the
+	/// policies of the node and the VM.
+	pub fn with_validator(mut self, validator: impl TransactionValidator + 'static) -> Self {
+		self.validators = self.validators.with_validator(validator);
+		self
+	}
 }
@@ -0,0 +1,285 @@
+use aptos_sdk::move_types::{identifier::Identifier, language_storage::StructTag};
+use aptos_types::{
+	access_path::AccessPath,
+	account_address::AccountAddress,
+	block_executor::partitioner::{
+		CrossShardDependencies, ExecutableTransactions, PartitionedTransactions, ShardedTxnIndex,
+		SubBlock, SubBlocksForShard, TransactionWithDependencies,
+	},
+	state_store::state_key::{StateKey, StateKeyInner},
+	transaction::{
+		analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+		signature_verified_transaction::SignatureVerifiedTransaction,
+		SignedTransaction, Transaction, TransactionPayload,
+	},
+	write_set::TOTAL_SUPPLY_STATE_KEY,
+};
+use std::collections::HashMap;
+
+/// The entry functions of `0x1` whose accesses the Aptos transaction analyzer declares.
+const DECLARED_ENTRY_FUNCTIONS: [(&str, &str); 3] =
+	[("coin", "transfer"), ("aptos_account", "transfer"), ("aptos_account", "create_account")];
+
+/// The resources of `0x1` which the block prologue writes.
+const METADATA_WRITES: [(&str, &str); 3] = [
+	("timestamp", "CurrentTimeMicroseconds"),
+	("block", "BlockResource"),
+	("stake", "ValidatorPerformance"),
+];
+
+/// The accounts a transaction reads and writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessSet {
+	pub reads: Vec<AccountAddress>,
+	pub writes: Vec<AccountAddress>,
+}
+
+impl AccessSet {
+	pub fn new(reads: Vec<AccountAddress>, writes: Vec<AccountAddress>) -> Self {
+		Self { reads, writes }
+	}
+}
+
+/// Partitions blocks into shards of consecutive transactions, and executes the shards in parallel
+/// when the accesses of their transactions show that they are independent.
+///
+/// Partitioning is a function of the block alone: the accesses of a transaction are those
+/// declared by the Aptos transaction analyzer, and the transactions keep their order, so a
+/// sharded block commits the same transactions in the same order as an unsharded one. Like the
+/// access log of `move-access-log`, accesses are tracked by account.
+pub struct AccessPartitioner {
+	num_shards: usize,
+}
+
+impl AccessPartitioner {
+	pub fn new(num_shards: usize) -> Self {
+		Self { num_shards }
+	}
+
+	pub fn num_shards(&self) -> usize {
+		self.num_shards
+	}
+
+	/// Shards the transactions of a block if their shards are independent.
+	///
+	/// Blocks which do not start with a block metadata transaction followed only by user
+	/// transactions with declared accesses are left unsharded.
+	pub fn partition(
+		&self,
+		transactions: Vec<SignatureVerifiedTransaction>,
+	) -> Result<ExecutableTransactions, anyhow::Error> {
+		let shards = match self.shards(&transactions) {
+			Some(shards) => shards,
+			None => return Ok(ExecutableTransactions::Unsharded(transactions)),
+		};
+
+		let mut transactions = transactions.into_iter();
+		let metadata = transactions
+			.next()
+			.ok_or(anyhow::anyhow!("Block must contain a block metadata transaction"))?;
+		let user_transactions = shards.into_iter().zip(transactions).collect();
+		Ok(ExecutableTransactions::Sharded(self.shard(metadata, user_transactions)?))
+	}
+
+	/// Gets the shard of each user transaction, if the block can be sharded.
+	fn shards(&self, transactions: &[SignatureVerifiedTransaction]) -> Option<Vec<usize>> {
+		if self.num_shards < 2 {
+			return None;
+		}
+		let (metadata, user_transactions) = transactions.split_first()?;
+		if !matches!(metadata, SignatureVerifiedTransaction::Valid(Transaction::BlockMetadata(_))) {
+			return None;
+		}
+		let accesses: Vec<AccessSet> =
+			user_transactions.iter().map(accesses).collect::<Option<_>>()?;
+
+		let shards = consecutive_shards(user_transactions.len(), self.num_shards);
+		let sharded = shards.first() != shards.last() && shards_are_independent(&shards, &accesses);
+		sharded.then_some(shards)
+	}
+
+	/// Places the block metadata in the first round of the first shard, and the user transactions
+	/// in the second round of their shards.
+	///
+	/// Each user transaction depends on the block metadata for the resources the block prologue
+	/// writes, such as the timestamp.
+	fn shard(
+		&self,
+		metadata: SignatureVerifiedTransaction,
+		user_transactions: Vec<(usize, SignatureVerifiedTransaction)>,
+	) -> Result<PartitionedTransactions, anyhow::Error> {
+		let metadata_locations: Vec<StorageLocation> =
+			metadata_writes()?.into_iter().map(StorageLocation::Specific).collect();
+
+		let mut metadata_dependencies = CrossShardDependencies::default();
+		let mut rounds: Vec<Vec<_>> = (0..self.num_shards).map(|_| Vec::new()).collect();
+		for (index, (shard, transaction)) in user_transactions.into_iter().enumerate() {
+			let index = ShardedTxnIndex::new(index + 1, shard, 1);
+			metadata_dependencies.add_dependent_edge(index, metadata_locations.clone());
+
+			let mut dependencies = CrossShardDependencies::default();
+			for location in &metadata_locations {
+				dependencies.add_required_edge(ShardedTxnIndex::new(0, 0, 0), location.clone());
+			}
+			let analyzed = AnalyzedTransaction::new(transaction);
+			rounds[shard].push(TransactionWithDependencies::new(analyzed, dependencies));
+		}
+
+		let mut metadata = Some(TransactionWithDependencies::new(
+			AnalyzedTransaction::new(metadata),
+			metadata_dependencies,
+		));
+		let mut start_index = 1;
+		let mut sharded_transactions = Vec::new();
+		for (shard, transactions) in rounds.into_iter().enumerate() {
+			let first_round = match shard {
+				0 => SubBlock::new(0, metadata.take().into_iter().collect()),
+				_ => SubBlock::new(1, Vec::new()),
+			};
+			let num_transactions = transactions.len();
+			let second_round = SubBlock::new(start_index, transactions);
+			sharded_transactions
+				.push(SubBlocksForShard::new(shard, vec![first_round, second_round]));
+			start_index += num_transactions;
+		}
+
+		Ok(PartitionedTransactions::new(sharded_transactions, Vec::new()))
+	}
+}
+
+/// Gets the locations in storage of the resources the block prologue writes.
+fn metadata_writes() -> Result<Vec<StateKey>, anyhow::Error> {
+	METADATA_WRITES
+		.iter()
+		.map(|(module, name)| {
+			let tag = StructTag {
+				address: AccountAddress::ONE,
+				module: Identifier::new(*module)?,
+				name: Identifier::new(*name)?,
+				type_params: vec![],
+			};
+			Ok(StateKey::access_path(AccessPath::new(AccountAddress::ONE, tag.access_vector())))
+		})
+		.collect()
+}
+
+/// Assigns consecutive transactions to each shard, in as even runs as their number allows.
+fn consecutive_shards(num_transactions: usize, num_shards: usize) -> Vec<usize> {
+	let per_shard = num_transactions.div_ceil(num_shards).max(1);
+	(0..num_transactions).map(|index| index / per_shard).collect()
+}
+
+/// Whether no transaction accesses an account which a transaction in another shard writes.
+fn shards_are_independent(shards: &[usize], accesses: &[AccessSet]) -> bool {
+	let mut writers: HashMap<&AccountAddress, usize> = HashMap::new();
+	for (shard, access_set) in shards.iter().zip(accesses) {
+		for account in &access_set.writes {
+			if *writers.entry(account).or_insert(*shard) != *shard {
+				return false;
+			}
+		}
+	}
+
+	shards.iter().zip(accesses).all(|(shard, access_set)| {
+		access_set
+			.reads
+			.iter()
+			.all(|account| writers.get(account).unwrap_or(shard) == shard)
+	})
+}
+
+/// Gets the account of a location in storage.
+fn account_of(key: &StateKey) -> Option<AccountAddress> {
+	match key.inner() {
+		StateKeyInner::AccessPath(path) => Some(path.address),
+		StateKeyInner::TableItem { handle, .. } => Some(handle.0),
+		StateKeyInner::Raw(_) => None,
+	}
+}
+
+fn accounts_of<'a>(
+	locations: impl IntoIterator<Item = &'a StorageLocation>,
+) -> Option<Vec<AccountAddress>> {
+	locations
+		.into_iter()
+		.map(|location| match location {
+			StorageLocation::Specific(key) => account_of(key),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Whether the Aptos transaction analyzer declares the accesses of a transaction.
+fn is_declared(signed: &SignedTransaction) -> bool {
+	match signed.payload() {
+		TransactionPayload::EntryFunction(function) => {
+			let module = function.module();
+			*module.address() == AccountAddress::ONE
+				&& DECLARED_ENTRY_FUNCTIONS
+					.contains(&(module.name().as_str(), function.function().as_str()))
+		}
+		_ => false,
+	}
+}
+
+/// Gets the declared accesses of a user transaction, leaving out the total supply, which the
+/// sharded executor aggregates across shards.
+fn accesses(transaction: &SignatureVerifiedTransaction) -> Option<AccessSet> {
+	match transaction {
+		SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(signed))
+			if is_declared(signed) => {}
+		_ => return None,
+	}
+
+	let analyzed = AnalyzedTransaction::new(transaction.clone());
+	let writes = analyzed.write_hints().iter().filter(|location| match location {
+		StorageLocation::Specific(key) => *key != *TOTAL_SUPPLY_STATE_KEY,
+		_ => true,
+	});
+	Some(AccessSet::new(accounts_of(analyzed.read_hints())?, accounts_of(writes)?))
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	fn account(byte: u8) -> AccountAddress {
+		AccountAddress::new([byte; AccountAddress::LENGTH])
+	}
+
+	#[test]
+	fn test_shards_are_independent() -> Result<(), anyhow::Error> {
+		let accesses = vec![
+			AccessSet::new(vec![account(1), account(9)], vec![account(1)]),
+			AccessSet::new(vec![account(2), account(9)], vec![account(2)]),
+			AccessSet::new(vec![account(1)], vec![account(1), account(3)]),
+		];
+
+		// reading an account which no transaction writes does not conflict
+		assert!(shards_are_independent(&[0, 1, 0], &accesses));
+		// writing an account in two shards conflicts
+		assert!(!shards_are_independent(&[0, 1, 1], &accesses));
+
+		// reading an account which another shard writes conflicts
+		let accesses = vec![
+			AccessSet::new(vec![account(1)], vec![account(1)]),
+			AccessSet::new(vec![account(2), account(1)], vec![account(2)]),
+		];
+		assert!(!shards_are_independent(&[0, 1], &accesses));
+		assert!(shards_are_independent(&[1, 1], &accesses));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_consecutive_shards() -> Result<(), anyhow::Error> {
+		assert_eq!(consecutive_shards(5, 2), vec![0, 0, 0, 1, 1]);
+		assert_eq!(consecutive_shards(8, 4), vec![0, 0, 1, 1, 2, 2, 3, 3]);
+		// fewer transactions than shards leaves shards empty
+		assert_eq!(consecutive_shards(2, 4), vec![0, 1]);
+		assert_eq!(consecutive_shards(0, 4), Vec::<usize>::new());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,360 @@
+use super::Executor;
+use aptos_crypto::HashValue;
+use aptos_types::{
+	ledger_info::LedgerInfoWithSignatures,
+	state_proof::StateProof,
+	state_store::state_value::StateValueChunkWithProof,
+	transaction::{TransactionOutputListWithProof, Version},
+};
+use movement_types::{BlockCommitment, Commitment, Id};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// The number of state values in each chunk of a snapshot.
+pub const STATE_SNAPSHOT_CHUNK_SIZE: usize = 10_000;
+
+const MANIFEST_FILE: &str = "manifest.bcs";
+
+/// Describes a snapshot of the state at the end of a block, and the chunks it is stored in.
+///
+/// The state proof digests to the commitment of the block, which a snapshot is imported against.
+/// Its ledger info commits to the outputs of the block, whose last transaction info commits to
+/// the root hash of the state, which in turn proves each chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshotManifest {
+	/// The height of the block the snapshot was taken at.
+	pub block_height: u64,
+	/// The version of the state.
+	pub version: Version,
+	/// The root hash of the state.
+	pub root_hash: HashValue,
+	/// The ledger info at the version.
+	pub ledger_info: LedgerInfoWithSignatures,
+	/// The state proof the commitment of the block was digested from.
+	pub state_proof: StateProof,
+	/// The outputs of the block, ending at the version.
+	pub outputs: TransactionOutputListWithProof,
+	/// The files of the chunks, relative to the snapshot directory.
+	pub chunks: Vec<String>,
+}
+
+impl StateSnapshotManifest {
+	/// Reads the manifest of the snapshot in a directory.
+	pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+		let bytes = std::fs::read(path.join(MANIFEST_FILE))?;
+		Ok(bcs::from_bytes(&bytes)?)
+	}
+
+	fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+		std::fs::write(path.join(MANIFEST_FILE), bcs::to_bytes(self)?)?;
+		Ok(())
+	}
+
+	/// Gets the commitment of the block the snapshot was taken at, as it was committed.
+	pub fn commitment(&self) -> BlockCommitment {
+		BlockCommitment {
+			height: self.block_height,
+			block_id: Id(*self.ledger_info.ledger_info().consensus_block_id()),
+			commitment: Commitment::digest_state_proof(&self.state_proof),
+		}
+	}
+
+	/// Checks that the snapshot is of the trusted commitment, that the outputs are proven by the
+	/// ledger info, and that they end at the version of the state.
+	fn verify(&self, trusted_commitment: &BlockCommitment) -> Result<(), anyhow::Error> {
+		let commitment = self.commitment();
+		if commitment != *trusted_commitment {
+			anyhow::bail!(
+				"Snapshot commitment {:?} is not the trusted {:?}",
+				commitment,
+				trusted_commitment
+			);
+		}
+		if self.state_proof.latest_ledger_info_w_sigs() != &self.ledger_info {
+			anyhow::bail!("State proof is not of the ledger info of the snapshot");
+		}
+		if self.ledger_info.ledger_info().version() != self.version {
+			anyhow::bail!(
+				"Ledger info is at version {}, not at the snapshot version {}",
+				self.ledger_info.ledger_info().version(),
+				self.version
+			);
+		}
+		let first_version = self
+			.outputs
+			.first_transaction_output_version
+			.ok_or(anyhow::anyhow!("No outputs in the snapshot"))?;
+		self.outputs.verify(self.ledger_info.ledger_info(), Some(first_version))?;
+		let root_hash = self
+			.outputs
+			.proof
+			.transaction_infos
+			.last()
+			.ok_or(anyhow::anyhow!("No transaction infos in the snapshot"))?
+			.ensure_state_checkpoint_hash()?;
+		if root_hash != self.root_hash {
+			anyhow::bail!("State root hash {} is not the committed {}", self.root_hash, root_hash);
+		}
+		Ok(())
+	}
+}
+
+impl Executor {
+	/// Exports the state at the end of the block head into a directory, as a manifest and files
+	/// of chunks of at most `chunk_size` state values.
+	pub async fn export_state_snapshot(
+		&self,
+		path: &Path,
+		chunk_size: usize,
+	) -> Result<StateSnapshotManifest, anyhow::Error> {
+		let block_height = self.get_block_head_height()?;
+		let chunk_size = chunk_size.max(1);
+		let db_reader = self.db.reader.clone();
+		let path = path.to_path_buf();
+		tokio::task::spawn_blocking(move || {
+			let ledger_info = db_reader.get_latest_ledger_info()?;
+			let (start_version, version, _) = db_reader.get_block_info_by_height(block_height)?;
+			// the same state proof as the block was committed with, since it is the latest
+			let state_proof = db_reader.get_state_proof(version)?;
+			if ledger_info.ledger_info().version() != version {
+				anyhow::bail!(
+					"Block head {} is not committed at version {}",
+					block_height,
+					version
+				);
+			}
+
+			// the outputs of the whole block are kept, so that its block info can be read
+			let outputs = db_reader.get_transaction_outputs(
+				start_version,
+				version - start_version + 1,
+				version,
+			)?;
+			let root_hash = outputs
+				.proof
+				.transaction_infos
+				.last()
+				.ok_or(anyhow::anyhow!("No transaction infos for block {}", block_height))?
+				.ensure_state_checkpoint_hash()?;
+
+			std::fs::create_dir_all(&path)?;
+			let count = db_reader.get_state_item_count(version)?;
+			let mut chunks = Vec::new();
+			for first_index in (0..count).step_by(chunk_size) {
+				let chunk =
+					db_reader.get_state_value_chunk_with_proof(version, first_index, chunk_size)?;
+				let file = format!("chunk-{:08}.bcs", chunks.len());
+				std::fs::write(path.join(&file), bcs::to_bytes(&chunk)?)?;
+				chunks.push(file);
+			}
+			info!(
+				"Exported {} state values in {} chunks at version {}",
+				count,
+				chunks.len(),
+				version
+			);
+
+			let manifest = StateSnapshotManifest {
+				block_height,
+				version,
+				root_hash,
+				ledger_info,
+				state_proof,
+				outputs,
+				chunks,
+			};
+			manifest.write(&path)?;
+			Ok(manifest)
+		})
+		.await?
+	}
+
+	/// Imports the state snapshot in a directory, which becomes the block head.
+	///
+	/// The snapshot must be of a trusted commitment, such as one accepted by settlement, since
+	/// its ledger info carries no signatures. Every chunk is verified against the root hash of
+	/// the state before it is written.
+	pub async fn import_state_snapshot(
+		&self,
+		path: &Path,
+		trusted_commitment: &BlockCommitment,
+	) -> Result<StateSnapshotManifest, anyhow::Error> {
+		let db_writer = self.db.writer.clone();
+		let block_executor = self.block_executor.clone();
+		let path = path.to_path_buf();
+		let trusted_commitment = trusted_commitment.clone();
+		tokio::task::spawn_blocking(move || {
+			let manifest = StateSnapshotManifest::read(&path)?;
+			manifest.verify(&trusted_commitment)?;
+
+			let mut receiver =
+				db_writer.get_state_snapshot_receiver(manifest.version, manifest.root_hash)?;
+			for file in &manifest.chunks {
+				let chunk = read_chunk(&path.join(file))?;
+				if chunk.root_hash != manifest.root_hash {
+					anyhow::bail!("Chunk {} is not of the state {}", file, manifest.root_hash);
+				}
+				receiver.add_chunk(chunk.raw_values, chunk.proof)?;
+			}
+			receiver.finish_box()?;
+
+			db_writer.finalize_state_snapshot(
+				manifest.version,
+				manifest.outputs.clone(),
+				std::slice::from_ref(&manifest.ledger_info),
+			)?;
+
+			// the block executor caches the committed block, so it is reset onto the snapshot
+			block_executor.reset()?;
+			info!(
+				"Imported the state snapshot of block {} at version {}",
+				manifest.block_height, manifest.version
+			);
+			Ok(manifest)
+		})
+		.await?
+	}
+}
+
+fn read_chunk(path: &Path) -> Result<StateValueChunkWithProof, anyhow::Error> {
+	let bytes = std::fs::read(path)?;
+	Ok(bcs::from_bytes(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+	use aptos_sdk::{
+		transaction_builder::TransactionFactory,
+		types::{AccountKey, LocalAccount},
+	};
+	use aptos_types::{
+		account_config::aptos_test_root_address,
+		block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
+		block_metadata::BlockMetadata,
+		transaction::{signature_verified_transaction::into_signature_verified_block, Transaction},
+	};
+	use rand::SeedableRng;
+
+	async fn execute_block(
+		executor: &Executor,
+		block_id: HashValue,
+		timestamp: u64,
+		transactions: Vec<Transaction>,
+	) -> Result<BlockCommitment, anyhow::Error> {
+		let (epoch, round) = executor.get_next_epoch_and_round().await?;
+		let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			executor.proposer.author(),
+			vec![],
+			vec![],
+			timestamp,
+		));
+		let transactions = ExecutableTransactions::Unsharded(into_signature_verified_block(
+			std::iter::once(block_metadata).chain(transactions).collect(),
+		));
+		executor.execute_block(ExecutableBlock::new(block_id, transactions)).await
+	}
+
+	#[tokio::test]
+	async fn test_export_import_state_snapshot() -> Result<(), anyhow::Error> {
+		let private_key = Ed25519PrivateKey::generate_for_testing();
+		let (executor, _tempdir) = Executor::try_test_default(private_key.clone())?;
+		executor.rollover_genesis_now().await?;
+
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(executor.maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+		let tx_factory =
+			TransactionFactory::new(executor.maptos_config.chain.maptos_chain_id.clone());
+		let mut rng = ::rand::rngs::StdRng::from_seed([8u8; 32]);
+		let mut head_commitment = None;
+		for _ in 0..3 {
+			let new_account = LocalAccount::generate(&mut rng);
+			let user_account_creation_tx = root_account.sign_with_transaction_builder(
+				tx_factory.create_user_account(new_account.public_key()),
+			);
+			let commitment = execute_block(
+				&executor,
+				HashValue::random(),
+				chrono::Utc::now().timestamp_micros() as u64,
+				vec![Transaction::UserTransaction(user_account_creation_tx)],
+			)
+			.await?;
+			head_commitment = Some(commitment);
+		}
+
+		// small chunks, so that the snapshot spans several of them
+		let snapshot_dir = tempfile::tempdir()?;
+		let manifest = executor.export_state_snapshot(snapshot_dir.path(), 100).await?;
+		assert_eq!(manifest.block_height, executor.get_block_head_height()?);
+		assert_eq!(Some(manifest.commitment()), head_commitment);
+		assert!(manifest.chunks.len() > 1);
+
+		let (imported, _imported_tempdir) = Executor::try_test_default(private_key)?;
+		let imported_manifest = imported
+			.import_state_snapshot(snapshot_dir.path(), &manifest.commitment())
+			.await?;
+		assert_eq!(imported_manifest.root_hash, manifest.root_hash);
+		assert_eq!(imported.db.reader.get_synced_version()?, manifest.version);
+		assert_eq!(imported.get_block_head_height()?, manifest.block_height);
+
+		// both executors commit the same next block
+		let block_id = HashValue::random();
+		let timestamp = chrono::Utc::now().timestamp_micros() as u64;
+		let commitment = execute_block(&executor, block_id, timestamp, vec![]).await?;
+		let imported_commitment = execute_block(&imported, block_id, timestamp, vec![]).await?;
+		assert_eq!(imported_commitment, commitment);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_import_state_snapshot_rejects_tampered_chunk() -> Result<(), anyhow::Error> {
+		let private_key = Ed25519PrivateKey::generate_for_testing();
+		let (executor, _tempdir) = Executor::try_test_default(private_key.clone())?;
+		executor.rollover_genesis_now().await?;
+
+		let snapshot_dir = tempfile::tempdir()?;
+		let manifest = executor.export_state_snapshot(snapshot_dir.path(), 100).await?;
+
+		// replace the first chunk with the second, which the proofs do not cover
+		let first = snapshot_dir.path().join(&manifest.chunks[0]);
+		std::fs::copy(snapshot_dir.path().join(&manifest.chunks[1]), first)?;
+
+		let (imported, _imported_tempdir) = Executor::try_test_default(private_key)?;
+		assert!(imported
+			.import_state_snapshot(snapshot_dir.path(), &manifest.commitment())
+			.await
+			.is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_import_state_snapshot_rejects_untrusted_commitment() -> Result<(), anyhow::Error>
+	{
+		let private_key = Ed25519PrivateKey::generate_for_testing();
+		let (executor, _tempdir) = Executor::try_test_default(private_key.clone())?;
+		executor.rollover_genesis_now().await?;
+
+		let snapshot_dir = tempfile::tempdir()?;
+		let manifest = executor.export_state_snapshot(snapshot_dir.path(), 100).await?;
+
+		// a snapshot is only imported against the commitment it was taken at
+		let mut untrusted = manifest.commitment();
+		untrusted.commitment = Commitment::test();
+		let (imported, _imported_tempdir) = Executor::try_test_default(private_key)?;
+		assert!(imported.import_state_snapshot(snapshot_dir.path(), &untrusted).await.is_err());
+		assert_eq!(imported.get_block_head_height()?, 0);
+
+		Ok(())
+	}
+}
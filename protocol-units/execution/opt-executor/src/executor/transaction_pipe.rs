@@ -1,14 +1,11 @@
-use super::Executor;
+use super::{validation::TransactionValidator, Executor};
 use aptos_mempool::core_mempool::CoreMempool;
 use aptos_mempool::SubmissionStatus;
 use aptos_mempool::{core_mempool::TimelineState, MempoolClientRequest};
 use aptos_sdk::types::mempool_status::{MempoolStatus, MempoolStatusCode};
-use aptos_types::transaction::{SignedTransaction, VMValidatorResult};
-use aptos_vm_validator::vm_validator::TransactionValidation;
-use aptos_vm_validator::vm_validator::VMValidator;
+use aptos_types::transaction::SignedTransaction;
 use async_channel::Sender;
 use futures::StreamExt;
-use std::sync::Arc;
 use thiserror::Error;
 use tracing::{debug, info, info_span, warn, Instrument};
 
@@ -115,16 +112,10 @@ impl Executor {
 		transaction: SignedTransaction,
 		transaction_channel: Sender<SignedTransaction>,
 	) -> Result<SubmissionStatus, TransactionPipeError> {
-		// Pre-execute Tx to validate its content.
-		// Re-create the validator for each Tx because it uses a frozen version of the ledger.
-		let vm_validator = VMValidator::new(Arc::clone(&self.db.reader));
-		let tx_result = vm_validator.validate_transaction(transaction.clone())?;
-		match tx_result.status() {
-			Some(_) => {
-				let ms = MempoolStatus::new(MempoolStatusCode::VmError);
-				return Ok((ms, tx_result.status()));
-			}
-			None => {}
+		// Pre-execute Tx to validate its content, and apply the policies of the node.
+		if let Some(status) = self.validators.validate(&transaction)? {
+			debug!("Transaction rejected by validation: {:?}", status);
+			return Ok(status);
 		}
 
 		debug!(
@@ -143,6 +134,7 @@ impl Executor {
 		match status.code {
 			MempoolStatusCode::Accepted => {
 				debug!("Transaction accepted: {:?}", transaction);
+				self.validators.record_accepted(&transaction)?;
 				transaction_channel
 					.send(transaction.clone())
 					.await
@@ -169,6 +161,7 @@ mod tests {
 	use std::collections::BTreeSet;
 
 	use super::*;
+	use crate::executor::validation::SenderFilter;
 	use aptos_api::{accept_type::AcceptType, transactions::SubmitTransactionPost};
 	use aptos_types::{
 		account_config, test_helpers::transaction_test_helpers, transaction::SignedTransaction,
@@ -223,6 +216,36 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_pipe_mempool_rejects_by_validator() -> Result<(), anyhow::Error> {
+		// header
+		let (executor, _tempdir) = Executor::try_test_default(GENESIS_KEYPAIR.0.clone())?;
+		let mut executor = executor
+			.with_validator(SenderFilter::blocklist([account_config::aptos_test_root_address()]));
+		let user_transaction = create_signed_transaction(1, &executor.maptos_config);
+
+		// send transaction to mempool
+		let (req_sender, callback) = oneshot::channel();
+		executor
+			.mempool_client_sender
+			.send(MempoolClientRequest::SubmitTransaction(user_transaction.clone(), req_sender))
+			.await?;
+
+		// tick the transaction pipe
+		let (tx, rx) = async_channel::unbounded();
+		let mut core_mempool = CoreMempool::new(&executor.node_config.clone());
+		executor
+			.tick_transaction_pipe(&mut core_mempool, tx, &mut std::time::Instant::now())
+			.await?;
+
+		// the transaction is rejected with the status of the validator
+		let (status, _vm_status_code) = callback.await??;
+		assert_eq!(status.code, MempoolStatusCode::RejectedByFilter);
+		assert!(rx.is_empty());
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_pipe_mempool_cancellation() -> Result<(), anyhow::Error> {
 		// header
@@ -0,0 +1,395 @@
+use aptos_mempool::SubmissionStatus;
+use aptos_sdk::types::mempool_status::{MempoolStatus, MempoolStatusCode};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+	account_address::AccountAddress, transaction::SignedTransaction, vm_status::StatusCode,
+};
+use aptos_vm_validator::vm_validator::{TransactionValidation, VMValidator};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The most senders the rate limiter tracks before it forgets those outside their window.
+const MAX_TRACKED_SENDERS: usize = 2usize.pow(16);
+
+/// Validates transactions before they are added to the mempool.
+pub trait TransactionValidator: Send + Sync {
+	/// Returns the status the transaction is rejected with, or `None` if it is valid.
+	fn validate(
+		&self,
+		transaction: &SignedTransaction,
+	) -> Result<Option<SubmissionStatus>, anyhow::Error>;
+
+	/// Records a transaction which every validator accepted and the mempool took.
+	fn record_accepted(&self, _transaction: &SignedTransaction) -> Result<(), anyhow::Error> {
+		Ok(())
+	}
+}
+
+/// A chain of validators, which rejects a transaction with the status of the first validator
+/// rejecting it.
+#[derive(Clone, Default)]
+pub struct ValidatorChain {
+	validators: Vec<Arc<dyn TransactionValidator>>,
+}
+
+impl ValidatorChain {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a validator to the end of the chain.
+	pub fn with_validator(mut self, validator: impl TransactionValidator + 'static) -> Self {
+		self.validators.push(Arc::new(validator));
+		self
+	}
+
+	pub fn len(&self) -> usize {
+		self.validators.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.validators.is_empty()
+	}
+}
+
+impl TransactionValidator for ValidatorChain {
+	fn validate(
+		&self,
+		transaction: &SignedTransaction,
+	) -> Result<Option<SubmissionStatus>, anyhow::Error> {
+		for validator in &self.validators {
+			if let Some(status) = validator.validate(transaction)? {
+				return Ok(Some(status));
+			}
+		}
+		Ok(None)
+	}
+
+	fn record_accepted(&self, transaction: &SignedTransaction) -> Result<(), anyhow::Error> {
+		for validator in &self.validators {
+			validator.record_accepted(transaction)?;
+		}
+		Ok(())
+	}
+}
+
+/// Validates transactions against the VM at the latest committed state.
+pub struct VmValidator {
+	db_reader: Arc<dyn DbReader>,
+}
+
+impl VmValidator {
+	pub fn new(db_reader: Arc<dyn DbReader>) -> Self {
+		Self { db_reader }
+	}
+}
+
+impl TransactionValidator for VmValidator {
+	fn validate(
+		&self,
+		transaction: &SignedTransaction,
+	) -> Result<Option<SubmissionStatus>, anyhow::Error> {
+		// Re-create the validator for each Tx because it uses a frozen version of the ledger.
+		let vm_validator = VMValidator::new(Arc::clone(&self.db_reader));
+		let result = vm_validator.validate_transaction(transaction.clone())?;
+		Ok(result
+			.status()
+			.map(|status| (MempoolStatus::new(MempoolStatusCode::VmError), Some(status))))
+	}
+}
+
+/// Rejects transactions from senders on a blocklist, or off an allowlist.
+pub enum SenderFilter {
+	Blocklist(BTreeSet<AccountAddress>),
+	Allowlist(BTreeSet<AccountAddress>),
+}
+
+impl SenderFilter {
+	pub fn blocklist(senders: impl IntoIterator<Item = AccountAddress>) -> Self {
+		Self::Blocklist(senders.into_iter().collect())
+	}
+
+	pub fn allowlist(senders: impl IntoIterator<Item = AccountAddress>) -> Self {
+		Self::Allowlist(senders.into_iter().collect())
+	}
+
+	fn allows(&self, sender: &AccountAddress) -> bool {
+		match self {
+			Self::Blocklist(senders) => !senders.contains(sender),
+			Self::Allowlist(senders) => senders.contains(sender),
+		}
+	}
+}
+
+impl TransactionValidator for SenderFilter {
+	fn validate(
+		&self,
+		transaction: &SignedTransaction,
+	) -> Result<Option<SubmissionStatus>, anyhow::Error> {
+		if self.allows(&transaction.sender()) {
+			return Ok(None);
+		}
+		let status = MempoolStatus::new(MempoolStatusCode::RejectedByFilter)
+			.with_message(format!("Sender {} is not allowed", transaction.sender()));
+		Ok(Some((status, None)))
+	}
+}
+
+/// Rejects transactions larger than a number of bytes.
+pub struct MaxTransactionSize {
+	max_bytes: usize,
+}
+
+impl MaxTransactionSize {
+	pub fn new(max_bytes: usize) -> Self {
+		Self { max_bytes }
+	}
+}
+
+impl TransactionValidator for MaxTransactionSize {
+	fn validate(
+		&self,
+		transaction: &SignedTransaction,
+	) -> Result<Option<SubmissionStatus>, anyhow::Error> {
+		let bytes = transaction.txn_bytes_len();
+		if bytes <= self.max_bytes {
+			return Ok(None);
+		}
+		let status = MempoolStatus::new(MempoolStatusCode::VmError).with_message(format!(
+			"Transaction of {} bytes exceeds the maximum of {} bytes",
+			bytes, self.max_bytes
+		));
+		Ok(Some((status, Some(StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE))))
+	}
+}
+
+/// Rejects transactions from senders who submitted too many in the current period, counting only
+/// the accepted transactions.
+pub struct SenderRateLimiter {
+	max_transactions: u64,
+	period: Duration,
+	/// The start of the current period of each sender, and the transactions submitted in it.
+	windows: Mutex<HashMap<AccountAddress, (Instant, u64)>>,
+}
+
+impl SenderRateLimiter {
+	pub fn new(max_transactions: u64, period: Duration) -> Self {
+		Self { max_transactions, period, windows: Mutex::new(HashMap::new()) }
+	}
+}
+
+impl SenderRateLimiter {
+	/// Gets the transactions the sender submitted in the current period, which starts anew once
+	/// the period is over.
+	fn with_count<R>(
+		&self,
+		sender: AccountAddress,
+		f: impl FnOnce(&mut u64) -> R,
+	) -> Result<R, anyhow::Error> {
+		let now = Instant::now();
+		let mut windows = self
+			.windows
+			.lock()
+			.map_err(|e| anyhow::anyhow!("Rate limiter poisoned: {}", e))?;
+		if windows.len() >= MAX_TRACKED_SENDERS {
+			windows.retain(|_, (start, _)| now.duration_since(*start) < self.period);
+		}
+
+		let (start, count) = windows.entry(sender).or_insert((now, 0));
+		if now.duration_since(*start) >= self.period {
+			*start = now;
+			*count = 0;
+		}
+		Ok(f(count))
+	}
+}
+
+impl TransactionValidator for SenderRateLimiter {
+	fn validate(
+		&self,
+		transaction: &SignedTransaction,
+	) -> Result<Option<SubmissionStatus>, anyhow::Error> {
+		let count = self.with_count(transaction.sender(), |count| *count)?;
+		if count >= self.max_transactions {
+			let status = MempoolStatus::new(MempoolStatusCode::TooManyTransactions)
+				.with_message(format!("Sender {} exceeded its rate limit", transaction.sender()));
+			return Ok(Some((status, None)));
+		}
+		Ok(None)
+	}
+
+	fn record_accepted(&self, transaction: &SignedTransaction) -> Result<(), anyhow::Error> {
+		self.with_count(transaction.sender(), |count| *count += 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use aptos_types::{account_config, chain_id::ChainId, test_helpers::transaction_test_helpers};
+	use aptos_vm_genesis::GENESIS_KEYPAIR;
+
+	fn create_signed_transaction(
+		sender: AccountAddress,
+		sequence_number: u64,
+	) -> SignedTransaction {
+		transaction_test_helpers::get_test_txn_with_chain_id(
+			sender,
+			sequence_number,
+			&GENESIS_KEYPAIR.0,
+			GENESIS_KEYPAIR.1.clone(),
+			ChainId::test(),
+		)
+	}
+
+	fn rejection_code(
+		validator: &impl TransactionValidator,
+		transaction: &SignedTransaction,
+	) -> Result<Option<MempoolStatusCode>, anyhow::Error> {
+		Ok(validator.validate(transaction)?.map(|(status, _)| status.code))
+	}
+
+	/// Validates a transaction, and records it if it is accepted.
+	fn submit(
+		validator: &impl TransactionValidator,
+		transaction: &SignedTransaction,
+	) -> Result<Option<MempoolStatusCode>, anyhow::Error> {
+		let code = rejection_code(validator, transaction)?;
+		if code.is_none() {
+			validator.record_accepted(transaction)?;
+		}
+		Ok(code)
+	}
+
+	/// Rejects transactions with a sequence number.
+	struct RejectSequenceNumber(u64);
+
+	impl TransactionValidator for RejectSequenceNumber {
+		fn validate(
+			&self,
+			transaction: &SignedTransaction,
+		) -> Result<Option<SubmissionStatus>, anyhow::Error> {
+			if transaction.sequence_number() != self.0 {
+				return Ok(None);
+			}
+			Ok(Some((MempoolStatus::new(MempoolStatusCode::InvalidSeqNumber), None)))
+		}
+	}
+
+	#[test]
+	fn test_sender_filter() -> Result<(), anyhow::Error> {
+		let blocked = AccountAddress::random();
+		let transaction = create_signed_transaction(blocked, 0);
+		let other_transaction = create_signed_transaction(AccountAddress::random(), 0);
+
+		let blocklist = SenderFilter::blocklist([blocked]);
+		assert_eq!(
+			rejection_code(&blocklist, &transaction)?,
+			Some(MempoolStatusCode::RejectedByFilter)
+		);
+		assert_eq!(rejection_code(&blocklist, &other_transaction)?, None);
+
+		let allowlist = SenderFilter::allowlist([blocked]);
+		assert_eq!(rejection_code(&allowlist, &transaction)?, None);
+		assert_eq!(
+			rejection_code(&allowlist, &other_transaction)?,
+			Some(MempoolStatusCode::RejectedByFilter)
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_max_transaction_size() -> Result<(), anyhow::Error> {
+		let transaction = create_signed_transaction(account_config::aptos_test_root_address(), 0);
+		let bytes = transaction.txn_bytes_len();
+
+		assert_eq!(rejection_code(&MaxTransactionSize::new(bytes), &transaction)?, None);
+		let (status, vm_status) =
+			MaxTransactionSize::new(bytes - 1).validate(&transaction)?.expect("rejected");
+		assert_eq!(status.code, MempoolStatusCode::VmError);
+		assert_eq!(vm_status, Some(StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_sender_rate_limiter() -> Result<(), anyhow::Error> {
+		let sender = AccountAddress::random();
+		let rate_limiter = SenderRateLimiter::new(2, Duration::from_secs(3600));
+		for sequence_number in 0..2 {
+			let transaction = create_signed_transaction(sender, sequence_number);
+			assert_eq!(submit(&rate_limiter, &transaction)?, None);
+		}
+		assert_eq!(
+			submit(&rate_limiter, &create_signed_transaction(sender, 2))?,
+			Some(MempoolStatusCode::TooManyTransactions)
+		);
+
+		// other senders have their own limit
+		let other_transaction = create_signed_transaction(AccountAddress::random(), 0);
+		assert_eq!(submit(&rate_limiter, &other_transaction)?, None);
+
+		// only accepted transactions count towards the limit
+		let rate_limiter = SenderRateLimiter::new(1, Duration::from_secs(3600));
+		let transaction = create_signed_transaction(sender, 0);
+		assert_eq!(rejection_code(&rate_limiter, &transaction)?, None);
+		assert_eq!(submit(&rate_limiter, &transaction)?, None);
+		assert_eq!(
+			submit(&rate_limiter, &create_signed_transaction(sender, 1))?,
+			Some(MempoolStatusCode::TooManyTransactions)
+		);
+
+		// the limit resets with the period
+		let rate_limiter = SenderRateLimiter::new(1, Duration::ZERO);
+		for sequence_number in 0..3 {
+			let transaction = create_signed_transaction(sender, sequence_number);
+			assert_eq!(submit(&rate_limiter, &transaction)?, None);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_validator_chain_rejects_with_first_rejection() -> Result<(), anyhow::Error> {
+		let sender = AccountAddress::random();
+		let transaction = create_signed_transaction(sender, 0);
+
+		let chain = ValidatorChain::new()
+			.with_validator(SenderFilter::blocklist([AccountAddress::random()]))
+			.with_validator(SenderFilter::blocklist([sender]))
+			.with_validator(MaxTransactionSize::new(0));
+		assert_eq!(chain.len(), 3);
+		assert_eq!(
+			rejection_code(&chain, &transaction)?,
+			Some(MempoolStatusCode::RejectedByFilter)
+		);
+
+		assert_eq!(rejection_code(&ValidatorChain::new(), &transaction)?, None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_validator_chain_records_accepted_transactions() -> Result<(), anyhow::Error> {
+		let sender = AccountAddress::random();
+		let chain = ValidatorChain::new()
+			.with_validator(SenderRateLimiter::new(1, Duration::from_secs(3600)))
+			.with_validator(RejectSequenceNumber(0));
+
+		// a transaction rejected further down the chain does not count towards the rate limit
+		assert_eq!(
+			submit(&chain, &create_signed_transaction(sender, 0))?,
+			Some(MempoolStatusCode::InvalidSeqNumber)
+		);
+		assert_eq!(submit(&chain, &create_signed_transaction(sender, 1))?, None);
+		assert_eq!(
+			submit(&chain, &create_signed_transaction(sender, 2))?,
+			Some(MempoolStatusCode::TooManyTransactions)
+		);
+
+		Ok(())
+	}
+}
@@ -1,9 +1,12 @@
 use super::common::{
-	default_maptos_chain_id, default_maptos_private_key, default_maptos_rest_listen_hostname,
-	default_maptos_rest_listen_port,
+	default_maptos_chain_id, default_maptos_executor_shards, default_maptos_genesis_path,
+	default_maptos_max_transaction_size, default_maptos_private_key,
+	default_maptos_rest_listen_hostname, default_maptos_rest_listen_port,
+	default_maptos_sender_rate_limit,
 };
+use super::genesis::GenesisConfig;
 use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_types::chain_id::ChainId;
+use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -27,6 +30,30 @@ pub struct Config {
 
 	/// The path to the Aptos database
 	pub maptos_db_path: Option<PathBuf>,
+
+	/// The number of shards blocks are executed in
+	#[serde(default = "default_maptos_executor_shards")]
+	pub maptos_executor_shards: usize,
+
+	/// The path to the genesis file, which must be the same across the network
+	#[serde(default = "default_maptos_genesis_path")]
+	pub maptos_genesis_path: Option<PathBuf>,
+
+	/// The maximum size of a submitted transaction in bytes
+	#[serde(default = "default_maptos_max_transaction_size")]
+	pub maptos_max_transaction_size: Option<usize>,
+
+	/// The most transactions a sender may submit per second
+	#[serde(default = "default_maptos_sender_rate_limit")]
+	pub maptos_sender_rate_limit: Option<u64>,
+
+	/// The senders whose transactions are rejected
+	#[serde(default)]
+	pub maptos_sender_blocklist: Vec<AccountAddress>,
+
+	/// The only senders whose transactions are accepted, if any
+	#[serde(default)]
+	pub maptos_sender_allowlist: Option<Vec<AccountAddress>>,
 }
 
 impl Default for Config {
@@ -37,6 +64,31 @@ impl Default for Config {
 			maptos_rest_listen_port: default_maptos_rest_listen_port(),
 			maptos_private_key: default_maptos_private_key(),
 			maptos_db_path: None,
+			maptos_executor_shards: default_maptos_executor_shards(),
+			maptos_genesis_path: default_maptos_genesis_path(),
+			maptos_max_transaction_size: default_maptos_max_transaction_size(),
+			maptos_sender_rate_limit: default_maptos_sender_rate_limit(),
+			maptos_sender_blocklist: Vec::new(),
+			maptos_sender_allowlist: None,
+		}
+	}
+}
+
+impl Config {
+	/// Gets the genesis from the genesis file, or the default genesis of a test chain if there is
+	/// none, whose root key is well known rather than that of the node.
+	pub fn try_genesis(&self) -> Result<GenesisConfig, anyhow::Error> {
+		let genesis = match &self.maptos_genesis_path {
+			Some(path) => GenesisConfig::try_from_file(path)?,
+			None => GenesisConfig::test(self.maptos_chain_id),
+		};
+		if genesis.chain_id != self.maptos_chain_id {
+			anyhow::bail!(
+				"Genesis chain id {} does not match the chain id {}",
+				genesis.chain_id,
+				self.maptos_chain_id
+			);
 		}
+		Ok(genesis)
 	}
 }
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform, ValidCryptoMaterialStringExt};
@@ -70,6 +71,18 @@ env_default!(
 // The default chain id
 env_default!(default_maptos_chain_id, "MAPTOS_CHAIN_ID", ChainId, ChainId::from_str("27").unwrap());
 
+// The default number of executor shards, where a single shard executes blocks unsharded
+env_default!(default_maptos_executor_shards, "MAPTOS_EXECUTOR_SHARDS", usize, 1);
+
+// The default genesis file, where none uses the default genesis of the chain
+env_default!(default_maptos_genesis_path, "MAPTOS_GENESIS_PATH", PathBuf);
+
+// The default maximum size of a submitted transaction in bytes, where none leaves it unlimited
+env_default!(default_maptos_max_transaction_size, "MAPTOS_MAX_TRANSACTION_SIZE", usize);
+
+// The default most transactions a sender may submit per second, where none leaves it unlimited
+env_default!(default_maptos_sender_rate_limit, "MAPTOS_SENDER_RATE_LIMIT", u64);
+
 // The default private key
 pub fn default_maptos_private_key() -> Ed25519PrivateKey {
 	match std::env::var("MAPTOS_PRIVATE_KEY") {
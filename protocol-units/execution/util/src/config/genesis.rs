@@ -0,0 +1,190 @@
+use aptos_crypto::{
+	bls12381,
+	ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+	HashValue, PrivateKey, Uniform,
+};
+use aptos_types::{
+	account_address::AccountAddress,
+	chain_id::ChainId,
+	on_chain_config::{OnChainConsensusConfig, OnChainExecutionConfig},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// This number should not exceed u64::MAX / 1_000_000_000
+// to avoid overflowing calculations in aptos-vm-genesis.
+// This will last several centuries.
+const EPOCH_DURATION_SECS: u64 = 60 * 60 * 24 * 1024 * 128;
+
+/// An account funded by the genesis block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisAccount {
+	/// The address of the account, which is also its authentication key
+	pub address: AccountAddress,
+
+	/// The balance of the account in octas
+	pub balance: u64,
+}
+
+/// A validator of the genesis block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisValidator {
+	/// The address of the account owning the stake
+	pub owner_address: AccountAddress,
+
+	/// The address of the account operating the validator
+	pub operator_address: AccountAddress,
+
+	/// The address of the account voting with the stake
+	pub voter_address: AccountAddress,
+
+	/// The stake of the validator
+	pub stake: u64,
+
+	/// The consensus public key of the validator
+	pub consensus_public_key: bls12381::PublicKey,
+
+	/// The proof of possession of the consensus private key
+	pub proof_of_possession: bls12381::ProofOfPossession,
+}
+
+/// The genesis of a chain, from which every node bootstraps the same state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisConfig {
+	/// The chain id
+	pub chain_id: ChainId,
+
+	/// The public key of the root account, which signs the transactions of the genesis block
+	pub root_public_key: Ed25519PublicKey,
+
+	/// The path to the framework release bundle, or the head release bundle if none
+	#[serde(default)]
+	pub framework_path: Option<PathBuf>,
+
+	/// The sha3-256 hash of the framework release bundle, which must be given with its path
+	#[serde(default)]
+	pub framework_hash: Option<HashValue>,
+
+	/// The validators, the first of which proposes every block
+	#[serde(default)]
+	pub validators: Vec<GenesisValidator>,
+
+	/// The number of test validators, of a test chain which lists no validators
+	#[serde(default = "default_validator_count")]
+	pub validator_count: usize,
+
+	/// The stake of each test validator
+	#[serde(default = "default_validator_stake")]
+	pub validator_stake: u64,
+
+	/// The duration of an epoch
+	#[serde(default = "default_epoch_duration_secs")]
+	pub epoch_duration_secs: u64,
+
+	/// Whether the chain is a test chain, on which the root account can mint
+	#[serde(default = "default_is_test")]
+	pub is_test: bool,
+
+	/// The minimum stake of a validator
+	#[serde(default)]
+	pub min_stake: u64,
+
+	/// The maximum stake of a validator
+	#[serde(default = "default_max_stake")]
+	pub max_stake: u64,
+
+	/// The yearly percentage of rewards on stake
+	#[serde(default)]
+	pub rewards_apy_percentage: u64,
+
+	/// The percentage by which the voting power may increase in an epoch
+	#[serde(default = "default_voting_power_increase_limit")]
+	pub voting_power_increase_limit: u64,
+
+	/// The entries of the default gas schedule to override, by name
+	#[serde(default)]
+	pub gas_schedule: BTreeMap<String, u64>,
+
+	/// The on-chain consensus config
+	#[serde(default = "OnChainConsensusConfig::default_for_genesis")]
+	pub consensus_config: OnChainConsensusConfig,
+
+	/// The on-chain execution config, whose block gas limit bounds the gas of every block
+	#[serde(default = "OnChainExecutionConfig::default_for_genesis")]
+	pub execution_config: OnChainExecutionConfig,
+
+	/// The accounts funded by the genesis block
+	#[serde(default)]
+	pub accounts: Vec<GenesisAccount>,
+}
+
+impl GenesisConfig {
+	/// Creates the default genesis of a chain.
+	pub fn new(chain_id: ChainId, root_public_key: Ed25519PublicKey) -> Self {
+		Self {
+			chain_id,
+			root_public_key,
+			framework_path: None,
+			framework_hash: None,
+			validators: Vec::new(),
+			validator_count: default_validator_count(),
+			validator_stake: default_validator_stake(),
+			epoch_duration_secs: default_epoch_duration_secs(),
+			is_test: default_is_test(),
+			min_stake: 0,
+			max_stake: default_max_stake(),
+			rewards_apy_percentage: 0,
+			voting_power_increase_limit: default_voting_power_increase_limit(),
+			gas_schedule: BTreeMap::new(),
+			consensus_config: OnChainConsensusConfig::default_for_genesis(),
+			execution_config: OnChainExecutionConfig::default_for_genesis(),
+			accounts: Vec::new(),
+		}
+	}
+
+	/// Creates the default genesis of a test chain, whose root key is well known so that it is
+	/// the same on every node.
+	pub fn test(chain_id: ChainId) -> Self {
+		Self::new(chain_id, test_root_private_key().public_key())
+	}
+
+	/// Reads the genesis from a JSON file.
+	pub fn try_from_file(path: &Path) -> Result<Self, anyhow::Error> {
+		let genesis = std::fs::read(path)
+			.map_err(|e| anyhow::anyhow!("Failed to read genesis file {:?}: {}", path, e))?;
+		serde_json::from_slice(&genesis)
+			.map_err(|e| anyhow::anyhow!("Failed to parse genesis file {:?}: {}", path, e))
+	}
+}
+
+/// Gets the private key of the root account of test chains, which is generated from a fixed seed.
+pub fn test_root_private_key() -> Ed25519PrivateKey {
+	Ed25519PrivateKey::generate(&mut StdRng::from_seed([0u8; 32]))
+}
+
+fn default_validator_count() -> usize {
+	1
+}
+
+fn default_validator_stake() -> u64 {
+	100_000_000
+}
+
+fn default_epoch_duration_secs() -> u64 {
+	EPOCH_DURATION_SECS
+}
+
+fn default_is_test() -> bool {
+	true
+}
+
+fn default_max_stake() -> u64 {
+	// 1M APTOS coins (with 8 decimals).
+	100_000_000_000_000
+}
+
+fn default_voting_power_increase_limit() -> u64 {
+	50
+}
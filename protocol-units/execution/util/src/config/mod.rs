@@ -3,6 +3,7 @@ pub mod client;
 pub mod common;
 pub mod faucet;
 pub mod fin;
+pub mod genesis;
 pub mod indexer;
 pub mod indexer_processor;
 use serde::{Deserialize, Serialize};
@@ -2,7 +2,8 @@ use anyhow::Error;
 use bcs;
 use mempool_util::{MempoolBlockOperations, MempoolTransaction, MempoolTransactionOperations};
 use movement_types::{Block, Id};
-use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use rocksdb::{ColumnFamilyDescriptor, Options, WriteBatch, DB};
+use std::collections::HashSet;
 use std::fmt::Write;
 use std::sync::Arc;
 
@@ -48,6 +49,21 @@ impl RocksdbMempool {
 		key
 	}
 
+	/// Estimates the number of transactions in the mempool.
+	///
+	/// The estimate comes from RocksDB and may lag behind recent writes and deletions.
+	pub async fn estimate_mempool_transaction_count(&self) -> Result<u64, Error> {
+		let db = self.db.clone();
+		tokio::task::spawn_blocking(move || {
+			let cf_handle = db
+				.cf_handle("mempool_transactions")
+				.ok_or_else(|| Error::msg("CF handle not found"))?;
+			let count = db.property_int_value_cf(&cf_handle, "rocksdb.estimate-num-keys")?;
+			Ok(count.unwrap_or(0))
+		})
+		.await?
+	}
+
 	fn internal_get_mempool_transaction_key(
 		db: Arc<DB>,
 		transaction_id: &Id,
@@ -94,6 +110,7 @@ impl MempoolTransactionOperations for RocksdbMempool {
 		.await?
 	}
 
+	/// Adds the transactions which are not in the mempool yet, all in a single write batch.
 	async fn add_mempool_transactions(
 		&self,
 		transactions: Vec<MempoolTransaction>,
@@ -107,16 +124,22 @@ impl MempoolTransactionOperations for RocksdbMempool {
 				.cf_handle("transaction_lookups")
 				.ok_or_else(|| Error::msg("CF handle not found"))?;
 
+			let mut batch = WriteBatch::default();
+			let mut batched = HashSet::new();
 			for tx in transactions {
-				if Self::internal_has_mempool_transaction(db.clone(), &tx.transaction.id())? {
+				let id = tx.transaction.id();
+				if batched.contains(&id) || Self::internal_has_mempool_transaction(db.clone(), &id)?
+				{
 					continue;
 				}
 
 				let serialized_tx = bcs::to_bytes(&tx)?;
 				let key = Self::construct_mempool_transaction_key(&tx);
-				db.put_cf(&mempool_transactions_cf_handle, &key, &serialized_tx)?;
-				db.put_cf(&transaction_lookups_cf_handle, tx.transaction.id().to_vec(), &key)?;
+				batch.put_cf(&mempool_transactions_cf_handle, &key, &serialized_tx);
+				batch.put_cf(&transaction_lookups_cf_handle, id.to_vec(), &key);
+				batched.insert(id);
 			}
+			db.write(batch)?;
 			Ok::<(), Error>(())
 		})
 		.await??;
@@ -0,0 +1 @@
+buildtime::proto_build_main!("movementlabs/protocol_units/sequencing/memseq/v1beta1.proto");
@@ -0,0 +1,2 @@
+tonic::include_proto!("movementlabs.protocol_units.sequencing.memseq.v1beta1"); // The string specified here
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("memseq-grpc-descriptor");
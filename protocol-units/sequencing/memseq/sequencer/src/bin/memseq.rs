@@ -0,0 +1,20 @@
+use memseq::manager::Manager;
+
+use std::env;
+
+const TIMING_LOG_ENV: &str = "MEMSEQ_TIMING_LOG";
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+	let tracing_config =
+		movement_tracing::Config { timing_log_path: env::var_os(TIMING_LOG_ENV).map(Into::into) };
+	let _guard = movement_tracing::init_tracing_subscriber(tracing_config);
+
+	let dot_movement = dot_movement::DotMovement::try_from_env()?;
+	let config_file = dot_movement.try_get_or_create_config_file().await?;
+	let manager = Manager::new(config_file).await?;
+	manager.try_initialize().await?;
+	manager.try_run().await?;
+
+	Ok(())
+}
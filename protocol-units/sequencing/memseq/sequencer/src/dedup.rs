@@ -0,0 +1,228 @@
+use memseq_util::DedupWindow;
+use movement_types::{Block, Id};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Returned when publishing transactions which were sequenced recently.
+#[derive(Debug, thiserror::Error)]
+#[error("Transactions were already sequenced: {}", display_ids(.ids))]
+pub struct DuplicateTransactionError {
+	pub ids: Vec<Id>,
+}
+
+fn display_ids(ids: &[Id]) -> String {
+	ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// The ids of the transactions sequenced in one block.
+#[derive(Debug)]
+struct SequencedBlock {
+	height: u64,
+	timestamp_ms: u64,
+	transaction_ids: Vec<Id>,
+}
+
+/// A bounded index of recently sequenced transactions.
+///
+/// Blocks are evicted once they fall out of the window, either by the number of blocks sequenced after them
+/// or by the time elapsed since they were sequenced.
+/// Transactions popped for a block which has not been recorded yet are held in flight.
+#[derive(Debug)]
+pub struct RecentlySequenced {
+	window: DedupWindow,
+	height: u64,
+	blocks: VecDeque<SequencedBlock>,
+	// counts, because the same transaction may be observed in more than one block within the window
+	transaction_ids: HashMap<Id, usize>,
+	in_flight: HashSet<Id>,
+}
+
+impl RecentlySequenced {
+	pub fn new(window: DedupWindow) -> Self {
+		Self {
+			window,
+			height: 0,
+			blocks: VecDeque::new(),
+			transaction_ids: HashMap::new(),
+			in_flight: HashSet::new(),
+		}
+	}
+
+	pub fn window(&self) -> DedupWindow {
+		self.window
+	}
+
+	/// The number of transactions currently in the index.
+	pub fn len(&self) -> usize {
+		self.transaction_ids.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.transaction_ids.is_empty()
+	}
+
+	/// Whether the transaction was sequenced within the window, or is in flight.
+	pub fn contains(&self, transaction_id: &Id) -> bool {
+		self.transaction_ids.contains_key(transaction_id) || self.in_flight.contains(transaction_id)
+	}
+
+	/// Marks transactions which were popped for a block as in flight until the block is recorded.
+	pub fn insert_in_flight(&mut self, transaction_ids: impl IntoIterator<Item = Id>) {
+		self.in_flight.extend(transaction_ids);
+	}
+
+	/// Releases transactions in flight which were not sequenced after all.
+	pub fn release_in_flight(&mut self, transaction_ids: impl IntoIterator<Item = Id>) {
+		for id in transaction_ids {
+			self.in_flight.remove(&id);
+		}
+	}
+
+	/// Removes transactions whose block failed to be published, so that they may be published again.
+	pub fn remove_unpublished(&mut self, transaction_ids: impl IntoIterator<Item = Id>) {
+		let unpublished: HashSet<Id> = transaction_ids.into_iter().collect();
+		if unpublished.is_empty() {
+			return;
+		}
+		for block in &mut self.blocks {
+			block.transaction_ids.retain(|id| !unpublished.contains(id));
+		}
+		for id in &unpublished {
+			self.transaction_ids.remove(id);
+			self.in_flight.remove(id);
+		}
+	}
+
+	/// Adds the transactions of a sequenced block to the index and evicts blocks which fell out of the window.
+	pub fn insert_block(&mut self, block: &Block, timestamp_ms: u64) {
+		self.height += 1;
+		let transaction_ids =
+			block.transactions.iter().map(|transaction| transaction.id()).collect();
+		self.insert(SequencedBlock { height: self.height, timestamp_ms, transaction_ids });
+		self.prune(timestamp_ms);
+	}
+
+	fn insert(&mut self, block: SequencedBlock) {
+		for id in &block.transaction_ids {
+			self.in_flight.remove(id);
+			*self.transaction_ids.entry(id.clone()).or_insert(0) += 1;
+		}
+		self.blocks.push_back(block);
+	}
+
+	/// Evicts the blocks which fell out of the window at the given time.
+	pub fn prune(&mut self, now_ms: u64) {
+		while let Some(oldest) = self.blocks.front() {
+			let expired = match self.window {
+				DedupWindow::Blocks(blocks) => self.height - oldest.height >= blocks,
+				DedupWindow::Millis(millis) => now_ms.saturating_sub(oldest.timestamp_ms) >= millis,
+			};
+			if !expired {
+				break;
+			}
+
+			if let Some(oldest) = self.blocks.pop_front() {
+				for id in oldest.transaction_ids {
+					if let Some(count) = self.transaction_ids.get_mut(&id) {
+						*count -= 1;
+						if *count == 0 {
+							self.transaction_ids.remove(&id);
+						}
+					}
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use movement_types::Transaction;
+
+	fn block_with(data: &[u8]) -> Block {
+		let transactions = data.iter().map(|d| Transaction::new(vec![*d], 0)).collect();
+		Block::new(Default::default(), vec![0], transactions)
+	}
+
+	#[test]
+	fn test_window_by_blocks() {
+		let mut index = RecentlySequenced::new(DedupWindow::Blocks(2));
+		let first = Transaction::new(vec![1], 0);
+
+		index.insert_block(&block_with(&[1, 2]), 0);
+		assert!(index.contains(&first.id()));
+
+		index.insert_block(&block_with(&[3]), 0);
+		assert!(index.contains(&first.id()));
+
+		// the first block falls out of the window
+		index.insert_block(&block_with(&[4]), 0);
+		assert!(!index.contains(&first.id()));
+		assert_eq!(index.len(), 2);
+	}
+
+	#[test]
+	fn test_window_by_time() {
+		let mut index = RecentlySequenced::new(DedupWindow::Millis(1000));
+		let first = Transaction::new(vec![1], 0);
+
+		index.insert_block(&block_with(&[1]), 0);
+		index.prune(999);
+		assert!(index.contains(&first.id()));
+
+		index.prune(1000);
+		assert!(!index.contains(&first.id()));
+		assert!(index.is_empty());
+	}
+
+	#[test]
+	fn test_transaction_in_several_blocks() {
+		let mut index = RecentlySequenced::new(DedupWindow::Blocks(2));
+		let first = Transaction::new(vec![1], 0);
+
+		index.insert_block(&block_with(&[1]), 0);
+		index.insert_block(&block_with(&[1]), 0);
+		index.insert_block(&block_with(&[2]), 0);
+
+		// the second block containing the transaction is still in the window
+		assert!(index.contains(&first.id()));
+	}
+
+	#[test]
+	fn test_in_flight() {
+		let mut index = RecentlySequenced::new(DedupWindow::Blocks(1));
+		let first = Transaction::new(vec![1], 0);
+		let second = Transaction::new(vec![2], 0);
+
+		index.insert_in_flight([first.id(), second.id()]);
+		assert!(index.contains(&first.id()));
+		assert!(index.is_empty());
+
+		// a released transaction may be published again
+		index.release_in_flight([second.id()]);
+		assert!(!index.contains(&second.id()));
+
+		// once its block is recorded, the transaction falls out with the window
+		index.insert_block(&block_with(&[1]), 0);
+		index.insert_block(&block_with(&[3]), 0);
+		assert!(!index.contains(&first.id()));
+	}
+	#[test]
+	fn test_remove_unpublished() {
+		let mut index = RecentlySequenced::new(DedupWindow::Blocks(2));
+		let first = Transaction::new(vec![1], 0);
+		let second = Transaction::new(vec![2], 0);
+
+		index.insert_block(&block_with(&[1, 2]), 0);
+		index.remove_unpublished([first.id()]);
+		assert!(!index.contains(&first.id()));
+		assert!(index.contains(&second.id()));
+
+		// a later block with the transaction is not evicted along with the unpublished one
+		index.insert_block(&block_with(&[1]), 0);
+		index.insert_block(&block_with(&[3]), 0);
+		assert!(index.contains(&first.id()));
+		assert!(!index.contains(&second.id()));
+	}
+}
@@ -1,9 +1,24 @@
+pub mod dedup;
+pub mod manager;
+pub mod rotation;
+pub mod service;
+
+use dedup::{DuplicateTransactionError, RecentlySequenced};
 use mempool_util::{MempoolBlockOperations, MempoolTransactionOperations};
+pub use memseq_util::{Config, DedupWindow};
 pub use move_rocks::RocksdbMempool;
-pub use movement_types::{Block, Id, Transaction};
+use movement_algs::batching::{pull_batch, BatchLimits};
+pub use movement_types::{Block, BlockMetadata, Id, Transaction};
+use rotation::Rotation;
+use sequencing_util::rotation::{ProposerKey, SequencerSet};
 pub use sequencing_util::Sequencer;
-use std::{path::PathBuf, sync::Arc};
-use tokio::sync::RwLock;
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+use tokio::sync::{RwLock, RwLockWriteGuard};
+use tracing::warn;
 
 #[derive(Clone)]
 pub struct Memseq<T: MempoolBlockOperations + MempoolTransactionOperations> {
@@ -13,6 +28,10 @@ pub struct Memseq<T: MempoolBlockOperations + MempoolTransactionOperations> {
 	pub parent_block: Arc<RwLock<Id>>,
 	// this value should not be changed after initialization
 	building_time_ms: u64,
+	// set when the sequencer takes turns proposing with a sequencer set
+	rotation: Option<Rotation>,
+	// set when resubmitted transactions are rejected within a window
+	recently_sequenced: Option<Arc<RwLock<RecentlySequenced>>>,
 }
 
 impl<T: MempoolBlockOperations + MempoolTransactionOperations> Memseq<T> {
@@ -22,7 +41,14 @@ impl<T: MempoolBlockOperations + MempoolTransactionOperations> Memseq<T> {
 		parent_block: Arc<RwLock<Id>>,
 		building_time_ms: u64,
 	) -> Self {
-		Self { mempool, block_size, parent_block, building_time_ms }
+		Self {
+			mempool,
+			block_size,
+			parent_block,
+			building_time_ms,
+			rotation: None,
+			recently_sequenced: None,
+		}
 	}
 
 	pub fn with_block_size(mut self, block_size: u32) -> Self {
@@ -38,6 +64,147 @@ impl<T: MempoolBlockOperations + MempoolTransactionOperations> Memseq<T> {
 	pub fn building_time_ms(&self) -> u64 {
 		self.building_time_ms
 	}
+
+	/// Makes the sequencer take turns proposing blocks with the other members of its sequencer set.
+	pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+		self.rotation = Some(rotation);
+		self
+	}
+
+	pub fn rotation(&self) -> Option<&Rotation> {
+		self.rotation.as_ref()
+	}
+
+	/// Makes the sequencer reject transactions which were sequenced within the window.
+	pub fn with_dedup_window(mut self, window: DedupWindow) -> Self {
+		self.recently_sequenced = Some(Arc::new(RwLock::new(RecentlySequenced::new(window))));
+		self
+	}
+
+	/// Locks the index of recently sequenced transactions for publishing the given transactions,
+	/// failing with a [DuplicateTransactionError] if any of them were sequenced recently.
+	///
+	/// Transactions are neither popped for a block nor recorded as sequenced while the guard
+	/// is held, so the check and the insert into the mempool which follows it are atomic.
+	async fn lock_for_publishing(
+		&self,
+		transactions: &[Transaction],
+	) -> Result<Option<RwLockWriteGuard<'_, RecentlySequenced>>, anyhow::Error> {
+		let recently_sequenced = match &self.recently_sequenced {
+			Some(recently_sequenced) => recently_sequenced,
+			None => return Ok(None),
+		};
+
+		let mut recently_sequenced = recently_sequenced.write().await;
+		recently_sequenced.prune(now_ms()?);
+		let duplicates: Vec<Id> = transactions
+			.iter()
+			.map(|transaction| transaction.id())
+			.filter(|id| recently_sequenced.contains(id))
+			.collect();
+		if !duplicates.is_empty() {
+			return Err(DuplicateTransactionError { ids: duplicates }.into());
+		}
+		Ok(Some(recently_sequenced))
+	}
+
+	/// Pops transactions for the next block, marking them in flight until the block is recorded
+	/// so that they cannot be published again in the meantime.
+	async fn pop_transactions(&self, n: usize) -> Result<Vec<Transaction>, anyhow::Error> {
+		let mut recently_sequenced = match &self.recently_sequenced {
+			Some(recently_sequenced) => Some(recently_sequenced.write().await),
+			None => None,
+		};
+		let transactions = self.mempool.pop_transactions(n).await?;
+		if let Some(recently_sequenced) = &mut recently_sequenced {
+			recently_sequenced.insert_in_flight(transactions.iter().map(Transaction::id));
+		}
+		Ok(transactions)
+	}
+
+	/// Returns transactions which were popped for a block that was not sequenced to the mempool.
+	async fn return_transactions(
+		&self,
+		transactions: Vec<Transaction>,
+	) -> Result<(), anyhow::Error> {
+		let mut recently_sequenced = match &self.recently_sequenced {
+			Some(recently_sequenced) => Some(recently_sequenced.write().await),
+			None => None,
+		};
+		if let Some(recently_sequenced) = &mut recently_sequenced {
+			recently_sequenced.release_in_flight(transactions.iter().map(Transaction::id));
+		}
+		self.mempool.add_transactions(transactions).await
+	}
+
+	/// Remembers the transactions of a sequenced block.
+	async fn record_sequenced(&self, block: &Block) -> Result<(), anyhow::Error> {
+		if let Some(recently_sequenced) = &self.recently_sequenced {
+			recently_sequenced.write().await.insert_block(block, now_ms()?);
+		}
+		Ok(())
+	}
+
+	/// Forgets the transactions of blocks which failed to be published to the DA,
+	/// so that they are not rejected as sequenced when they are published again.
+	pub async fn release_unpublished(&self, transaction_ids: Vec<Id>) {
+		if let Some(recently_sequenced) = &self.recently_sequenced {
+			recently_sequenced.write().await.remove_unpublished(transaction_ids);
+		}
+	}
+
+	/// Signs a block this sequencer built and accepts it into the schedule of its sequencer set,
+	/// if it is part of one. Gets whether the block may be proposed, which it may not if another
+	/// member claimed the slot while the block was being built.
+	async fn propose_block(&self, block: &mut Block) -> Result<bool, anyhow::Error> {
+		let rotation = match &self.rotation {
+			Some(rotation) => rotation,
+			None => return Ok(true),
+		};
+		rotation.sign_block(block)?;
+		if let Err(e) = rotation.try_accept(block, now_ms()?).await {
+			warn!("Returning transactions of unscheduled block to mempool: {:?}", e);
+			return Ok(false);
+		}
+		Ok(true)
+	}
+
+	/// Observes a block proposed by another member of the sequencer set,
+	/// which was published to the DA at the given time.
+	/// Accepted blocks become the parent of the next block built by this sequencer.
+	pub async fn observe_block(
+		&self,
+		block: &Block,
+		da_timestamp_ms: u64,
+	) -> Result<(), anyhow::Error> {
+		let rotation = self
+			.rotation
+			.as_ref()
+			.ok_or(anyhow::anyhow!("Memseq is not part of a sequencer set"))?;
+		rotation.try_accept(block, da_timestamp_ms).await?;
+		*self.parent_block.write().await = block.id();
+		self.record_sequenced(block).await?;
+		Ok(())
+	}
+
+	/// Gets the metadata for the next block, or None if it is not this sequencer's turn to propose.
+	async fn next_block_metadata(&self) -> Result<Option<BlockMetadata>, anyhow::Error> {
+		let rotation = match &self.rotation {
+			Some(rotation) => rotation,
+			None => return Ok(Some(BlockMetadata::default())),
+		};
+
+		let now_ms = now_ms()?;
+		match rotation.slot_to_propose_at(now_ms).await {
+			Some(slot) => Ok(Some(rotation.metadata(slot))),
+			None => {
+				// wait for the next window, but not longer than a block would take to build
+				let wait_ms = rotation.ms_until_next_window(now_ms).await.min(self.building_time_ms);
+				tokio::time::sleep(std::time::Duration::from_millis(wait_ms)).await;
+				Ok(None)
+			}
+		}
+	}
 }
 
 impl Memseq<RocksdbMempool> {
@@ -53,61 +220,122 @@ impl Memseq<RocksdbMempool> {
 		Ok(Self::new(mempool, block_size, parent_block, building_time_ms))
 	}
 
+	/// Creates a Memseq backed by Move Rocks from the memseq config.
+	pub fn try_from_config(config: &Config) -> Result<Self, anyhow::Error> {
+		let path = config.try_sequencer_database_path()?;
+		let memseq = Self::try_move_rocks(
+			PathBuf::from(path),
+			config.memseq_max_block_size,
+			config.memseq_build_time,
+		)?;
+		let memseq = match config.memseq_dedup_window {
+			Some(window) => memseq.with_dedup_window(window),
+			None => memseq,
+		};
+
+		if !config.is_rotating() {
+			return Ok(memseq);
+		}
+
+		let set = SequencerSet::try_from_hex(
+			&config.memseq_sequencer_set,
+			config.memseq_slot_duration_ms,
+			config.memseq_takeover_timeout_ms,
+		)?;
+		let key = ProposerKey::try_from_hex(config.try_memseq_sequencer_private_key()?)?;
+		Ok(memseq.with_rotation(Rotation::try_new(key, set)?))
+	}
+
+	/// Estimates the number of transactions waiting in the mempool.
+	pub async fn mempool_depth(&self) -> Result<u64, anyhow::Error> {
+		self.mempool.estimate_mempool_transaction_count().await
+	}
+
+	/// Reads the memseq config from the TOML file in the environment and creates a Memseq from it.
 	pub fn try_from_env_toml_file() -> Result<Self, anyhow::Error> {
-		unimplemented!("try_from_env_toml_file")
+		let config = Config::try_from_env_toml_file()?;
+		Self::try_from_config(&config)
+	}
+
+	/// Reads the memseq config from a TOML file and creates a Memseq from it.
+	pub fn try_from_toml_file(path: &Path) -> Result<Self, anyhow::Error> {
+		let config = Config::try_from_toml_file(&path.to_path_buf())?;
+		Self::try_from_config(&config)
 	}
 }
 
+/// Gets the current unix time in milliseconds.
+fn now_ms() -> Result<u64, anyhow::Error> {
+	Ok(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis() as u64)
+}
+
 impl<T: MempoolBlockOperations + MempoolTransactionOperations> Sequencer for Memseq<T> {
+	/// Publishes the transactions unless any of them was sequenced recently, in which case
+	/// none are published and this fails with a [DuplicateTransactionError] naming them.
 	async fn publish_many(&self, transactions: Vec<Transaction>) -> Result<(), anyhow::Error> {
-		self.mempool.add_transactions(transactions).await?;
-		Ok(())
+		let _recently_sequenced = self.lock_for_publishing(&transactions).await?;
+		self.mempool.add_transactions(transactions).await
 	}
 
+	/// Publishes the transaction unless it was sequenced recently,
+	/// in which case this fails with a [DuplicateTransactionError].
 	async fn publish(&self, transaction: Transaction) -> Result<(), anyhow::Error> {
-		self.mempool.add_transaction(transaction).await?;
-		Ok(())
+		let _recently_sequenced =
+			self.lock_for_publishing(std::slice::from_ref(&transaction)).await?;
+		self.mempool.add_transaction(transaction).await
 	}
 
 	async fn wait_for_next_block(&self) -> Result<Option<Block>, anyhow::Error> {
-		let mut transactions = Vec::with_capacity(self.block_size as usize);
-
-		let mut now = std::time::Instant::now();
-
-		loop {
-			let current_block_size = transactions.len() as u32;
-			if current_block_size >= self.block_size {
-				break;
-			}
-
-			let remaining = self.block_size - current_block_size;
-			let mut transactions_to_add = self.mempool.pop_transactions(remaining as usize).await?;
-			transactions.append(&mut transactions_to_add);
-
-			// sleep to yield to other tasks and wait for more transactions
-			tokio::task::yield_now().await;
+		let metadata = match self.next_block_metadata().await? {
+			Some(metadata) => metadata,
+			None => return Ok(None),
+		};
 
-			if now.elapsed().as_millis() as u64 > self.building_time_ms {
-				break;
+		let limits = BatchLimits::new(Duration::from_millis(self.building_time_ms))
+			.with_max_count(self.block_size as usize);
+		let batch =
+			pull_batch(&limits, |_| 0, |remaining| self.pop_transactions(remaining)).await?;
+		let transactions = batch.items;
+		if !batch.overflow.is_empty() {
+			if let Err(e) = self.return_transactions(batch.overflow).await {
+				self.return_transactions(transactions).await?;
+				return Err(e);
 			}
 		}
 
 		if transactions.is_empty() {
-			Ok(None)
-		} else {
-			let new_block = {
-				let parent_block = self.parent_block.read().await.clone();
-				Block::new(Default::default(), parent_block.to_vec(), transactions)
-			};
+			return Ok(None);
+		}
 
-			// update the parent block
-			{
-				let mut parent_block = self.parent_block.write().await;
-				*parent_block = new_block.id();
+		let mut new_block = {
+			let parent_block = self.parent_block.read().await.clone();
+			Block::new(metadata, parent_block.to_vec(), transactions)
+		};
+
+		// the popped transactions are returned to the mempool unless the block is sequenced
+		let sequenced = match self.propose_block(&mut new_block).await {
+			Ok(true) => self.record_sequenced(&new_block).await.map(|_| true),
+			proposed => proposed,
+		};
+		match sequenced {
+			Ok(true) => {}
+			Ok(false) => {
+				self.return_transactions(new_block.transactions).await?;
+				return Ok(None);
 			}
+			Err(e) => {
+				self.return_transactions(new_block.transactions).await?;
+				return Err(e);
+			}
+		}
 
-			Ok(Some(new_block))
+		// update the parent block
+		{
+			let mut parent_block = self.parent_block.write().await;
+			*parent_block = new_block.id();
 		}
+
+		Ok(Some(new_block))
 	}
 }
 
@@ -143,6 +371,82 @@ pub mod test {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_publish_rejects_recently_sequenced() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq =
+			Memseq::try_move_rocks(path, 128, 100)?.with_dedup_window(DedupWindow::Blocks(1));
+
+		let sequenced = Transaction::new(vec![1], 0);
+		memseq.publish(sequenced.clone()).await?;
+		memseq.wait_for_next_block().await?;
+
+		// resubmitting a sequenced transaction is rejected
+		let result = memseq.publish(sequenced.clone()).await;
+		let error = result.unwrap_err();
+		let duplicate = error
+			.downcast_ref::<DuplicateTransactionError>()
+			.ok_or(anyhow::anyhow!("Expected a duplicate transaction error"))?;
+		assert_eq!(duplicate.ids, vec![sequenced.id()]);
+
+		// none of the transactions of a batch with a duplicate are published
+		let fresh = Transaction::new(vec![2], 0);
+		let result = memseq.publish_many(vec![sequenced.clone(), fresh.clone()]).await;
+		assert!(result.is_err());
+		assert!(memseq.wait_for_next_block().await?.is_none());
+		memseq.publish_many(vec![fresh.clone()]).await?;
+		let block = memseq.wait_for_next_block().await?.ok_or(anyhow::anyhow!("Block not found"))?;
+		assert_eq!(block.transactions, vec![fresh]);
+
+		// the first block has fallen out of the window
+		memseq.publish(sequenced).await?;
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_return_transactions() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq =
+			Memseq::try_move_rocks(path, 128, 100)?.with_dedup_window(DedupWindow::Blocks(1));
+
+		let transaction = Transaction::new(vec![1], 0);
+		memseq.publish(transaction.clone()).await?;
+		let popped = memseq.pop_transactions(10).await?;
+		assert_eq!(popped, vec![transaction.clone()]);
+
+		// popped transactions are in flight, so they cannot be published again
+		assert!(memseq.publish(transaction.clone()).await.is_err());
+
+		// returned transactions are no longer in flight and are sequenced in the next block
+		memseq.return_transactions(popped).await?;
+		let block = memseq.wait_for_next_block().await?.ok_or(anyhow::anyhow!("Block not found"))?;
+		assert_eq!(block.transactions, vec![transaction]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_release_unpublished() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq =
+			Memseq::try_move_rocks(path, 128, 100)?.with_dedup_window(DedupWindow::Blocks(8));
+
+		let transaction = Transaction::new(vec![1], 0);
+		memseq.publish(transaction.clone()).await?;
+		let block = memseq.wait_for_next_block().await?.ok_or(anyhow::anyhow!("Block not found"))?;
+		assert!(memseq.publish(transaction.clone()).await.is_err());
+
+		// the block did not reach the DA, so its transactions may be published again
+		memseq.release_unpublished(block.transactions.iter().map(Transaction::id).collect()).await;
+		memseq.publish(transaction).await?;
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_publish_error_propagation() -> Result<(), anyhow::Error> {
 		let mempool = MockMempool;
@@ -236,6 +540,81 @@ pub mod test {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_try_from_config() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+
+		let mut config = Config {
+			sequencer_database_path: Some(
+				path.to_str().ok_or(anyhow::anyhow!("PathBuf to str failed"))?.to_string(),
+			),
+			memseq_max_block_size: 64,
+			memseq_build_time: 300,
+			..Config::default()
+		};
+
+		let memseq = Memseq::try_from_config(&config)?;
+		assert_eq!(memseq.block_size, 64);
+		assert_eq!(memseq.building_time_ms, 300);
+		assert!(memseq.rotation().is_none());
+
+		// a missing database path is an error
+		config.sequencer_database_path = None;
+		assert!(Memseq::try_from_config(&config).is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_try_from_config_with_rotation() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+
+		let mut config = Config {
+			sequencer_database_path: Some(
+				path.to_str().ok_or(anyhow::anyhow!("PathBuf to str failed"))?.to_string(),
+			),
+			memseq_sequencer_private_key: Some(hex::encode([2; 32])),
+			memseq_sequencer_set: vec![
+				ProposerKey::from_bytes([1; 32])?.id().to_string(),
+				ProposerKey::from_bytes([2; 32])?.id().to_string(),
+			],
+			..Config::default()
+		};
+
+		let memseq = Memseq::try_from_config(&config)?;
+		let rotation = memseq.rotation().ok_or(anyhow::anyhow!("Rotation not configured"))?;
+		assert_eq!(rotation.sequencer_id(), &ProposerKey::from_bytes([2; 32])?.id());
+
+		// the sequencer must be a member of the set
+		config.memseq_sequencer_private_key = Some(hex::encode([3; 32]));
+		assert!(Memseq::try_from_config(&config).is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_try_from_toml_file() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let config_path = dir.path().join("memseq.toml");
+		let database_path = dir.path().join("sequencer");
+
+		let config = Config {
+			sequencer_database_path: Some(
+				database_path.to_str().ok_or(anyhow::anyhow!("PathBuf to str failed"))?.to_string(),
+			),
+			memseq_max_block_size: 32,
+			..Config::default()
+		};
+		config.try_write_to_toml_file(&config_path)?;
+
+		let memseq = Memseq::try_from_toml_file(&config_path)?;
+		assert_eq!(memseq.block_size, 32);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_memseq_initialization() -> Result<(), anyhow::Error> {
 		let dir = tempdir()?;
@@ -0,0 +1,39 @@
+use crate::{Config, Memseq, RocksdbMempool};
+use godfig::{backend::config_file::ConfigFile, Godfig};
+
+#[derive(Clone)]
+pub struct Manager {
+	godfig: Godfig<Config, ConfigFile>,
+}
+
+// Implements a very simple manager for a standalone memseq service.
+impl Manager {
+	pub async fn new(file: tokio::fs::File) -> Result<Self, anyhow::Error> {
+		let godfig = Godfig::new(
+			ConfigFile::new(file),
+			vec![
+				"memseq_config".to_string(), // the standalone memseq config lives under its own key
+			],
+		);
+		Ok(Self { godfig })
+	}
+
+	/// Writes the default config if none is present yet.
+	pub async fn try_initialize(&self) -> Result<(), anyhow::Error> {
+		self.godfig
+			.try_transaction(|config| async move { Ok(Some(config.unwrap_or_default())) })
+			.await?;
+		Ok(())
+	}
+
+	pub async fn try_memseq(&self) -> Result<(Memseq<RocksdbMempool>, Config), anyhow::Error> {
+		let config = self.godfig.try_wait_for_ready().await?;
+		let memseq = Memseq::try_from_config(&config)?;
+		Ok((memseq, config))
+	}
+
+	pub async fn try_run(&self) -> Result<(), anyhow::Error> {
+		let (memseq, config) = self.try_memseq().await?;
+		memseq.run_server(config.memseq_service()).await
+	}
+}
@@ -0,0 +1,186 @@
+use movement_types::{Block, BlockMetadata, Id};
+use sequencing_util::rotation::{ProposerKey, ProposerSchedule, SequencerSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// The state of a sequencer taking turns proposing blocks with the other members of its set.
+#[derive(Debug, Clone)]
+pub struct Rotation {
+	key: ProposerKey,
+	sequencer_id: Id,
+	schedule: Arc<RwLock<ProposerSchedule>>,
+}
+
+impl Rotation {
+	pub fn try_new(key: ProposerKey, set: SequencerSet) -> Result<Self, anyhow::Error> {
+		let sequencer_id = key.id();
+		if !set.members().contains(&sequencer_id) {
+			anyhow::bail!("Sequencer {} is not a member of the sequencer set", sequencer_id);
+		}
+		let schedule = Arc::new(RwLock::new(ProposerSchedule::new(set)));
+		Ok(Self { key, sequencer_id, schedule })
+	}
+
+	pub fn sequencer_id(&self) -> &Id {
+		&self.sequencer_id
+	}
+
+	/// Gets the slot this sequencer may propose for at the given time, if any.
+	///
+	/// A sequencer may claim a slot during its own takeover window if no block has been accepted for the slot yet.
+	/// Once it has claimed the slot, it may keep proposing until the slot ends.
+	pub async fn slot_to_propose_at(&self, now_ms: u64) -> Option<u64> {
+		let schedule = self.schedule.read().await;
+		let set = schedule.set();
+		let slot = set.slot_at(now_ms);
+		let rank = set.rank_of(slot, &self.sequencer_id)?;
+
+		match schedule.last_accepted() {
+			Some((last_slot, proposer)) if last_slot == slot => {
+				(proposer == &self.sequencer_id).then_some(slot)
+			}
+			Some((last_slot, _)) if last_slot > slot => None,
+			_ => (set.rank_at(now_ms) == rank).then_some(slot),
+		}
+	}
+
+	/// Gets the time until the next takeover window or slot starts.
+	pub async fn ms_until_next_window(&self, now_ms: u64) -> u64 {
+		let schedule = self.schedule.read().await;
+		let set = schedule.set();
+		let slot = set.slot_at(now_ms);
+		let elapsed = now_ms - set.slot_start_ms(slot);
+		let until_window = set.takeover_timeout_ms() - elapsed % set.takeover_timeout_ms();
+		let until_slot = set.slot_start_ms(slot + 1) - now_ms;
+		until_window.min(until_slot)
+	}
+
+	/// Builds the metadata of a block this sequencer proposes for the given slot.
+	/// The block is signed once it is built.
+	pub fn metadata(&self, slot: u64) -> BlockMetadata {
+		BlockMetadata::Proposed { proposer: self.sequencer_id.clone(), slot, signature: Vec::new() }
+	}
+
+	/// Signs a block this sequencer built.
+	pub fn sign_block(&self, block: &mut Block) -> Result<(), anyhow::Error> {
+		self.key.sign_block(block)
+	}
+
+	/// Accepts a block published to the DA at the given time into the schedule,
+	/// failing if it was not proposed by the scheduled proposer.
+	pub async fn try_accept(
+		&self,
+		block: &Block,
+		da_timestamp_ms: u64,
+	) -> Result<(), anyhow::Error> {
+		self.schedule.write().await.try_accept(block, da_timestamp_ms)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::{Memseq, RocksdbMempool, Sequencer, Transaction};
+	use tempfile::{tempdir, TempDir};
+
+	fn keys(n: u8) -> Result<Vec<ProposerKey>, anyhow::Error> {
+		(0..n).map(|i| ProposerKey::from_bytes([i + 1; 32])).collect()
+	}
+
+	fn set(
+		keys: &[ProposerKey],
+		slot_duration_ms: u64,
+		takeover_timeout_ms: u64,
+	) -> Result<SequencerSet, anyhow::Error> {
+		let members = keys.iter().map(ProposerKey::id).collect();
+		SequencerSet::try_new(members, slot_duration_ms, takeover_timeout_ms)
+	}
+
+	#[tokio::test]
+	async fn test_slot_to_propose_at() -> Result<(), anyhow::Error> {
+		let keys = keys(3)?;
+		let set = set(&keys, 1000, 250)?;
+		let leader = Rotation::try_new(keys[1].clone(), set.clone())?;
+		let follower = Rotation::try_new(keys[2].clone(), set.clone())?;
+
+		// the leader of slot 1 may claim it in the first window, the follower in the second
+		assert_eq!(leader.slot_to_propose_at(1000).await, Some(1));
+		assert_eq!(follower.slot_to_propose_at(1000).await, None);
+		assert_eq!(follower.slot_to_propose_at(1250).await, Some(1));
+		assert_eq!(leader.slot_to_propose_at(1250).await, None);
+
+		// once the leader has claimed the slot, it keeps it
+		let mut block = Block::new(leader.metadata(1), vec![0], vec![Transaction::test()]);
+		leader.sign_block(&mut block)?;
+		leader.try_accept(&block, 1000).await?;
+		follower.try_accept(&block, 1000).await?;
+		assert_eq!(leader.slot_to_propose_at(1900).await, Some(1));
+		assert_eq!(follower.slot_to_propose_at(1250).await, None);
+
+		assert_eq!(leader.ms_until_next_window(1100).await, 150);
+
+		// a sequencer must be a member of the set
+		assert!(Rotation::try_new(ProposerKey::from_bytes([9; 32])?, set).is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_follower_takes_over_from_down_leader() -> Result<(), anyhow::Error> {
+		let keys = keys(3)?;
+		let set = set(&keys, 600, 200)?;
+
+		// keys[0] is down, so only the others are running in process
+		let mut dirs: Vec<TempDir> = Vec::new();
+		let mut nodes: Vec<Memseq<RocksdbMempool>> = Vec::new();
+		for key in &keys[1..] {
+			let dir = tempdir()?;
+			let memseq = Memseq::try_move_rocks(dir.path().to_path_buf(), 16, 20)?
+				.with_rotation(Rotation::try_new(key.clone(), set.clone())?);
+			dirs.push(dir);
+			nodes.push(memseq);
+		}
+
+		// the full node accepts blocks in the order they were published
+		let mut full_node = ProposerSchedule::new(set.clone());
+		let mut takeover = false;
+		let start = std::time::Instant::now();
+		let mut round: u64 = 0;
+		while !takeover {
+			if start.elapsed().as_secs() > 10 {
+				anyhow::bail!("No follower took over from the down leader");
+			}
+
+			for (i, node) in nodes.iter().enumerate() {
+				node.publish(Transaction::new(round.to_le_bytes().to_vec(), i as u64)).await?;
+				let block = match node.wait_for_next_block().await? {
+					Some(block) => block,
+					None => continue,
+				};
+
+				let published_ms = crate::now_ms()?;
+				full_node.try_accept(&block, published_ms)?;
+				for (j, other) in nodes.iter().enumerate() {
+					if i != j {
+						other.observe_block(&block, published_ms).await?;
+					}
+				}
+
+				let (proposer, slot) =
+					block.metadata.proposer_slot().ok_or(anyhow::anyhow!("No proposer"))?;
+				assert_eq!(proposer, rotation_id(node)?);
+				if set.leader(slot) == &keys[0].id() {
+					takeover = true;
+				}
+			}
+			round += 1;
+		}
+
+		Ok(())
+	}
+
+	fn rotation_id(node: &Memseq<RocksdbMempool>) -> Result<&Id, anyhow::Error> {
+		Ok(node.rotation().ok_or(anyhow::anyhow!("No rotation"))?.sequencer_id())
+	}
+}
@@ -0,0 +1,183 @@
+use crate::{
+	dedup::DuplicateTransactionError, Block, Memseq, RocksdbMempool, Sequencer, Transaction,
+};
+use memseq_grpc::memseq_service_server::{MemseqService, MemseqServiceServer};
+use memseq_grpc::{
+	Block as GrpcBlock, PublishManyRequest, PublishManyResponse, PublishRequest, PublishResponse,
+	Transaction as GrpcTransaction, WaitForNextBlockRequest, WaitForNextBlockResponse,
+};
+use tonic::transport::Server;
+use tracing::info;
+
+/// Converts a gRPC transaction into a sequencer transaction.
+/// The id is always derived by the sequencer, so the one provided by the client is ignored.
+pub fn transaction_from_grpc(transaction: GrpcTransaction) -> Transaction {
+	Transaction::new(transaction.data, transaction.sequence_number)
+}
+
+/// Converts a sequencer transaction into a gRPC transaction.
+pub fn transaction_to_grpc(transaction: Transaction) -> GrpcTransaction {
+	GrpcTransaction {
+		id: transaction.id.to_vec(),
+		data: transaction.data,
+		sequence_number: transaction.sequence_number,
+	}
+}
+
+/// Converts a sequencer block into a gRPC block.
+pub fn block_to_grpc(block: Block) -> GrpcBlock {
+	GrpcBlock {
+		id: block.id.to_vec(),
+		parent: block.parent,
+		transactions: block.transactions.into_iter().map(transaction_to_grpc).collect(),
+	}
+}
+
+/// Maps an error from publishing transactions to a gRPC status.
+/// Recently sequenced transactions are reported as [tonic::Code::AlreadyExists].
+pub fn status_from_publish_error(error: anyhow::Error) -> tonic::Status {
+	match error.downcast_ref::<DuplicateTransactionError>() {
+		Some(duplicate) => tonic::Status::already_exists(duplicate.to_string()),
+		None => tonic::Status::internal(error.to_string()),
+	}
+}
+
+impl Memseq<RocksdbMempool> {
+	/// Serves the sequencer over gRPC at the given address.
+	pub async fn run_server(&self, address: String) -> Result<(), anyhow::Error> {
+		let reflection = tonic_reflection::server::Builder::configure()
+			.register_encoded_file_descriptor_set(memseq_grpc::FILE_DESCRIPTOR_SET)
+			.build()?;
+
+		info!("Memseq server listening on: {}", address);
+		Server::builder()
+			.accept_http1(true)
+			.add_service(MemseqServiceServer::new(self.clone()))
+			.add_service(reflection)
+			.serve(address.parse()?)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[tonic::async_trait]
+impl MemseqService for Memseq<RocksdbMempool> {
+	/// Publish a single transaction.
+	async fn publish(
+		&self,
+		request: tonic::Request<PublishRequest>,
+	) -> std::result::Result<tonic::Response<PublishResponse>, tonic::Status> {
+		let transaction = request
+			.into_inner()
+			.transaction
+			.ok_or(tonic::Status::invalid_argument("No transaction in request"))?;
+
+		Sequencer::publish(self, transaction_from_grpc(transaction))
+			.await
+			.map_err(status_from_publish_error)?;
+
+		Ok(tonic::Response::new(PublishResponse {}))
+	}
+
+	/// Publish a batch of transactions.
+	async fn publish_many(
+		&self,
+		request: tonic::Request<PublishManyRequest>,
+	) -> std::result::Result<tonic::Response<PublishManyResponse>, tonic::Status> {
+		let transactions = request
+			.into_inner()
+			.transactions
+			.into_iter()
+			.map(transaction_from_grpc)
+			.collect();
+
+		Sequencer::publish_many(self, transactions)
+			.await
+			.map_err(status_from_publish_error)?;
+
+		Ok(tonic::Response::new(PublishManyResponse {}))
+	}
+
+	/// Build the next block, waiting at most the building time.
+	async fn wait_for_next_block(
+		&self,
+		_request: tonic::Request<WaitForNextBlockRequest>,
+	) -> std::result::Result<tonic::Response<WaitForNextBlockResponse>, tonic::Status> {
+		let block = Sequencer::wait_for_next_block(self)
+			.await
+			.map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+		Ok(tonic::Response::new(WaitForNextBlockResponse { block: block.map(block_to_grpc) }))
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::DedupWindow;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_publish_and_wait_for_next_block() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq = Memseq::try_move_rocks(path, 128, 250)?;
+
+		let transaction = GrpcTransaction { data: vec![1, 2, 3], sequence_number: 0, id: vec![] };
+		MemseqService::publish(
+			&memseq,
+			tonic::Request::new(PublishRequest { transaction: Some(transaction) }),
+		)
+		.await?;
+
+		let response = MemseqService::wait_for_next_block(
+			&memseq,
+			tonic::Request::new(WaitForNextBlockRequest {}),
+		)
+		.await?
+		.into_inner();
+		let block = response.block.ok_or(anyhow::anyhow!("Block not found"))?;
+
+		let expected = Transaction::new(vec![1, 2, 3], 0);
+		assert_eq!(block.transactions.len(), 1);
+		assert_eq!(block.transactions[0].id, expected.id.to_vec());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_publish_sequenced_transaction_already_exists() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq =
+			Memseq::try_move_rocks(path, 128, 250)?.with_dedup_window(DedupWindow::Blocks(8));
+
+		let transaction = GrpcTransaction { data: vec![1, 2, 3], sequence_number: 0, id: vec![] };
+		let request = || PublishRequest { transaction: Some(transaction.clone()) };
+		MemseqService::publish(&memseq, tonic::Request::new(request())).await?;
+		Sequencer::wait_for_next_block(&memseq).await?;
+
+		let result = MemseqService::publish(&memseq, tonic::Request::new(request())).await;
+		assert_eq!(result.unwrap_err().code(), tonic::Code::AlreadyExists);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_publish_requires_transaction() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq = Memseq::try_move_rocks(path, 128, 250)?;
+
+		let result = MemseqService::publish(
+			&memseq,
+			tonic::Request::new(PublishRequest { transaction: None }),
+		)
+		.await;
+		assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+		Ok(())
+	}
+}
@@ -21,12 +21,89 @@ pub struct Config {
 	/// The memseq max block size
 	#[serde(default = "default_memseq_max_block_size")]
 	pub memseq_max_block_size: u32,
+
+	/// The hostname to listen on for the memseq service
+	#[serde(default = "default_memseq_listen_hostname")]
+	pub memseq_listen_hostname: String,
+
+	/// The port to listen on for the memseq service
+	#[serde(default = "default_memseq_listen_port")]
+	pub memseq_listen_port: u16,
+
+	/// The hostname for memseq connection
+	#[serde(default = "default_memseq_connection_hostname")]
+	pub memseq_connection_hostname: String,
+
+	/// The port for memseq connection
+	#[serde(default = "default_memseq_connection_port")]
+	pub memseq_connection_port: u16,
+
+	/// The hex ed25519 private key this sequencer signs its proposals with, its id is the public key
+	#[serde(default = "default_memseq_sequencer_private_key")]
+	pub memseq_sequencer_private_key: Option<String>,
+
+	/// The hex public keys of the sequencers taking turns proposing blocks,
+	/// leader rotation is disabled when empty
+	#[serde(default)]
+	pub memseq_sequencer_set: Vec<String>,
+
+	/// The duration of a leader rotation slot in milliseconds
+	#[serde(default = "default_memseq_slot_duration_ms")]
+	pub memseq_slot_duration_ms: u64,
+
+	/// The time a follower waits within a slot before taking over from the previous proposer, in milliseconds
+	#[serde(default = "default_memseq_takeover_timeout_ms")]
+	pub memseq_takeover_timeout_ms: u64,
+
+	/// The window within which resubmitted transactions are rejected as duplicates, disabled when unset
+	#[serde(default = "default_memseq_dedup_window")]
+	pub memseq_dedup_window: Option<DedupWindow>,
+}
+
+/// The window over which recently sequenced transactions are remembered.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupWindow {
+	/// Remember the transactions of the last n blocks.
+	Blocks(u64),
+	/// Remember the transactions sequenced within the last n milliseconds.
+	Millis(u64),
 }
 
 env_default!(default_memseq_build_time, "MEMSEQ_BUILD_TIME", u64, 1000);
 
 env_default!(default_memseq_max_block_size, "MEMSEQ_MAX_BLOCK_SIZE", u32, 2048);
 
+env_default!(
+	default_memseq_listen_hostname,
+	"MEMSEQ_LISTEN_HOSTNAME",
+	String,
+	"0.0.0.0".to_string()
+);
+
+env_default!(default_memseq_listen_port, "MEMSEQ_LISTEN_PORT", u16, 30735);
+
+env_default!(
+	default_memseq_connection_hostname,
+	"MEMSEQ_CONNECTION_HOSTNAME",
+	String,
+	"0.0.0.0".to_string()
+);
+
+env_default!(default_memseq_connection_port, "MEMSEQ_CONNECTION_PORT", u16, 30735);
+
+env_default!(default_memseq_sequencer_private_key, "MEMSEQ_SEQUENCER_PRIVATE_KEY", String);
+
+env_default!(default_memseq_slot_duration_ms, "MEMSEQ_SLOT_DURATION_MS", u64, 60_000);
+
+env_default!(default_memseq_takeover_timeout_ms, "MEMSEQ_TAKEOVER_TIMEOUT_MS", u64, 20_000);
+
+env_default!(default_memseq_dedup_window_blocks, "MEMSEQ_DEDUP_WINDOW_BLOCKS", u64, 1024);
+
+pub fn default_memseq_dedup_window() -> Option<DedupWindow> {
+	Some(DedupWindow::Blocks(default_memseq_dedup_window_blocks()))
+}
+
 impl Default for Config {
 	fn default() -> Self {
 		Config {
@@ -34,6 +111,15 @@ impl Default for Config {
 			sequencer_database_path: Config::default_sequencer_database_path(),
 			memseq_build_time: default_memseq_build_time(),
 			memseq_max_block_size: default_memseq_max_block_size(),
+			memseq_listen_hostname: default_memseq_listen_hostname(),
+			memseq_listen_port: default_memseq_listen_port(),
+			memseq_connection_hostname: default_memseq_connection_hostname(),
+			memseq_connection_port: default_memseq_connection_port(),
+			memseq_sequencer_private_key: default_memseq_sequencer_private_key(),
+			memseq_sequencer_set: Vec::new(),
+			memseq_slot_duration_ms: default_memseq_slot_duration_ms(),
+			memseq_takeover_timeout_ms: default_memseq_takeover_timeout_ms(),
+			memseq_dedup_window: default_memseq_dedup_window(),
 		}
 	}
 }
@@ -65,6 +151,28 @@ impl Config {
 			.ok_or(anyhow::anyhow!("No sequencer database path provided"))
 	}
 
+	/// Gets a result for the sequencer private key member.
+	pub fn try_memseq_sequencer_private_key(&self) -> Result<&str, anyhow::Error> {
+		self.memseq_sequencer_private_key
+			.as_deref()
+			.ok_or(anyhow::anyhow!("No sequencer private key provided"))
+	}
+
+	/// Whether the sequencer takes turns proposing with the other members of the sequencer set.
+	pub fn is_rotating(&self) -> bool {
+		!self.memseq_sequencer_set.is_empty()
+	}
+
+	/// Gets the address the memseq service listens on.
+	pub fn memseq_service(&self) -> String {
+		format!("{}:{}", self.memseq_listen_hostname, self.memseq_listen_port)
+	}
+
+	/// Gets the address clients use to connect to the memseq service.
+	pub fn memseq_connection(&self) -> String {
+		format!("http://{}:{}", self.memseq_connection_hostname, self.memseq_connection_port)
+	}
+
 	/// Try to read the location of the config file from the environment and then read the config from the file
 	pub fn try_from_env_toml_file() -> Result<Self, anyhow::Error> {
 		let path = DotMovement::try_from_env()?;
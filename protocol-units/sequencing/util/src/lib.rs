@@ -1,3 +1,5 @@
+pub mod rotation;
+
 use movement_types::{AtomicTransactionBundle, Block, Transaction};
 
 pub trait Sequencer {
@@ -0,0 +1,517 @@
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey, Signature};
+use movement_types::{Block, BlockMetadata, Id, Transaction};
+use std::fmt;
+
+/// Separates proposal signatures from signatures over other messages.
+const PROPOSAL_DOMAIN: &[u8] = b"movement::sequencing::proposal";
+
+/// Gets the message a proposer signs to propose a block in a slot.
+pub fn proposal_message(block_id: &Id, slot: u64) -> Vec<u8> {
+	let mut message = PROPOSAL_DOMAIN.to_vec();
+	message.extend_from_slice(block_id.as_ref());
+	message.extend_from_slice(&slot.to_le_bytes());
+	message
+}
+
+/// Verifies that the proposer signed the block id and slot.
+/// The id of a member of a sequencer set is its ed25519 public key.
+pub fn verify_proposal(
+	proposer: &Id,
+	block_id: &Id,
+	slot: u64,
+	signature: &[u8],
+) -> Result<(), anyhow::Error> {
+	let public_key = PublicKey::from_bytes(proposer.as_ref())
+		.map_err(|e| anyhow::anyhow!("Proposer {} is not a public key: {}", proposer, e))?;
+	let signature = Signature::try_from(signature)
+		.map_err(|e| anyhow::anyhow!("Invalid proposal signature: {}", e))?;
+	public_key
+		.verify_strict(&proposal_message(block_id, slot), &signature)
+		.map_err(|e| anyhow::anyhow!("Proposal signature of {} does not verify: {}", proposer, e))
+}
+
+/// The ed25519 key a member of a sequencer set signs its proposals with.
+#[derive(Clone)]
+pub struct ProposerKey {
+	secret: [u8; 32],
+	public: PublicKey,
+}
+
+impl ProposerKey {
+	pub fn from_bytes(secret: [u8; 32]) -> Result<Self, anyhow::Error> {
+		let secret_key = SecretKey::from_bytes(&secret)
+			.map_err(|e| anyhow::anyhow!("Invalid proposer key: {}", e))?;
+		Ok(Self { secret, public: PublicKey::from(&secret_key) })
+	}
+
+	/// Creates a proposer key from a hex encoded ed25519 private key.
+	pub fn try_from_hex(hex_str: &str) -> Result<Self, anyhow::Error> {
+		let bytes = hex::decode(hex_str.trim_start_matches("0x"))
+			.map_err(|e| anyhow::anyhow!("Invalid hex proposer key: {}", e))?;
+		let secret: [u8; 32] =
+			bytes.try_into().map_err(|_| anyhow::anyhow!("Proposer key must be 32 bytes"))?;
+		Self::from_bytes(secret)
+	}
+
+	/// Gets the id of the proposer, which is its public key.
+	pub fn id(&self) -> Id {
+		Id(self.public.to_bytes())
+	}
+
+	/// Signs the proposal of a block in a slot.
+	pub fn sign(&self, block_id: &Id, slot: u64) -> Result<Vec<u8>, anyhow::Error> {
+		let secret_key = SecretKey::from_bytes(&self.secret)
+			.map_err(|e| anyhow::anyhow!("Invalid proposer key: {}", e))?;
+		let expanded = ExpandedSecretKey::from(&secret_key);
+		let signature = expanded.sign(&proposal_message(block_id, slot), &self.public);
+		Ok(signature.to_bytes().to_vec())
+	}
+
+	/// Signs a block proposed for a slot, setting this key as its proposer.
+	pub fn sign_block(&self, block: &mut Block) -> Result<(), anyhow::Error> {
+		let (_, slot) = block
+			.metadata
+			.proposer_slot()
+			.ok_or(anyhow::anyhow!("Block {} is not proposed for a slot", block.id()))?;
+		let signature = self.sign(&block.id(), slot)?;
+		block.metadata = BlockMetadata::Proposed { proposer: self.id(), slot, signature };
+		Ok(())
+	}
+
+	/// Builds a block proposed by this key in the given slot.
+	pub fn propose(
+		&self,
+		slot: u64,
+		parent: Vec<u8>,
+		transactions: Vec<Transaction>,
+	) -> Result<Block, anyhow::Error> {
+		let metadata = BlockMetadata::Proposed { proposer: self.id(), slot, signature: Vec::new() };
+		let mut block = Block::new(metadata, parent, transactions);
+		self.sign_block(&mut block)?;
+		Ok(block)
+	}
+}
+
+impl fmt::Debug for ProposerKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ProposerKey").field("id", &self.id()).finish_non_exhaustive()
+	}
+}
+
+/// A known set of sequencers which take turns proposing blocks by slot.
+///
+/// Time is divided into slots of `slot_duration_ms`. The leader of a slot is chosen round-robin
+/// from the members. Each slot is further divided into takeover windows of `takeover_timeout_ms`:
+/// the leader may claim the slot in the first window, the next member in the second window, and so on.
+/// Once a member has claimed a slot, it proposes for the remainder of the slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencerSet {
+	members: Vec<Id>,
+	slot_duration_ms: u64,
+	takeover_timeout_ms: u64,
+}
+
+impl SequencerSet {
+	pub fn try_new(
+		members: Vec<Id>,
+		slot_duration_ms: u64,
+		takeover_timeout_ms: u64,
+	) -> Result<Self, anyhow::Error> {
+		if members.is_empty() {
+			anyhow::bail!("Sequencer set must not be empty");
+		}
+		if slot_duration_ms == 0 || takeover_timeout_ms == 0 {
+			anyhow::bail!("Slot duration and takeover timeout must be greater than zero");
+		}
+		for (i, member) in members.iter().enumerate() {
+			if members[..i].contains(member) {
+				anyhow::bail!("Duplicate sequencer in set: {}", member);
+			}
+		}
+		Ok(Self { members, slot_duration_ms, takeover_timeout_ms })
+	}
+
+	/// Creates a sequencer set from hex encoded member ids.
+	pub fn try_from_hex(
+		members: &[String],
+		slot_duration_ms: u64,
+		takeover_timeout_ms: u64,
+	) -> Result<Self, anyhow::Error> {
+		let members = members
+			.iter()
+			.map(|member| Id::try_from_hex(member))
+			.collect::<Result<Vec<_>, _>>()?;
+		Self::try_new(members, slot_duration_ms, takeover_timeout_ms)
+	}
+
+	pub fn members(&self) -> &[Id] {
+		&self.members
+	}
+
+	pub fn slot_duration_ms(&self) -> u64 {
+		self.slot_duration_ms
+	}
+
+	pub fn takeover_timeout_ms(&self) -> u64 {
+		self.takeover_timeout_ms
+	}
+
+	/// Gets the slot containing the given timestamp.
+	pub fn slot_at(&self, timestamp_ms: u64) -> u64 {
+		timestamp_ms / self.slot_duration_ms
+	}
+
+	/// Gets the timestamp at which the given slot starts.
+	pub fn slot_start_ms(&self, slot: u64) -> u64 {
+		slot.saturating_mul(self.slot_duration_ms)
+	}
+
+	/// The number of members which get a takeover window within a single slot.
+	pub fn ranks_per_slot(&self) -> usize {
+		let windows = self.slot_duration_ms.div_ceil(self.takeover_timeout_ms);
+		(windows as usize).min(self.members.len())
+	}
+
+	/// Gets the proposer of the given rank for a slot. Rank 0 is the leader.
+	pub fn proposer(&self, slot: u64, rank: usize) -> &Id {
+		let len = self.members.len() as u64;
+		let index = (slot % len + rank as u64 % len) % len;
+		&self.members[index as usize]
+	}
+
+	/// Gets the leader of the given slot.
+	pub fn leader(&self, slot: u64) -> &Id {
+		self.proposer(slot, 0)
+	}
+
+	/// Gets the rank of a member for the given slot, if it gets a takeover window in that slot.
+	pub fn rank_of(&self, slot: u64, id: &Id) -> Option<usize> {
+		(0..self.ranks_per_slot()).find(|rank| self.proposer(slot, *rank) == id)
+	}
+
+	/// Gets the timestamp at which the takeover window of the given rank opens in a slot.
+	pub fn takeover_start_ms(&self, slot: u64, rank: usize) -> u64 {
+		self.slot_start_ms(slot)
+			.saturating_add((rank as u64).saturating_mul(self.takeover_timeout_ms))
+	}
+
+	/// Gets the rank whose takeover window contains the given timestamp.
+	pub fn rank_at(&self, timestamp_ms: u64) -> usize {
+		let slot = self.slot_at(timestamp_ms);
+		let elapsed = timestamp_ms - self.slot_start_ms(slot);
+		((elapsed / self.takeover_timeout_ms) as usize).min(self.ranks_per_slot() - 1)
+	}
+
+	/// Whether the member is scheduled to propose in the given slot, either as leader or as a follower taking over.
+	pub fn is_scheduled(&self, slot: u64, id: &Id) -> bool {
+		self.rank_of(slot, id).is_some()
+	}
+}
+
+/// Accepts only blocks signed by the scheduled proposer of their slot.
+///
+/// Slots must not go backwards, and all blocks accepted for a slot must come from the member which first claimed it.
+/// A block may not claim a slot which had not started by the time it was published to the DA,
+/// nor claim it before the takeover window of its proposer opened.
+#[derive(Debug, Clone)]
+pub struct ProposerSchedule {
+	set: SequencerSet,
+	last_accepted: Option<(u64, Id)>,
+}
+
+impl ProposerSchedule {
+	pub fn new(set: SequencerSet) -> Self {
+		Self { set, last_accepted: None }
+	}
+
+	pub fn set(&self) -> &SequencerSet {
+		&self.set
+	}
+
+	/// Gets the slot and proposer of the last accepted block.
+	pub fn last_accepted(&self) -> Option<(u64, &Id)> {
+		self.last_accepted.as_ref().map(|(slot, proposer)| (*slot, proposer))
+	}
+
+	/// Rewinds the schedule to the slot and proposer of the last block kept by a revert, so that
+	/// the blocks above it are accepted again when they are read.
+	pub fn revert_to(&mut self, last_accepted: Option<(u64, Id)>) {
+		self.last_accepted = last_accepted;
+	}
+
+	/// Checks whether the block published to the DA at the given time would be accepted,
+	/// returning its slot and proposer.
+	pub fn check(&self, block: &Block, da_timestamp_ms: u64) -> Result<(u64, Id), anyhow::Error> {
+		let (proposer, slot) = block
+			.metadata
+			.proposer_slot()
+			.ok_or(anyhow::anyhow!("Block {} has no proposer", block.id()))?;
+		let signature = block
+			.metadata
+			.proposer_signature()
+			.ok_or(anyhow::anyhow!("Block {} has no proposer signature", block.id()))?;
+		// the signature covers the id, which must commit to the transactions
+		let computed_id = block.compute_id();
+		if computed_id != block.id {
+			anyhow::bail!(
+				"Block {} does not match its contents, which hash to {}",
+				block.id(),
+				computed_id
+			);
+		}
+		verify_proposal(proposer, &block.id(), slot, signature)?;
+
+		let da_slot = self.set.slot_at(da_timestamp_ms);
+		if slot > da_slot {
+			anyhow::bail!(
+				"Block {} claims slot {} but was published in slot {}",
+				block.id(),
+				slot,
+				da_slot
+			);
+		}
+
+		match self.last_accepted() {
+			Some((last_slot, _)) if slot < last_slot => {
+				anyhow::bail!(
+					"Block {} is for slot {} which precedes slot {}",
+					block.id(),
+					slot,
+					last_slot
+				)
+			}
+			Some((last_slot, last_proposer)) if slot == last_slot => {
+				if proposer != last_proposer {
+					anyhow::bail!(
+						"Block {} proposed by {} but slot {} was claimed by {}",
+						block.id(),
+						proposer,
+						slot,
+						last_proposer
+					);
+				}
+			}
+			_ => {
+				let rank = self.set.rank_of(slot, proposer).ok_or(anyhow::anyhow!(
+					"Block {} proposed by {} which is not scheduled for slot {}",
+					block.id(),
+					proposer,
+					slot
+				))?;
+				let window_start_ms = self.set.takeover_start_ms(slot, rank);
+				if da_timestamp_ms < window_start_ms {
+					anyhow::bail!(
+						"Block {} claims slot {} for {} before its takeover window at {}",
+						block.id(),
+						slot,
+						proposer,
+						window_start_ms
+					);
+				}
+			}
+		}
+
+		Ok((slot, proposer.clone()))
+	}
+
+	/// Accepts the block published to the DA at the given time
+	/// if it was proposed by the scheduled proposer.
+	pub fn try_accept(&mut self, block: &Block, da_timestamp_ms: u64) -> Result<(), anyhow::Error> {
+		let accepted = self.check(block, da_timestamp_ms)?;
+		self.last_accepted = Some(accepted);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	fn ids(n: u8) -> Vec<Id> {
+		(0..n).map(|i| Id([i; 32])).collect()
+	}
+
+	fn keys(n: u8) -> Result<Vec<ProposerKey>, anyhow::Error> {
+		(0..n).map(|i| ProposerKey::from_bytes([i + 1; 32])).collect()
+	}
+
+	fn block(proposer: &ProposerKey, slot: u64) -> Result<Block, anyhow::Error> {
+		proposer.propose(slot, vec![0], vec![Transaction::test()])
+	}
+
+	#[test]
+	fn test_leader_rotates_by_slot() -> Result<(), anyhow::Error> {
+		let members = ids(3);
+		let set = SequencerSet::try_new(members.clone(), 1000, 250)?;
+
+		assert_eq!(set.slot_at(999), 0);
+		assert_eq!(set.slot_at(1000), 1);
+		assert_eq!(set.leader(0), &members[0]);
+		assert_eq!(set.leader(1), &members[1]);
+		assert_eq!(set.leader(3), &members[0]);
+
+		// the follower of slot 1 is the leader of slot 2
+		assert_eq!(set.proposer(1, 1), &members[2]);
+		assert_eq!(set.rank_of(1, &members[0]), Some(2));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_takeover_windows() -> Result<(), anyhow::Error> {
+		let members = ids(4);
+		let set = SequencerSet::try_new(members.clone(), 1000, 400)?;
+
+		// only three windows fit into a slot
+		assert_eq!(set.ranks_per_slot(), 3);
+		assert_eq!(set.rank_at(1000), 0);
+		assert_eq!(set.rank_at(1399), 0);
+		assert_eq!(set.rank_at(1400), 1);
+		assert_eq!(set.rank_at(1999), 2);
+		assert!(!set.is_scheduled(0, &members[3]));
+		assert!(set.is_scheduled(1, &members[3]));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_sets() {
+		assert!(SequencerSet::try_new(vec![], 1000, 250).is_err());
+		assert!(SequencerSet::try_new(ids(2), 0, 250).is_err());
+		assert!(SequencerSet::try_new(vec![Id([1; 32]), Id([1; 32])], 1000, 250).is_err());
+	}
+
+	#[test]
+	fn test_schedule_accepts_only_scheduled_proposer() -> Result<(), anyhow::Error> {
+		let keys = keys(4)?;
+		let set = SequencerSet::try_new(keys.iter().map(ProposerKey::id).collect(), 1000, 400)?;
+		let mut schedule = ProposerSchedule::new(set);
+		let now_ms = 10_000;
+
+		// unproposed blocks are rejected
+		assert!(schedule.try_accept(&Block::test(), now_ms).is_err());
+
+		// keys[3] has no window in slot 0
+		assert!(schedule.try_accept(&block(&keys[3], 0)?, now_ms).is_err());
+
+		// the leader claims slot 0 and may propose several blocks
+		schedule.try_accept(&block(&keys[0], 0)?, now_ms)?;
+		schedule.try_accept(&block(&keys[0], 0)?, now_ms)?;
+
+		// a follower cannot propose in a claimed slot
+		assert!(schedule.try_accept(&block(&keys[1], 0)?, now_ms).is_err());
+
+		// a follower takes over slot 1
+		schedule.try_accept(&block(&keys[2], 1)?, now_ms)?;
+		assert_eq!(schedule.last_accepted(), Some((1, &keys[2].id())));
+
+		// the leader of slot 1 is now too late
+		assert!(schedule.try_accept(&block(&keys[1], 1)?, now_ms).is_err());
+
+		// slots cannot go backwards
+		assert!(schedule.try_accept(&block(&keys[0], 0)?, now_ms).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_schedule_reverts() -> Result<(), anyhow::Error> {
+		let keys = keys(2)?;
+		let set = SequencerSet::try_new(keys.iter().map(ProposerKey::id).collect(), 1000, 500)?;
+		let mut schedule = ProposerSchedule::new(set);
+		let now_ms = 10_000;
+
+		let kept = block(&keys[0], 0)?;
+		let reverted = [block(&keys[1], 1)?, block(&keys[0], 2)?];
+		schedule.try_accept(&kept, now_ms)?;
+		let last_kept = schedule.last_accepted().map(|(slot, proposer)| (slot, proposer.clone()));
+		for block in &reverted {
+			schedule.try_accept(block, now_ms)?;
+		}
+
+		// the reverted blocks precede the last accepted slot until the schedule is rewound
+		assert!(schedule.try_accept(&reverted[0], now_ms).is_err());
+		schedule.revert_to(last_kept);
+		for block in &reverted {
+			schedule.try_accept(block, now_ms)?;
+		}
+		assert_eq!(schedule.last_accepted(), Some((2, &keys[0].id())));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_schedule_rejects_forged_and_future_proposals() -> Result<(), anyhow::Error> {
+		let keys = keys(2)?;
+		let set = SequencerSet::try_new(keys.iter().map(ProposerKey::id).collect(), 1000, 500)?;
+		let mut schedule = ProposerSchedule::new(set);
+
+		// a block naming the leader but signed by another key is rejected
+		let mut forged = block(&keys[1], 0)?;
+		forged.metadata = BlockMetadata::Proposed {
+			proposer: keys[0].id(),
+			slot: 0,
+			signature: keys[1].sign(&forged.id(), 0)?,
+		};
+		assert!(schedule.try_accept(&forged, 0).is_err());
+
+		// the signature covers the slot
+		let mut moved = block(&keys[0], 0)?;
+		let signature = keys[0].sign(&moved.id(), 0)?;
+		moved.metadata = BlockMetadata::Proposed { proposer: keys[0].id(), slot: 2, signature };
+		assert!(schedule.try_accept(&moved, 2000).is_err());
+
+		// a block may not claim a slot which had not started when it was published
+		assert!(schedule.try_accept(&block(&keys[0], 2)?, 1999).is_err());
+		schedule.try_accept(&block(&keys[0], 2)?, 2000)?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_schedule_rejects_tampered_transactions() -> Result<(), anyhow::Error> {
+		let keys = keys(2)?;
+		let set = SequencerSet::try_new(keys.iter().map(ProposerKey::id).collect(), 1000, 500)?;
+		let mut schedule = ProposerSchedule::new(set);
+
+		// the signed id and slot are copied onto a block with other transactions
+		let mut tampered = block(&keys[0], 0)?;
+		tampered.transactions = vec![Transaction::new(vec![1, 2, 3], 0)];
+		assert!(schedule.try_accept(&tampered, 0).is_err());
+
+		schedule.try_accept(&block(&keys[0], 0)?, 0)?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_schedule_enforces_takeover_window() -> Result<(), anyhow::Error> {
+		let keys = keys(3)?;
+		let set = SequencerSet::try_new(keys.iter().map(ProposerKey::id).collect(), 1000, 300)?;
+		let mut schedule = ProposerSchedule::new(set);
+
+		// the follower of slot 1 may not claim it before its window opens at 1300
+		assert!(schedule.try_accept(&block(&keys[2], 1)?, 1000).is_err());
+		assert!(schedule.try_accept(&block(&keys[2], 1)?, 1299).is_err());
+
+		// the leader may claim it right away
+		schedule.try_accept(&block(&keys[1], 1)?, 1000)?;
+
+		// the last rank of slot 2 takes over once the earlier windows have passed
+		assert!(schedule.try_accept(&block(&keys[1], 2)?, 2599).is_err());
+		schedule.try_accept(&block(&keys[1], 2)?, 2600)?;
+		assert_eq!(schedule.last_accepted(), Some((2, &keys[1].id())));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_proposer_key_from_hex() -> Result<(), anyhow::Error> {
+		let key = ProposerKey::from_bytes([7; 32])?;
+		let from_hex = ProposerKey::try_from_hex(&format!("0x{}", hex::encode([7; 32])))?;
+		assert_eq!(key.id(), from_hex.id());
+		assert!(ProposerKey::try_from_hex("0x07").is_err());
+		Ok(())
+	}
+}
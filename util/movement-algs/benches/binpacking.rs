@@ -0,0 +1,116 @@
+//! Compares the binpacking heuristics on blob sizes like the ones the light node submits.
+//!
+//! Before timing the heuristics, prints a quality report of the bins each heuristic needs for
+//! batches of blocks drawn from each size distribution, against the lower bound of the total size
+//! over the capacity.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use movement_algs::grouping_heuristic::binpacking::{
+	BestFitBinpacking, ExactBinpacking, FirstFitBinpacking, FirstFitDecreasingBinpacking,
+	NextFitBinpacking,
+};
+use movement_algs::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The capacity the light node packs blocks into blobs with.
+const CAPACITY: usize = 1_700_000;
+
+/// The largest batch the exact heuristic solves rather than falling back.
+const EXACT_MAX_ELEMENTS: usize = 16;
+
+type Strategy = fn() -> Box<dyn GroupingHeuristic<usize>>;
+
+fn strategies() -> Vec<(&'static str, Strategy)> {
+	vec![
+		("next-fit", || NextFitBinpacking::boxed(CAPACITY)),
+		("first-fit", || FirstFitBinpacking::boxed(CAPACITY)),
+		("first-fit-decreasing", || FirstFitDecreasingBinpacking::boxed(CAPACITY)),
+		("best-fit", || BestFitBinpacking::boxed(CAPACITY)),
+		("exact", || ExactBinpacking::boxed(CAPACITY, EXACT_MAX_ELEMENTS)),
+	]
+}
+
+type Distribution = fn(&mut StdRng) -> usize;
+
+/// Block sizes in bytes, from a quiet chain of small blocks to blocks of about half a blob.
+/// Whether the strategy packs batches of the size with its own algorithm. The exact heuristic
+/// falls back to First Fit Decreasing above its maximum, so it is not reported or timed there.
+fn runs_own_algorithm(strategy_name: &str, batch_size: usize) -> bool {
+	strategy_name != "exact" || batch_size <= EXACT_MAX_ELEMENTS
+}
+
+fn distributions() -> Vec<(&'static str, Distribution)> {
+	vec![
+		("small", |rng| rng.gen_range(2_000, 200_000)),
+		("mixed", |rng| {
+			if rng.gen_bool(0.8) {
+				rng.gen_range(2_000, 200_000)
+			} else {
+				rng.gen_range(400_000, 1_600_000)
+			}
+		}),
+		("large", |rng| rng.gen_range(300_000, 1_200_000)),
+		("half", |rng| rng.gen_range(CAPACITY * 2 / 5, CAPACITY * 3 / 5)),
+	]
+}
+
+fn batch(distribution: Distribution, rng: &mut StdRng, size: usize) -> Vec<usize> {
+	(0..size).map(|_| distribution(rng)).collect()
+}
+
+fn bins(strategy: Strategy, batch: &[usize]) -> usize {
+	strategy()
+		.distribute(vec![GroupingOutcome::new_apply(batch.to_vec())])
+		.expect("binpacking failed")
+		.len()
+}
+
+fn report() {
+	for batch_size in [12, 200] {
+		println!("bins for 50 batches of {} blocks", batch_size);
+		for (name, distribution) in distributions() {
+			let mut rng = StdRng::seed_from_u64(0);
+			let batches: Vec<_> =
+				(0..50).map(|_| batch(distribution, &mut rng, batch_size)).collect();
+
+			let lower_bound: usize =
+				batches.iter().map(|batch| batch.iter().sum::<usize>().div_ceil(CAPACITY)).sum();
+			let counts: Vec<_> = strategies()
+				.into_iter()
+				.filter(|(strategy_name, _)| runs_own_algorithm(strategy_name, batch_size))
+				.map(|(strategy_name, strategy)| {
+					let count: usize = batches.iter().map(|batch| bins(strategy, batch)).sum();
+					format!("{} {}", strategy_name, count)
+				})
+				.collect();
+			println!("  {}: lower-bound {}, {}", name, lower_bound, counts.join(", "));
+		}
+	}
+}
+
+fn bench_binpacking(c: &mut Criterion) {
+	report();
+
+	for (name, distribution) in distributions() {
+		let mut group = c.benchmark_group(format!("binpack_{}", name));
+		for batch_size in [12, 200] {
+			let batch = batch(distribution, &mut StdRng::seed_from_u64(0), batch_size);
+			for (strategy_name, strategy) in strategies() {
+				if !runs_own_algorithm(strategy_name, batch_size) {
+					continue;
+				}
+				group.bench_with_input(
+					BenchmarkId::new(strategy_name, batch_size),
+					&batch,
+					|b, batch| {
+						b.iter(|| bins(strategy, batch));
+					},
+				);
+			}
+		}
+		group.finish();
+	}
+}
+
+criterion_group!(benches, bench_binpacking);
+criterion_main!(benches);
@@ -0,0 +1,428 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Gets the size of an item in bytes, for the byte limit of a batch.
+pub type Weigher<T> = Box<dyn Fn(&T) -> usize + Send + Sync>;
+
+/// Limits on a batch.
+///
+/// A batch is closed once it holds `max_count` items, once the next item would take it over
+/// `max_bytes` bytes, or once `max_wait` has passed since it was started, whichever comes first.
+/// An item heavier than `max_bytes` on its own is batched alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLimits {
+	pub max_count: usize,
+	pub max_bytes: usize,
+	pub max_wait: Duration,
+}
+
+impl BatchLimits {
+	/// Creates limits which only close a batch once `max_wait` has passed.
+	pub fn new(max_wait: Duration) -> Self {
+		Self { max_count: usize::MAX, max_bytes: usize::MAX, max_wait }
+	}
+
+	pub fn with_max_count(mut self, max_count: usize) -> Self {
+		self.max_count = max_count;
+		self
+	}
+
+	pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+		self.max_bytes = max_bytes;
+		self
+	}
+}
+
+/// A batch being filled up to its limits.
+struct Batch<T> {
+	items: Vec<T>,
+	bytes: usize,
+	limits: BatchLimits,
+	full: bool,
+}
+
+impl<T> Batch<T> {
+	fn new(limits: BatchLimits) -> Self {
+		Self { items: Vec::new(), bytes: 0, limits, full: limits.max_count == 0 }
+	}
+
+	/// Checks whether an item of the weight fits, closing the batch if it does not.
+	fn fits(&mut self, weight: usize) -> bool {
+		if !self.full && !self.items.is_empty() && self.bytes + weight > self.limits.max_bytes {
+			self.full = true;
+		}
+		!self.full
+	}
+
+	/// Adds an item which fits.
+	fn push(&mut self, item: T, weight: usize) {
+		self.items.push(item);
+		self.bytes += weight;
+		self.full =
+			self.items.len() >= self.limits.max_count || self.bytes >= self.limits.max_bytes;
+	}
+
+	fn remaining_count(&self) -> usize {
+		self.limits.max_count.saturating_sub(self.items.len())
+	}
+}
+
+/// The items of a pulled batch, and the items which were pulled but did not fit in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PulledBatch<T> {
+	pub items: Vec<T>,
+	pub overflow: Vec<T>,
+}
+
+/// Pulls a batch from a source which can only be polled, such as a mempool.
+///
+/// The source is asked for at most the remaining count of the batch, and is polled again after
+/// yielding until the batch is closed. The deadline is only checked between polls, so the source
+/// should return promptly, with no items if it has none. Items pulled after the byte limit was
+/// reached are returned as overflow, for the caller to return to the source.
+pub async fn pull_batch<T, W, F, Fut>(
+	limits: &BatchLimits,
+	weigh: W,
+	mut pull: F,
+) -> Result<PulledBatch<T>, anyhow::Error>
+where
+	W: Fn(&T) -> usize,
+	F: FnMut(usize) -> Fut,
+	Fut: Future<Output = Result<Vec<T>, anyhow::Error>>,
+{
+	let start = Instant::now();
+	let mut batch = Batch::new(*limits);
+	let mut overflow = Vec::new();
+	while !batch.full {
+		for item in pull(batch.remaining_count()).await? {
+			let weight = weigh(&item);
+			if batch.fits(weight) {
+				batch.push(item, weight);
+			} else {
+				overflow.push(item);
+			}
+		}
+
+		if start.elapsed() >= limits.max_wait {
+			break;
+		}
+
+		// yield to other tasks and wait for more items
+		tokio::task::yield_now().await;
+	}
+
+	Ok(PulledBatch { items: batch.items, overflow })
+}
+
+/// The items queued under a key.
+struct Lane<T> {
+	items: VecDeque<T>,
+	/// The number of items the lane may still add to batches before the next lane's turn.
+	credit: usize,
+}
+
+struct QueueState<K, T> {
+	lanes: BTreeMap<K, Lane<T>>,
+	/// The keys of the lanes with queued items, in the order of their turns.
+	turns: VecDeque<K>,
+	weights: BTreeMap<K, usize>,
+	len: usize,
+	closed: bool,
+}
+
+impl<K, T> QueueState<K, T>
+where
+	K: Ord + Clone,
+{
+	fn push(&mut self, key: K, item: T) {
+		let lane = self
+			.lanes
+			.entry(key.clone())
+			.or_insert_with(|| Lane { items: VecDeque::new(), credit: 0 });
+		if lane.items.is_empty() {
+			self.turns.push_back(key);
+		}
+		lane.items.push_back(item);
+		self.len += 1;
+	}
+
+	/// Moves items into the batch in weighted round robin over the keys, until the batch is full or
+	/// the queue is empty.
+	fn take(&mut self, batch: &mut Batch<T>, weigh: &Weigher<T>) {
+		while let Some(key) = self.turns.front() {
+			let weight = self.weights.get(key).copied().unwrap_or(1).max(1);
+			let Some(lane) = self.lanes.get_mut(key) else {
+				self.turns.pop_front();
+				continue;
+			};
+			let Some(item) = lane.items.front() else {
+				self.lanes.remove(key);
+				self.turns.pop_front();
+				continue;
+			};
+
+			let item_weight = weigh(item);
+			if !batch.fits(item_weight) {
+				return;
+			}
+			if lane.credit == 0 {
+				lane.credit = weight;
+			}
+			if let Some(item) = lane.items.pop_front() {
+				batch.push(item, item_weight);
+				lane.credit -= 1;
+				self.len -= 1;
+			}
+
+			if lane.items.is_empty() {
+				self.lanes.remove(key);
+				self.turns.pop_front();
+			} else if lane.credit == 0 {
+				self.turns.rotate_left(1);
+			}
+		}
+	}
+}
+
+/// A bounded queue which items are pushed into under a key, and taken out of in batches.
+///
+/// Batches take turns between the keys in proportion to their weights, which default to 1, so
+/// that no key can starve the others. Within a key, items keep the order they were pushed in.
+/// Once the queue holds `capacity` items, pushes wait for batches to be taken.
+pub struct FairQueue<K, T> {
+	capacity: usize,
+	weigh: Weigher<T>,
+	state: Mutex<QueueState<K, T>>,
+	pushed: Notify,
+	taken: Notify,
+}
+
+impl<K, T> FairQueue<K, T>
+where
+	K: Ord + Clone,
+{
+	/// Creates a queue of at most `capacity` items, which weighs every item as 0 bytes.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity,
+			weigh: Box::new(|_| 0),
+			state: Mutex::new(QueueState {
+				lanes: BTreeMap::new(),
+				turns: VecDeque::new(),
+				weights: BTreeMap::new(),
+				len: 0,
+				closed: false,
+			}),
+			pushed: Notify::new(),
+			taken: Notify::new(),
+		}
+	}
+
+	/// Sets how the byte limit of batches weighs the items.
+	pub fn with_weigher(mut self, weigh: impl Fn(&T) -> usize + Send + Sync + 'static) -> Self {
+		self.weigh = Box::new(weigh);
+		self
+	}
+
+	fn state(&self) -> Result<std::sync::MutexGuard<'_, QueueState<K, T>>, anyhow::Error> {
+		self.state.lock().map_err(|_| anyhow::anyhow!("Fair queue poisoned"))
+	}
+
+	/// Sets the number of items the key adds to batches in each of its turns.
+	pub fn set_weight(&self, key: K, weight: usize) -> Result<(), anyhow::Error> {
+		self.state()?.weights.insert(key, weight);
+		Ok(())
+	}
+
+	/// Pushes an item under the key, waiting while the queue is full.
+	pub async fn push(&self, key: K, item: T) -> Result<(), anyhow::Error> {
+		loop {
+			let taken = self.taken.notified();
+			{
+				let mut state = self.state()?;
+				if state.closed {
+					anyhow::bail!("Fair queue closed");
+				}
+				if state.len < self.capacity {
+					state.push(key, item);
+					drop(state);
+					self.pushed.notify_waiters();
+					return Ok(());
+				}
+			}
+			taken.await;
+		}
+	}
+
+	/// Takes the next batch, waiting until it is full, its wait has passed or the queue is closed
+	/// and empty. The batch is empty if no items were pushed within its wait.
+	pub async fn next_batch(&self, limits: &BatchLimits) -> Result<Vec<T>, anyhow::Error> {
+		let deadline = Instant::now() + limits.max_wait;
+		let mut batch = Batch::new(*limits);
+		loop {
+			let pushed = self.pushed.notified();
+			let drained = {
+				let mut state = self.state()?;
+				let len = state.len;
+				state.take(&mut batch, &self.weigh);
+				if state.len < len {
+					self.taken.notify_waiters();
+				}
+				state.closed && state.len == 0
+			};
+
+			if batch.full || drained {
+				return Ok(batch.items);
+			}
+			if tokio::time::timeout_at(deadline, pushed).await.is_err() {
+				return Ok(batch.items);
+			}
+		}
+	}
+
+	/// Closes the queue, failing later pushes. Queued items can still be taken.
+	pub fn close(&self) -> Result<(), anyhow::Error> {
+		self.state()?.closed = true;
+		self.pushed.notify_waiters();
+		self.taken.notify_waiters();
+		Ok(())
+	}
+
+	/// Gets the number of queued items.
+	pub fn len(&self) -> usize {
+		self.state().map(|state| state.len).unwrap_or_default()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use std::sync::Arc;
+
+	const WAIT: Duration = Duration::from_millis(50);
+
+	#[tokio::test]
+	async fn test_batch_limits() -> Result<(), anyhow::Error> {
+		let queue = FairQueue::new(16).with_weigher(|item: &usize| *item);
+		for item in [1, 2, 3, 4, 20, 5] {
+			queue.push((), item).await?;
+		}
+
+		let limits = BatchLimits::new(WAIT).with_max_count(3).with_max_bytes(8);
+		assert_eq!(queue.next_batch(&limits).await?, vec![1, 2, 3]);
+		assert_eq!(queue.next_batch(&limits).await?, vec![4]);
+		// an item heavier than the byte limit is batched alone
+		assert_eq!(queue.next_batch(&limits).await?, vec![20]);
+
+		// the last item waits out the deadline
+		let start = Instant::now();
+		assert_eq!(queue.next_batch(&limits).await?, vec![5]);
+		assert!(start.elapsed() >= WAIT);
+		assert_eq!(queue.next_batch(&limits).await?, Vec::<usize>::new());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_keys_take_turns() -> Result<(), anyhow::Error> {
+		let queue = FairQueue::new(64);
+		queue.set_weight("heavy", 2)?;
+		for item in 0..6 {
+			queue.push("flood", ("flood", item)).await?;
+		}
+		for item in 0..3 {
+			queue.push("heavy", ("heavy", item)).await?;
+			queue.push("light", ("light", item)).await?;
+		}
+
+		let batch = queue.next_batch(&BatchLimits::new(WAIT).with_max_count(8)).await?;
+		let should_be = vec![
+			("flood", 0),
+			("heavy", 0),
+			("heavy", 1),
+			("light", 0),
+			("flood", 1),
+			("heavy", 2),
+			("light", 1),
+			("flood", 2),
+		];
+		assert_eq!(batch, should_be);
+
+		// the turns carry over to the next batch
+		let batch = queue.next_batch(&BatchLimits::new(WAIT)).await?;
+		assert_eq!(batch, vec![("light", 2), ("flood", 3), ("flood", 4), ("flood", 5)]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_back_pressure() -> Result<(), anyhow::Error> {
+		let queue = Arc::new(FairQueue::new(2));
+		let producer = {
+			let queue = queue.clone();
+			tokio::spawn(async move {
+				for item in 0..5 {
+					queue.push((), item).await?;
+				}
+				queue.close()
+			})
+		};
+
+		tokio::time::sleep(WAIT).await;
+		assert_eq!(queue.len(), 2);
+
+		let mut items = Vec::new();
+		loop {
+			let batch = queue.next_batch(&BatchLimits::new(WAIT)).await?;
+			if batch.is_empty() {
+				break;
+			}
+			items.extend(batch);
+		}
+		producer.await??;
+		assert_eq!(items, vec![0, 1, 2, 3, 4]);
+		assert!(queue.push((), 5).await.is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_pull_batch() -> Result<(), anyhow::Error> {
+		let source = Mutex::new((0..10).collect::<VecDeque<usize>>());
+		let pull = |remaining: usize| {
+			let source = &source;
+			async move {
+				let mut source = source.lock().map_err(|_| anyhow::anyhow!("poisoned"))?;
+				// the source hands out at most 2 items at a time
+				let count = remaining.min(2).min(source.len());
+				Ok(source.drain(..count).collect::<Vec<_>>())
+			}
+		};
+
+		let limits = BatchLimits::new(WAIT).with_max_count(5);
+		let batch = pull_batch(&limits, |_| 0, pull).await?;
+		assert_eq!(batch, PulledBatch { items: vec![0, 1, 2, 3, 4], overflow: vec![] });
+
+		// items pulled beyond the byte limit overflow
+		let limits = BatchLimits::new(WAIT).with_max_bytes(10);
+		let batch = pull_batch(&limits, |item| *item, pull).await?;
+		assert_eq!(batch, PulledBatch { items: vec![5], overflow: vec![6] });
+
+		// the batch is closed at the deadline when the source runs dry
+		let start = Instant::now();
+		let batch = pull_batch(&BatchLimits::new(WAIT), |_| 0, pull).await?;
+		assert_eq!(batch.items, vec![7, 8, 9]);
+		assert!(start.elapsed() >= WAIT);
+
+		Ok(())
+	}
+}
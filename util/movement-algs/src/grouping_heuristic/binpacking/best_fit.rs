@@ -0,0 +1,98 @@
+use super::BinpackingWeighted;
+use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+
+/// Implements the 1.7 OPT Best Fit binpacking heuristic,
+/// where OPT is the minimum number of bins required to pack all elements.
+/// This means that, if the optimal packing requires 10 bins,
+/// the heuristic will require at most 17 bins.
+///
+/// Best Fit places each element in the fullest bin it fits in, opening a new bin when it fits in none.
+/// The tight bound of 1.7 OPT for Best Fit was proven by Dosa and Sgall, 2014: "Optimal Analysis of Best Fit Bin Packing"
+///
+/// Best Fit leaves fewer gaps than First Fit in practice, but does not preserve the original order of the elements.
+///
+/// This implementation does not allow for elements heavier than the capacity.
+pub struct BestFitBinpacking {
+	pub capacity: usize,
+}
+
+impl BestFitBinpacking {
+	pub fn new(capacity: usize) -> Self {
+		Self { capacity }
+	}
+
+	pub fn boxed(capacity: usize) -> Box<Self> {
+		Box::new(Self::new(capacity))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for BestFitBinpacking
+where
+	T: BinpackingWeighted,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let elements = distribution.into_iter().flat_map(|outcome| outcome.into_inner());
+
+		let mut result: Vec<GroupingOutcome<T>> = Vec::new();
+		let mut weights: Vec<usize> = Vec::new();
+		for element in elements {
+			let weight = element.weight();
+
+			// if the element is heavier than the capacity, return an error
+			if weight > self.capacity {
+				return Err(anyhow::anyhow!("Element is heavier than the capacity"));
+			}
+
+			// find the fullest bin the element fits in
+			let best = weights
+				.iter()
+				.enumerate()
+				.filter(|(_, bin_weight)| *bin_weight + weight <= self.capacity)
+				.max_by_key(|(index, bin_weight)| (**bin_weight, std::cmp::Reverse(*index)))
+				.map(|(index, _)| index);
+
+			match best {
+				Some(index) => {
+					weights[index] += weight;
+					result[index].0.push(element);
+				}
+				None => {
+					weights.push(weight);
+					result.push(GroupingOutcome::new(vec![element]));
+				}
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_best_fit_binpacking() -> Result<(), anyhow::Error> {
+		let mut heuristic = BestFitBinpacking::new(10);
+		let distribution =
+			vec![GroupingOutcome::new_apply(vec![5, 7]), GroupingOutcome::new_apply(vec![3, 5, 2])];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		// the 3 goes into the fuller bin with the 7, rather than the first bin it fits in
+		let should_be = vec![
+			GroupingOutcome::new_apply(vec![5, 5]),
+			GroupingOutcome::new_apply(vec![7, 3]),
+			GroupingOutcome::new_apply(vec![2]),
+		];
+		assert_eq!(distribution, should_be);
+
+		assert!(heuristic.distribute(vec![GroupingOutcome::new_apply(vec![11])]).is_err());
+
+		Ok(())
+	}
+}
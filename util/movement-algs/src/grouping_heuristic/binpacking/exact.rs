@@ -0,0 +1,217 @@
+use super::{BinpackingWeighted, FirstFitDecreasingBinpacking};
+use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+
+/// Packs elements into the minimum number of bins with a branch and bound search.
+///
+/// The search starts from the First Fit Decreasing packing and places the heaviest elements first,
+/// pruning any branch which cannot beat the best packing found so far. Its running time is
+/// exponential in the number of elements, so distributions of more than `max_elements` elements
+/// are packed with First Fit Decreasing instead.
+///
+/// Within each bin, the elements keep their original order, but the bins are not ordered.
+///
+/// This implementation does not allow for elements heavier than the capacity.
+pub struct ExactBinpacking {
+	pub capacity: usize,
+	pub max_elements: usize,
+}
+
+impl ExactBinpacking {
+	pub fn new(capacity: usize, max_elements: usize) -> Self {
+		Self { capacity, max_elements }
+	}
+
+	pub fn boxed(capacity: usize, max_elements: usize) -> Box<Self> {
+		Box::new(Self::new(capacity, max_elements))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for ExactBinpacking
+where
+	T: BinpackingWeighted,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let elements: Vec<_> =
+			distribution.into_iter().flat_map(|outcome| outcome.into_inner()).collect();
+		if elements.len() > self.max_elements {
+			return FirstFitDecreasingBinpacking::new(self.capacity)
+				.distribute(vec![GroupingOutcome::new(elements)]);
+		}
+
+		let weights: Vec<usize> = elements.iter().map(|element| element.weight()).collect();
+
+		// if an element is heavier than the capacity, return an error
+		if weights.iter().any(|weight| *weight > self.capacity) {
+			return Err(anyhow::anyhow!("Element is heavier than the capacity"));
+		}
+
+		let bins = Search::new(&weights, self.capacity).run();
+		let mut result: Vec<GroupingOutcome<T>> = (0..bins.iter().max().map_or(0, |max| max + 1))
+			.map(|_| GroupingOutcome::new(vec![]))
+			.collect();
+		for (element, bin) in elements.into_iter().zip(bins) {
+			result[bin].0.push(element);
+		}
+
+		Ok(result)
+	}
+}
+
+/// The state of the branch and bound search over the elements, heaviest first.
+struct Search {
+	capacity: usize,
+	/// The weights and original indices of the elements, heaviest first.
+	elements: Vec<(usize, usize)>,
+	/// No packing can use fewer bins than this.
+	lower_bound: usize,
+	/// The bin of each element, by original index, in the best packing found so far.
+	best: Vec<usize>,
+	best_bins: usize,
+	/// The weight of each bin in the packing being searched.
+	loads: Vec<usize>,
+	/// The bin of each element, by original index, in the packing being searched.
+	current: Vec<usize>,
+}
+
+impl Search {
+	fn new(weights: &[usize], capacity: usize) -> Self {
+		let mut elements: Vec<_> = weights.iter().copied().zip(0..).collect();
+		elements.sort_by_key(|(weight, _)| std::cmp::Reverse(*weight));
+
+		let total: usize = weights.iter().sum();
+		let lower_bound = match capacity {
+			0 => 1,
+			capacity => total.div_ceil(capacity).max(1),
+		}
+		.min(weights.len());
+
+		// start from the first fit decreasing packing
+		let mut loads: Vec<usize> = Vec::new();
+		let mut best = vec![0; weights.len()];
+		for (weight, index) in &elements {
+			let bin = match loads.iter().position(|load| load + weight <= capacity) {
+				Some(bin) => bin,
+				None => {
+					loads.push(0);
+					loads.len() - 1
+				}
+			};
+			loads[bin] += weight;
+			best[*index] = bin;
+		}
+
+		Self {
+			capacity,
+			lower_bound,
+			best_bins: loads.len(),
+			current: best.clone(),
+			best,
+			elements,
+			loads: Vec::new(),
+		}
+	}
+
+	/// Gets the bin of each element, by original index, in an optimal packing.
+	fn run(mut self) -> Vec<usize> {
+		let remaining = self.elements.iter().map(|(weight, _)| weight).sum();
+		self.search(0, remaining);
+		self.best
+	}
+
+	fn search(&mut self, position: usize, remaining: usize) {
+		if self.best_bins <= self.lower_bound {
+			return;
+		}
+		let Some(&(weight, index)) = self.elements.get(position) else {
+			self.best_bins = self.loads.len();
+			self.best.clone_from(&self.current);
+			return;
+		};
+
+		// the remaining weight which does not fit in the open bins needs new bins
+		let free: usize = self.loads.iter().map(|load| self.capacity - load).sum();
+		let needed = match self.capacity {
+			0 => 0,
+			capacity => remaining.saturating_sub(free).div_ceil(capacity),
+		};
+		if self.loads.len() + needed >= self.best_bins {
+			return;
+		}
+
+		// bins with the same load lead to the same packings, so only one of them is tried
+		let mut tried: Vec<usize> = Vec::new();
+		for bin in 0..self.loads.len() {
+			let load = self.loads[bin];
+			if load + weight > self.capacity || tried.contains(&load) {
+				continue;
+			}
+			tried.push(load);
+
+			self.loads[bin] += weight;
+			self.current[index] = bin;
+			self.search(position + 1, remaining - weight);
+			self.loads[bin] -= weight;
+		}
+
+		if self.loads.len() + 1 < self.best_bins {
+			self.loads.push(weight);
+			self.current[index] = self.loads.len() - 1;
+			self.search(position + 1, remaining - weight);
+			self.loads.pop();
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::binpacking::BestFitBinpacking;
+
+	fn bins(distribution: &[GroupingOutcome<usize>]) -> Vec<Vec<usize>> {
+		let mut bins: Vec<Vec<usize>> =
+			distribution.iter().map(|outcome| outcome.clone().into_original()).collect();
+		for bin in &mut bins {
+			bin.sort();
+		}
+		bins.sort();
+		bins
+	}
+
+	#[test]
+	fn test_exact_binpacking() -> Result<(), anyhow::Error> {
+		// first fit decreasing and best fit both need 4 bins, but 3 bins are enough
+		let elements = vec![3, 6, 6, 2, 4, 2, 5, 2];
+		let mut heuristic = ExactBinpacking::new(10, 16);
+
+		let distribution =
+			heuristic.distribute(vec![GroupingOutcome::new_apply(elements.clone())])?;
+		assert_eq!(bins(&distribution), vec![vec![2, 2, 6], vec![2, 3, 5], vec![4, 6]]);
+
+		let heuristics: [Box<dyn GroupingHeuristic<usize>>; 2] =
+			[FirstFitDecreasingBinpacking::boxed(10), BestFitBinpacking::boxed(10)];
+		for mut heuristic in heuristics {
+			let distribution =
+				heuristic.distribute(vec![GroupingOutcome::new_apply(elements.clone())])?;
+			assert_eq!(distribution.len(), 4);
+		}
+
+		assert!(heuristic.distribute(vec![GroupingOutcome::new_apply(vec![11])]).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_exact_binpacking_falls_back() -> Result<(), anyhow::Error> {
+		let elements = vec![3, 6, 6, 2, 4, 2, 5, 2];
+		let mut heuristic = ExactBinpacking::new(10, 7);
+
+		let distribution = heuristic.distribute(vec![GroupingOutcome::new_apply(elements)])?;
+		assert_eq!(distribution.len(), 4);
+
+		Ok(())
+	}
+}
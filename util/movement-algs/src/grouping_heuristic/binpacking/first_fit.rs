@@ -13,7 +13,7 @@ use super::BinpackingWeighted;
 /// 
 /// First Fit is particularly suitable for situations where the original order should not be changed.
 /// 
-/// This implementation does not allow for elements heavier than the capacity to overflow the bins. So, if you are looking to apply this in a stack with, for example, a splitting heuristic, you should consider wrapping with the CatchError heuristic.
+/// This implementation does not allow for elements heavier than the capacity to overflow the bins. So, if you are looking to apply this in a stack with, for example, a splitting heuristic, you should consider wrapping with the [crate::grouping_heuristic::catch_error::CatchError] heuristic.
 pub struct FirstFitBinpacking {
     pub capacity: usize
 }
@@ -1,7 +1,13 @@
+pub mod best_fit;
+pub use best_fit::BestFitBinpacking;
+pub mod exact;
+pub use exact::ExactBinpacking;
 pub mod first_fit_decreasing;
 pub use first_fit_decreasing::*;
 pub mod first_fit;
 pub use first_fit::*;
+pub mod next_fit;
+pub use next_fit::NextFitBinpacking;
 
 use crate::grouping_heuristic::{ElementalFailure, ElementalOutcome};
 
@@ -0,0 +1,88 @@
+use super::BinpackingWeighted;
+use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+
+/// Implements the 2 OPT Next Fit binpacking heuristic,
+/// where OPT is the minimum number of bins required to pack all elements.
+/// This means that, if the optimal packing requires 10 bins,
+/// the heuristic will require at most 20 bins.
+///
+/// Next Fit only ever places an element in the last bin, opening a new bin when it does not fit.
+/// It runs in linear time and preserves the original order of the elements across the bins.
+///
+/// This implementation does not allow for elements heavier than the capacity.
+pub struct NextFitBinpacking {
+	pub capacity: usize,
+}
+
+impl NextFitBinpacking {
+	pub fn new(capacity: usize) -> Self {
+		Self { capacity }
+	}
+
+	pub fn boxed(capacity: usize) -> Box<Self> {
+		Box::new(Self::new(capacity))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for NextFitBinpacking
+where
+	T: BinpackingWeighted,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let elements = distribution.into_iter().flat_map(|outcome| outcome.into_inner());
+
+		let mut result: Vec<GroupingOutcome<T>> = Vec::new();
+		let mut current_weight = 0;
+		for element in elements {
+			let weight = element.weight();
+
+			// if the element is heavier than the capacity, return an error
+			if weight > self.capacity {
+				return Err(anyhow::anyhow!("Element is heavier than the capacity"));
+			}
+
+			match result.last_mut() {
+				Some(last) if current_weight + weight <= self.capacity => {
+					current_weight += weight;
+					last.0.push(element);
+				}
+				_ => {
+					current_weight = weight;
+					result.push(GroupingOutcome::new(vec![element]));
+				}
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_next_fit_binpacking() -> Result<(), anyhow::Error> {
+		let mut heuristic = NextFitBinpacking::new(10);
+		let distribution =
+			vec![GroupingOutcome::new_apply(vec![6, 5]), GroupingOutcome::new_apply(vec![4, 1, 7])];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		// the 4 does not go back into the bin with the 6
+		let should_be = vec![
+			GroupingOutcome::new_apply(vec![6]),
+			GroupingOutcome::new_apply(vec![5, 4, 1]),
+			GroupingOutcome::new_apply(vec![7]),
+		];
+		assert_eq!(distribution, should_be);
+
+		assert!(heuristic.distribute(vec![GroupingOutcome::new_apply(vec![11])]).is_err());
+
+		Ok(())
+	}
+}
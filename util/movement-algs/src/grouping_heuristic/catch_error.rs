@@ -0,0 +1,172 @@
+use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+use std::time::Duration;
+
+/// Catches the errors of a heuristic which fails on particular elements, such as a binpacking
+/// heuristic given an element heavier than its capacity.
+///
+/// When the heuristic fails, each element is distributed alone to find the elements it fails on.
+/// Those elements become terminal failures in groups of their own, and the rest of the
+/// distribution is distributed again. Errors which do not come from particular elements are
+/// returned.
+///
+/// The heuristic is run once per element when isolating errors, so stateful heuristics such as
+/// [crate::grouping_heuristic::skip::SkipFor] should wrap this heuristic rather than be wrapped by it.
+pub struct CatchError<T>(pub Box<dyn GroupingHeuristic<T>>);
+
+impl<T> CatchError<T> {
+	pub fn new(heuristic: Box<dyn GroupingHeuristic<T>>) -> Self {
+		CatchError(heuristic)
+	}
+
+	pub fn boxed(heuristic: Box<dyn GroupingHeuristic<T>>) -> Box<Self> {
+		Box::new(CatchError(heuristic))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for CatchError<T>
+where
+	T: Clone,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		if let Ok(distribution) = self.0.distribute(distribution.clone()) {
+			return Ok(distribution);
+		}
+
+		// find the elements the heuristic fails on
+		let mut retained = Vec::with_capacity(distribution.len());
+		let mut caught = Vec::new();
+		for outcome in distribution {
+			let mut kept = Vec::new();
+			for element in outcome.into_inner() {
+				let alone = vec![GroupingOutcome::new(vec![element.clone()])];
+				if self.0.distribute(alone).is_ok() {
+					kept.push(element);
+				} else {
+					caught.push(GroupingOutcome::new(vec![element.to_terminal()]));
+				}
+			}
+			if !kept.is_empty() {
+				retained.push(GroupingOutcome::new(kept));
+			}
+		}
+
+		let mut distribution = self.0.distribute(retained)?;
+		distribution.extend(caught);
+		Ok(distribution)
+	}
+
+	fn backoff(&self) -> Option<Duration> {
+		self.0.backoff()
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::binpacking::{BinpackingWeighted, FirstFitBinpacking};
+	use crate::grouping_heuristic::drop_success::DropSuccess;
+	use crate::grouping_heuristic::retry::{MaxAttempts, Retry};
+	use crate::grouping_heuristic::skip::SkipFor;
+	use crate::grouping_heuristic::splitting::{Splitable, Splitting};
+	use crate::grouping_heuristic::{
+		ElementalFailure, ElementalOutcome, GroupOrdering, GroupingHeuristicStack,
+	};
+	use std::sync::Mutex;
+
+	#[test]
+	fn test_catch_error() -> Result<(), anyhow::Error> {
+		let mut heuristic = CatchError::new(FirstFitBinpacking::boxed(10));
+		let distribution = vec![GroupingOutcome::new_apply(vec![4, 12, 3, 11, 5])];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		let should_be = vec![
+			GroupingOutcome::new_apply(vec![4, 3]),
+			GroupingOutcome::new_apply(vec![5]),
+			GroupingOutcome::new(vec![ElementalOutcome::Failure(ElementalFailure::Terminal(12))]),
+			GroupingOutcome::new(vec![ElementalOutcome::Failure(ElementalFailure::Terminal(11))]),
+		];
+		assert_eq!(distribution, should_be);
+
+		Ok(())
+	}
+
+	/// A payload of parts which can be split apart.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	struct Payload(Vec<usize>);
+
+	impl BinpackingWeighted for Payload {
+		fn weight(&self) -> usize {
+			self.0.iter().sum()
+		}
+	}
+
+	impl Splitable for Payload {
+		fn split(self, factor: usize) -> Result<Vec<Self>, anyhow::Error> {
+			Ok(self.0.split(factor)?.into_iter().map(Payload).collect())
+		}
+	}
+
+	#[tokio::test]
+	async fn test_submission_pipeline() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![
+			DropSuccess::boxed(),
+			Retry::boxed(Duration::from_millis(1), Duration::from_millis(4)),
+			SkipFor::boxed(1, Splitting::boxed(2)),
+			CatchError::boxed(FirstFitBinpacking::boxed(10)),
+			MaxAttempts::boxed(3),
+		]);
+		let payloads =
+			vec![Payload(vec![3]), Payload(vec![6, 6]), Payload(vec![4]), Payload(vec![5])];
+
+		// the payload [4] fails once, and the payload [5] always fails
+		let submitted = Mutex::new(Vec::new());
+		let result = stack
+			.run_async_parallel(
+				GroupingOutcome::new_apply_distribution(payloads),
+				|_index, outcome| {
+					let submitted = &submitted;
+					async move {
+						let payloads = outcome.clone().into_original();
+						let mut submitted =
+							submitted.lock().map_err(|_| anyhow::anyhow!("poisoned"))?;
+						let failed = payloads.iter().any(|payload| {
+							payload.0 == [5] || (payload.0 == [4] && !submitted.contains(payload))
+						});
+						submitted.extend(payloads);
+						if failed {
+							Ok(outcome.to_failures_prefer_instrumental())
+						} else {
+							Ok(GroupingOutcome::new_all_success(outcome.0.len()))
+						}
+					}
+				},
+				2,
+				&GroupOrdering::Unordered,
+			)
+			.await?;
+
+		// the oversized payload is never submitted, and the failing payload is submitted 3 times
+		let submitted = submitted.into_inner().map_err(|_| anyhow::anyhow!("poisoned"))?;
+		assert!(!submitted.contains(&Payload(vec![6, 6])));
+		assert_eq!(submitted.iter().filter(|payload| payload.0 == [5]).count(), 3);
+		assert_eq!(submitted.iter().filter(|payload| payload.0 == [4]).count(), 2);
+
+		// only the oversized payload and the failing payload are left as failures
+		let mut failed: Vec<_> = result
+			.into_iter()
+			.flat_map(|outcome| outcome.into_inner())
+			.filter(|outcome| outcome.is_failure())
+			.flat_map(|outcome| GroupingOutcome::new(vec![outcome]).into_original())
+			.flat_map(|payload| payload.0)
+			.collect();
+		failed.sort();
+		assert_eq!(failed, vec![5, 6, 6]);
+
+		Ok(())
+	}
+}
@@ -1,11 +1,18 @@
 pub mod apply;
 pub mod binpacking;
+pub mod catch_error;
 pub mod chunking;
 pub mod drop_success;
+pub mod observer;
+pub mod retry;
 pub mod skip;
 pub mod splitting;
 
+use futures::stream::{self, StreamExt, TryStreamExt};
+use observer::{GroupingObserver, OutcomeCounts, TracingObserver};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// A failure type for a single member of the heuristically formed group.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -259,26 +266,95 @@ where
 		&mut self,
 		distribution: Vec<GroupingOutcome<T>>,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error>;
+
+	/// Gets how long to wait before running the distribution last returned, if at all.
+	fn backoff(&self) -> Option<Duration> {
+		None
+	}
+
+	/// Gets the name the heuristic is observed under, which defaults to the name of its type.
+	fn name(&self) -> &'static str {
+		let name = std::any::type_name::<Self>();
+		let name = name.split('<').next().unwrap_or(name);
+		name.rsplit("::").next().unwrap_or(name)
+	}
+}
+
+/// Gets the key of the chain a group belongs to from its index and outcome.
+pub type ChainKey<T> = Box<dyn Fn(usize, &GroupingOutcome<T>) -> u64 + Send + Sync>;
+
+/// Constrains the order in which groups land when they are run in parallel.
+pub enum GroupOrdering<T> {
+	/// Groups may land in any order.
+	Unordered,
+	/// Each group must land after the group before it.
+	Ordered,
+	/// Groups with the same key must land in order. Groups with different keys may land in any order.
+	Keyed(ChainKey<T>),
+}
+
+impl<T> GroupOrdering<T> {
+	/// Gets the key of the chain of groups which must land in order that the group belongs to.
+	pub fn chain(&self, index: usize, outcome: &GroupingOutcome<T>) -> u64 {
+		match self {
+			GroupOrdering::Unordered => index as u64,
+			GroupOrdering::Ordered => 0,
+			GroupOrdering::Keyed(key) => key(index, outcome),
+		}
+	}
 }
 
-pub struct GroupingHeuristicStack<T>(pub Vec<Box<dyn GroupingHeuristic<T>>>);
+/// Heuristics which are applied in order on each attempt of a run, and the observer of the runs.
+pub struct GroupingHeuristicStack<T> {
+	pub heuristics: Vec<Box<dyn GroupingHeuristic<T>>>,
+	pub observer: Box<dyn GroupingObserver>,
+}
 
 impl<T> GroupingHeuristicStack<T> {
+	/// Creates a stack which is observed with the [TracingObserver].
 	pub fn new(grouping: Vec<Box<dyn GroupingHeuristic<T>>>) -> Self {
-		Self(grouping)
+		Self { heuristics: grouping, observer: Box::new(TracingObserver) }
+	}
+
+	pub fn with_observer(mut self, observer: Box<dyn GroupingObserver>) -> Self {
+		self.observer = observer;
+		self
 	}
 
+	/// Distributes the outcomes as the first attempt of a run.
 	pub fn distribute(
 		&mut self,
 		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		self.distribute_attempt(0, distribution)
+	}
+
+	fn distribute_attempt(
+		&mut self,
+		attempt: usize,
+		distribution: Vec<GroupingOutcome<T>>,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
 		let mut distribution = distribution;
-		for heuristic in &mut self.0 {
+		for heuristic in &mut self.heuristics {
 			distribution = heuristic.distribute(distribution)?;
+			self.observer
+				.on_pass(attempt, heuristic.name(), &OutcomeCounts::of(&distribution));
 		}
 		Ok(distribution)
 	}
 
+	/// Gets the longest backoff any of the heuristics asks for.
+	pub fn backoff(&self) -> Option<Duration> {
+		self.heuristics.iter().filter_map(|heuristic| heuristic.backoff()).max()
+	}
+
+	/// Waits for the backoff the heuristics ask for, if any.
+	async fn wait_for_backoff(&self) {
+		if let Some(backoff) = self.backoff() {
+			futures_timer::Delay::new(backoff).await;
+		}
+	}
+
 	/// Runs the grouping heuristic synchronously.
 	pub async fn run(
 		&mut self,
@@ -286,9 +362,11 @@ impl<T> GroupingHeuristicStack<T> {
 		func: impl Fn(GroupingOutcome<T>) -> Result<GroupingOutcome<T>, anyhow::Error>,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
 		let mut distribution = distribution;
+		let mut attempt = 0;
 		loop {
 			// distribute
-			distribution = self.distribute(distribution)?;
+			distribution = self.distribute_attempt(attempt, distribution)?;
+			self.wait_for_backoff().await;
 
 			// run the function
 			let mut new_distribution = Vec::new();
@@ -296,6 +374,8 @@ impl<T> GroupingHeuristicStack<T> {
 				new_distribution.push(func(outcome)?);
 			}
 
+			self.observer.on_attempt(attempt, &OutcomeCounts::of(&new_distribution));
+
 			// check if we're done
 			if new_distribution.iter().all(|outcome| outcome.all_done()) {
 				return Ok(new_distribution);
@@ -303,6 +383,7 @@ impl<T> GroupingHeuristicStack<T> {
 
 			// update the distribution
 			distribution = new_distribution;
+			attempt += 1;
 		}
 	}
 
@@ -317,11 +398,13 @@ impl<T> GroupingHeuristicStack<T> {
 		F: Fn(usize, GroupingOutcome<T>, M) -> Fut + Send + Sync,
 		Fut: std::future::Future<Output = Result<(GroupingOutcome<T>, M), anyhow::Error>> + Send,
 	{
+		let mut attempt = 0;
 		loop {
 			// distribute
 			// this can be problematic for an async runtime
 			// it would be nice to spawn blocking
-			distribution = self.distribute(distribution)?;
+			distribution = self.distribute_attempt(attempt, distribution)?;
+			self.wait_for_backoff().await;
 
 			// run the function asynchronously
 			let mut new_distribution = Vec::new();
@@ -333,6 +416,8 @@ impl<T> GroupingHeuristicStack<T> {
 				new_distribution.push(new_outcome);
 			}
 
+			self.observer.on_attempt(attempt, &OutcomeCounts::of(&new_distribution));
+
 			// check if we're done
 			if new_distribution.iter().all(|outcome| outcome.all_done()) {
 				return Ok(new_distribution);
@@ -340,6 +425,84 @@ impl<T> GroupingHeuristicStack<T> {
 
 			// update the distribution
 			distribution = new_distribution;
+			attempt += 1;
+		}
+	}
+
+	/// Runs the grouping heuristic asynchronously, running up to `concurrency` groups at once.
+	///
+	/// Groups which must land in order run one after another. Once a group does not succeed, the
+	/// groups which must land after it are not run and fail instrumentally, so that they are
+	/// regrouped in the next iteration. Groups which are already done, such as groups of terminal
+	/// failures, are not run. Outcomes keep the positions of their groups.
+	pub async fn run_async_parallel<F, Fut>(
+		&mut self,
+		mut distribution: Vec<GroupingOutcome<T>>,
+		func: F,
+		concurrency: usize,
+		ordering: &GroupOrdering<T>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error>
+	where
+		F: Fn(usize, GroupingOutcome<T>) -> Fut + Send + Sync,
+		Fut: std::future::Future<Output = Result<GroupingOutcome<T>, anyhow::Error>> + Send,
+	{
+		let func = &func;
+		let mut attempt = 0;
+		loop {
+			// distribute
+			distribution = self.distribute_attempt(attempt, distribution)?;
+			self.wait_for_backoff().await;
+
+			// split the groups into chains which must each land in order
+			let mut chains: BTreeMap<u64, Vec<(usize, GroupingOutcome<T>)>> = BTreeMap::new();
+			for (index, outcome) in distribution.into_iter().enumerate() {
+				chains
+					.entry(ordering.chain(index, &outcome))
+					.or_default()
+					.push((index, outcome));
+			}
+
+			// run the chains concurrently, and the groups of each chain sequentially
+			let chain_results: Vec<Vec<(usize, GroupingOutcome<T>)>> =
+				stream::iter(chains.into_values())
+					.map(|chain| async move {
+						let mut results = Vec::with_capacity(chain.len());
+						let mut blocked = false;
+						for (index, outcome) in chain {
+							if outcome.all_done() {
+								results.push((index, outcome));
+								continue;
+							}
+							if blocked {
+								results.push((index, outcome.to_failures_prefer_instrumental()));
+								continue;
+							}
+							let outcome = func(index, outcome).await?;
+							blocked = !outcome.all_succeeded();
+							results.push((index, outcome));
+						}
+						Ok::<_, anyhow::Error>(results)
+					})
+					.buffer_unordered(concurrency.max(1))
+					.try_collect()
+					.await?;
+
+			// restore the original positions
+			let mut new_distribution: Vec<_> = chain_results.into_iter().flatten().collect();
+			new_distribution.sort_by_key(|(index, _)| *index);
+			let new_distribution: Vec<_> =
+				new_distribution.into_iter().map(|(_, outcome)| outcome).collect();
+
+			self.observer.on_attempt(attempt, &OutcomeCounts::of(&new_distribution));
+
+			// check if we're done
+			if new_distribution.iter().all(|outcome| outcome.all_done()) {
+				return Ok(new_distribution);
+			}
+
+			// update the distribution
+			distribution = new_distribution;
+			attempt += 1;
 		}
 	}
 }
@@ -347,9 +510,13 @@ impl<T> GroupingHeuristicStack<T> {
 #[cfg(test)]
 pub mod test {
 
+	use super::apply::ToApply;
 	use super::chunking::Chunking;
+	use super::drop_success::DropSuccess;
 	use super::*;
-	use std::sync::Arc;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::sync::{Arc, Mutex};
+	use std::time::Duration;
 	use tokio::sync::RwLock;
 
 	#[tokio::test]
@@ -377,4 +544,122 @@ pub mod test {
 
 		Ok(())
 	}
+
+	fn retrying_stack() -> GroupingHeuristicStack<usize> {
+		GroupingHeuristicStack::new(vec![
+			DropSuccess::boxed(),
+			ToApply::boxed(),
+			Chunking::boxed(1),
+		])
+	}
+
+	/// Runs groups of one element each, failing the element `fail_once` on its first attempt.
+	async fn run_failing_once(
+		ordering: GroupOrdering<usize>,
+		fail_once: usize,
+	) -> Result<(Vec<GroupingOutcome<usize>>, Vec<usize>), anyhow::Error> {
+		let attempts = Mutex::new(Vec::new());
+		let result = retrying_stack()
+			.run_async_parallel(
+				GroupingOutcome::new_apply_distribution((0..4).collect()),
+				|_index, outcome| {
+					let attempts = &attempts;
+					async move {
+						let elements = outcome.into_original();
+						let mut attempts =
+							attempts.lock().map_err(|_| anyhow::anyhow!("poisoned"))?;
+						let failed =
+							elements.contains(&fail_once) && !attempts.contains(&fail_once);
+						attempts.extend(elements.iter().copied());
+						if failed {
+							Ok(GroupingOutcome::new_apply(elements))
+						} else {
+							Ok(GroupingOutcome::new_all_success(elements.len()))
+						}
+					}
+				},
+				4,
+				&ordering,
+			)
+			.await?;
+		let attempts = attempts.into_inner().map_err(|_| anyhow::anyhow!("poisoned"))?;
+		Ok((result, attempts))
+	}
+
+	#[tokio::test]
+	async fn test_async_run_parallel_keeps_positions() -> Result<(), anyhow::Error> {
+		let in_flight = AtomicUsize::new(0);
+		let max_in_flight = AtomicUsize::new(0);
+		let mut stack = GroupingHeuristicStack::new(vec![Chunking::boxed(1)]);
+
+		let result = stack
+			.run_async_parallel(
+				GroupingOutcome::new_apply_distribution((0..4).collect()),
+				|index, outcome| {
+					let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+					async move {
+						let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+						max_in_flight.fetch_max(current, Ordering::SeqCst);
+						// later groups finish first
+						tokio::time::sleep(Duration::from_millis(40 - 10 * index as u64)).await;
+						in_flight.fetch_sub(1, Ordering::SeqCst);
+						Ok(outcome.all_to_terminal())
+					}
+				},
+				2,
+				&GroupOrdering::Unordered,
+			)
+			.await?;
+
+		assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+		let elements: Vec<_> =
+			result.into_iter().flat_map(|outcome| outcome.into_original()).collect();
+		assert_eq!(elements, vec![0, 1, 2, 3]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_async_run_parallel_ordered() -> Result<(), anyhow::Error> {
+		let (result, attempts) = run_failing_once(GroupOrdering::Ordered, 1).await?;
+
+		// the groups after the failed group wait for it to be retried
+		assert_eq!(attempts, vec![0, 1, 1, 2, 3]);
+		assert!(result.iter().all(|outcome| outcome.all_succeeded()));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_async_run_parallel_keyed() -> Result<(), anyhow::Error> {
+		let ordering = GroupOrdering::Keyed(Box::new(
+			|_index, outcome: &GroupingOutcome<usize>| match outcome.0.first() {
+				Some(ElementalOutcome::Apply(element)) => (*element % 2) as u64,
+				_ => 0,
+			},
+		));
+		let (result, attempts) = run_failing_once(ordering, 1).await?;
+
+		// only the odd chain waits for the failed group
+		let first_iteration: Vec<_> = attempts.iter().take(3).copied().collect();
+		assert!(first_iteration.contains(&0) && first_iteration.contains(&2));
+		assert!(!first_iteration.contains(&3));
+		assert_eq!(&attempts[3..], &[1, 3]);
+		assert!(result.iter().all(|outcome| outcome.all_succeeded()));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_async_run_parallel_unordered() -> Result<(), anyhow::Error> {
+		let (result, mut attempts) = run_failing_once(GroupOrdering::Unordered, 1).await?;
+
+		// no group waits for the failed group
+		assert_eq!(attempts.pop(), Some(1));
+		attempts.sort();
+		assert_eq!(attempts, vec![0, 1, 2, 3]);
+		assert!(result.iter().all(|outcome| outcome.all_succeeded()));
+
+		Ok(())
+	}
 }
@@ -0,0 +1,190 @@
+use crate::grouping_heuristic::{ElementalFailure, ElementalOutcome, GroupingOutcome};
+use tracing::{debug, info, warn};
+
+/// The number of groups and of each kind of outcome in a distribution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutcomeCounts {
+	pub groups: usize,
+	pub apply: usize,
+	pub success: usize,
+	pub instrumental: usize,
+	pub terminal: usize,
+}
+
+impl OutcomeCounts {
+	/// Counts the outcomes in the distribution.
+	pub fn of<T>(distribution: &[GroupingOutcome<T>]) -> Self {
+		let mut counts = Self { groups: distribution.len(), ..Self::default() };
+		for outcome in distribution.iter().flat_map(|outcome| outcome.0.iter()) {
+			match outcome {
+				ElementalOutcome::Apply(_) => counts.apply += 1,
+				ElementalOutcome::Success => counts.success += 1,
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(_)) => {
+					counts.instrumental += 1;
+				}
+				ElementalOutcome::Failure(ElementalFailure::Terminal(_)) => counts.terminal += 1,
+			}
+		}
+		counts
+	}
+}
+
+/// Observes the runs of a [crate::grouping_heuristic::GroupingHeuristicStack].
+///
+/// Attempts are counted from 0 within each run.
+pub trait GroupingObserver: Send + Sync {
+	/// Called after a heuristic in the stack distributed the outcomes of an attempt.
+	fn on_pass(&self, _attempt: usize, _heuristic: &str, _counts: &OutcomeCounts) {}
+
+	/// Called after the groups of an attempt were run.
+	fn on_attempt(&self, _attempt: usize, _counts: &OutcomeCounts) {}
+}
+
+/// Logs passes at debug level and attempts at info level, or at warn level once outcomes are
+/// terminal failures and so are dropped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingObserver;
+
+impl GroupingObserver for TracingObserver {
+	fn on_pass(&self, attempt: usize, heuristic: &str, counts: &OutcomeCounts) {
+		debug!(
+			attempt,
+			heuristic,
+			groups = counts.groups,
+			apply = counts.apply,
+			success = counts.success,
+			instrumental = counts.instrumental,
+			terminal = counts.terminal,
+			"grouping heuristic pass"
+		);
+	}
+
+	fn on_attempt(&self, attempt: usize, counts: &OutcomeCounts) {
+		if counts.terminal > 0 {
+			warn!(
+				attempt,
+				groups = counts.groups,
+				apply = counts.apply,
+				success = counts.success,
+				instrumental = counts.instrumental,
+				terminal = counts.terminal,
+				"grouping attempt with terminal failures"
+			);
+		} else {
+			info!(
+				attempt,
+				groups = counts.groups,
+				apply = counts.apply,
+				success = counts.success,
+				instrumental = counts.instrumental,
+				terminal = counts.terminal,
+				"grouping attempt"
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::apply::ToApply;
+	use crate::grouping_heuristic::chunking::Chunking;
+	use crate::grouping_heuristic::drop_success::DropSuccess;
+	use crate::grouping_heuristic::GroupingHeuristicStack;
+	use std::sync::atomic::{AtomicBool, Ordering};
+	use std::sync::{Arc, Mutex};
+
+	/// Records the passes and attempts it observes.
+	#[derive(Default)]
+	struct RecordingObserver {
+		passes: Mutex<Vec<(usize, String, OutcomeCounts)>>,
+		attempts: Mutex<Vec<(usize, OutcomeCounts)>>,
+	}
+
+	impl GroupingObserver for Arc<RecordingObserver> {
+		fn on_pass(&self, attempt: usize, heuristic: &str, counts: &OutcomeCounts) {
+			if let Ok(mut passes) = self.passes.lock() {
+				passes.push((attempt, heuristic.to_string(), *counts));
+			}
+		}
+
+		fn on_attempt(&self, attempt: usize, counts: &OutcomeCounts) {
+			if let Ok(mut attempts) = self.attempts.lock() {
+				attempts.push((attempt, *counts));
+			}
+		}
+	}
+
+	#[test]
+	fn test_outcome_counts() -> Result<(), anyhow::Error> {
+		let distribution = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Success,
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(1)),
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(2),
+				ElementalOutcome::Failure(ElementalFailure::Terminal(3)),
+				ElementalOutcome::Apply(4),
+			]),
+		];
+
+		let should_be =
+			OutcomeCounts { groups: 2, apply: 2, success: 1, instrumental: 1, terminal: 1 };
+		assert_eq!(OutcomeCounts::of(&distribution), should_be);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_observes_passes_and_attempts() -> Result<(), anyhow::Error> {
+		let observer = Arc::new(RecordingObserver::default());
+		let mut stack = GroupingHeuristicStack::new(vec![
+			DropSuccess::boxed(),
+			ToApply::boxed(),
+			Chunking::boxed(2),
+		])
+		.with_observer(Box::new(observer.clone()));
+
+		// the first group fails once
+		let failed = AtomicBool::new(false);
+		stack
+			.run(GroupingOutcome::new_apply_distribution(vec![1, 2, 3, 4]), |outcome| {
+				let elements = outcome.into_original();
+				if elements.contains(&1) && !failed.swap(true, Ordering::SeqCst) {
+					Ok(GroupingOutcome::new_apply(elements))
+				} else {
+					Ok(GroupingOutcome::new_all_success(elements.len()))
+				}
+			})
+			.await?;
+
+		let passes = observer.passes.lock().map_err(|_| anyhow::anyhow!("poisoned"))?;
+		let heuristics: Vec<_> = passes
+			.iter()
+			.map(|(attempt, heuristic, _)| (*attempt, heuristic.as_str()))
+			.collect();
+		assert_eq!(
+			heuristics,
+			vec![
+				(0, "DropSuccess"),
+				(0, "ToApply"),
+				(0, "Chunking"),
+				(1, "DropSuccess"),
+				(1, "ToApply"),
+				(1, "Chunking"),
+			]
+		);
+		assert_eq!(passes[2].2, OutcomeCounts { groups: 2, apply: 4, ..Default::default() });
+
+		let attempts = observer.attempts.lock().map_err(|_| anyhow::anyhow!("poisoned"))?;
+		let should_be = vec![
+			(0, OutcomeCounts { groups: 2, apply: 2, success: 2, ..Default::default() }),
+			(1, OutcomeCounts { groups: 1, success: 2, ..Default::default() }),
+		];
+		assert_eq!(*attempts, should_be);
+
+		Ok(())
+	}
+}
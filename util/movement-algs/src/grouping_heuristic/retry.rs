@@ -0,0 +1,165 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+use std::time::Duration;
+
+/// Retries instrumental failures, backing off exponentially between attempts.
+///
+/// Terminal failures are not retried. The first distribution is not a retry, so it is not backed
+/// off. Each retry after it backs off for twice as long as the one before it, up to the maximum.
+pub struct Retry {
+	pub base: Duration,
+	pub max: Duration,
+	pub attempts: u32,
+}
+
+impl Retry {
+	pub fn new(base: Duration, max: Duration) -> Self {
+		Self { base, max, attempts: 0 }
+	}
+
+	pub fn boxed(base: Duration, max: Duration) -> Box<Self> {
+		Box::new(Self::new(base, max))
+	}
+
+	/// Gets the backoff before the given attempt, where the first attempt is 0.
+	pub fn backoff_for(&self, attempt: u32) -> Option<Duration> {
+		let retry = attempt.checked_sub(1)?;
+		let factor = 2u32.saturating_pow(retry);
+		Some(self.base.saturating_mul(factor).min(self.max))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for Retry {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		self.attempts = self.attempts.saturating_add(1);
+
+		// convert instrumental failures to apply, keeping terminal failures
+		let distribution = distribution
+			.into_iter()
+			.map(|outcome| {
+				GroupingOutcome::new(
+					outcome
+						.into_inner()
+						.into_iter()
+						.map(|outcome| match outcome {
+							ElementalOutcome::Failure(ElementalFailure::Instrumental(t)) => {
+								ElementalOutcome::Apply(t)
+							}
+							outcome => outcome,
+						})
+						.collect(),
+				)
+			})
+			.collect();
+
+		Ok(distribution)
+	}
+
+	fn backoff(&self) -> Option<Duration> {
+		self.backoff_for(self.attempts.checked_sub(1)?)
+	}
+}
+
+/// Gives up once the distribution has been attempted the maximum number of times.
+///
+/// Every outcome which has not succeeded by then becomes a terminal failure, so that the stack
+/// stops running.
+pub struct MaxAttempts {
+	pub max: usize,
+	pub attempts: usize,
+}
+
+impl MaxAttempts {
+	pub fn new(max: usize) -> Self {
+		Self { max, attempts: 0 }
+	}
+
+	pub fn boxed(max: usize) -> Box<Self> {
+		Box::new(Self::new(max))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for MaxAttempts {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		self.attempts += 1;
+		if self.attempts <= self.max {
+			return Ok(distribution);
+		}
+
+		Ok(distribution.into_iter().map(|outcome| outcome.all_to_terminal()).collect())
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_retry() -> Result<(), anyhow::Error> {
+		let mut heuristic = Retry::new(Duration::from_millis(10), Duration::from_millis(40));
+		let distribution = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(1)),
+			ElementalOutcome::Failure(ElementalFailure::Terminal(2)),
+			ElementalOutcome::Apply(3),
+		])];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		let should_be = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Apply(1),
+			ElementalOutcome::Failure(ElementalFailure::Terminal(2)),
+			ElementalOutcome::Apply(3),
+		])];
+		assert_eq!(distribution, should_be);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_retry_backoff() -> Result<(), anyhow::Error> {
+		let mut heuristic = Retry::new(Duration::from_millis(10), Duration::from_millis(40));
+		assert_eq!(GroupingHeuristic::<usize>::backoff(&heuristic), None);
+
+		let mut backoffs = Vec::new();
+		for _ in 0..5 {
+			GroupingHeuristic::<usize>::distribute(&mut heuristic, vec![])?;
+			backoffs.push(GroupingHeuristic::<usize>::backoff(&heuristic));
+		}
+
+		let should_be = [None, Some(10), Some(20), Some(40), Some(40)]
+			.map(|backoff| backoff.map(Duration::from_millis));
+		assert_eq!(backoffs, should_be);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_max_attempts() -> Result<(), anyhow::Error> {
+		let mut heuristic = MaxAttempts::new(2);
+		let distribution =
+			vec![GroupingOutcome::new(vec![ElementalOutcome::Success, ElementalOutcome::Apply(1)])];
+
+		let distribution = heuristic.distribute(distribution)?;
+		let distribution = heuristic.distribute(distribution)?;
+		assert!(!distribution.iter().all(|outcome| outcome.all_done()));
+
+		let distribution = heuristic.distribute(distribution)?;
+		let should_be = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Terminal(1)),
+		])];
+		assert_eq!(distribution, should_be);
+
+		Ok(())
+	}
+}
@@ -106,7 +106,15 @@ mod block {
 	use movement_types::Block;
 
 	impl Splitable for Block {
+		/// Splits the transactions of the block into new blocks.
+		///
+		/// A block signed by its proposer is not split, since the signature covers its id,
+		/// which the split blocks would not have.
 		fn split(self, factor: usize) -> Result<Vec<Self>, anyhow::Error> {
+			if self.metadata.proposer_signature().is_some() {
+				return Ok(vec![self]);
+			}
+
 			// unpack the transactions
 			let Block { metadata, transactions, parent, id: _ } = self;
 
@@ -123,4 +131,38 @@ mod block {
 			Ok(blocks)
 		}
 	}
+	#[cfg(test)]
+	pub mod test {
+
+		use super::*;
+		use movement_types::Transaction;
+		use sequencing_util::rotation::{ProposerKey, ProposerSchedule, SequencerSet};
+
+		fn transactions(n: u64) -> Vec<Transaction> {
+			(0..n).map(|i| Transaction::new(vec![i as u8], i)).collect()
+		}
+
+		#[test]
+		fn test_signed_blocks_are_not_split() -> Result<(), anyhow::Error> {
+			let key = ProposerKey::from_bytes([1; 32])?;
+			let set = SequencerSet::try_new(vec![key.id()], 1000, 500)?;
+			let mut schedule = ProposerSchedule::new(set);
+
+			let signed = key.propose(0, vec![0], transactions(4))?;
+			let splits = signed.clone().split(2)?;
+			assert_eq!(splits, vec![signed]);
+			for split in &splits {
+				schedule.try_accept(split, 0)?;
+			}
+
+			// unsigned blocks are split into blocks of their own
+			let unsigned = Block::new(Default::default(), vec![0], transactions(4));
+			let splits = unsigned.clone().split(2)?;
+			assert_eq!(splits.len(), 2);
+			assert!(splits.iter().all(|split| split.id() == split.compute_id()));
+			assert!(splits.iter().all(|split| split.id() != unsigned.id()));
+
+			Ok(())
+		}
+	}
 }
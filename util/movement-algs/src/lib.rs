@@ -1 +1,3 @@
+#[cfg(feature = "batching")]
+pub mod batching;
 pub mod grouping_heuristic;
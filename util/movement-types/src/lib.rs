@@ -17,6 +17,16 @@ impl Id {
 	pub fn genesis_block() -> Self {
 		Self([0; 32])
 	}
+
+	/// Parses an id from its hex representation.
+	pub fn try_from_hex(hex_str: &str) -> Result<Self, anyhow::Error> {
+		let bytes = hex::decode(hex_str.trim_start_matches("0x"))
+			.map_err(|e| anyhow::anyhow!("Invalid hex id {}: {}", hex_str, e))?;
+		let bytes: [u8; 32] = bytes
+			.try_into()
+			.map_err(|_| anyhow::anyhow!("Id must be 32 bytes: {}", hex_str))?;
+		Ok(Self(bytes))
+	}
 }
 
 impl AsRef<[u8]> for Id {
@@ -96,6 +106,27 @@ impl From<Transaction> for AtomicTransactionBundle {
 pub enum BlockMetadata {
 	#[default]
 	BlockMetadata,
+	/// A block proposed by a member of a rotating sequencer set in the given slot,
+	/// with the proposer's signature over the block id and slot.
+	Proposed { proposer: Id, slot: u64, signature: Vec<u8> },
+}
+
+impl BlockMetadata {
+	/// Gets the proposer and slot of the block, if it was proposed by a rotating sequencer.
+	pub fn proposer_slot(&self) -> Option<(&Id, u64)> {
+		match self {
+			BlockMetadata::BlockMetadata => None,
+			BlockMetadata::Proposed { proposer, slot, .. } => Some((proposer, *slot)),
+		}
+	}
+
+	/// Gets the proposer's signature, if the block was proposed by a rotating sequencer.
+	pub fn proposer_signature(&self) -> Option<&[u8]> {
+		match self {
+			BlockMetadata::BlockMetadata => None,
+			BlockMetadata::Proposed { signature, .. } => Some(signature),
+		}
+	}
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -108,20 +139,29 @@ pub struct Block {
 
 impl Block {
 	pub fn new(metadata: BlockMetadata, parent: Vec<u8>, transactions: Vec<Transaction>) -> Self {
+		let id = Self::hash_id(&parent, &transactions);
+		Self { metadata, parent, transactions, id }
+	}
+
+	fn hash_id(parent: &[u8], transactions: &[Transaction]) -> Id {
 		let mut hasher = blake3::Hasher::new();
-		hasher.update(&parent);
-		for transaction in &transactions {
-			hasher.update(&transaction.id().as_ref());
+		hasher.update(parent);
+		for transaction in transactions {
+			hasher.update(transaction.id().as_ref());
 		}
-		let id = Id(hasher.finalize().into());
-
-		Self { metadata, parent, transactions, id }
+		Id(hasher.finalize().into())
 	}
 
 	pub fn id(&self) -> Id {
 		self.id.clone()
 	}
 
+	/// Computes the id of the block from its parent and transactions.
+	/// The stored id of a block read from elsewhere may not match it.
+	pub fn compute_id(&self) -> Id {
+		Self::hash_id(&self.parent, &self.transactions)
+	}
+
 	pub fn test() -> Self {
 		Self::new(BlockMetadata::BlockMetadata, vec![0], vec![Transaction::test()])
 	}